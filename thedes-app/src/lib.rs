@@ -8,6 +8,8 @@ pub mod settings;
 
 pub const SAVE_EXTENSION: &'static str = ".save.thedes";
 
+pub const GRAVEYARD_FILE: &str = "graveyard.thedes";
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
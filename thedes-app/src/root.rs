@@ -2,6 +2,10 @@ use std::{fmt, path::PathBuf};
 
 use thedes_asset::Assets;
 use thedes_audio::{AudioClient, AudioControllerType};
+use thedes_domain::{
+    craft::{RecipeBook, RecipeBookError},
+    dialogue::{DialogueBook, DialogueBookError},
+};
 use thedes_settings::Settings;
 use thedes_tui::{
     core::event::Key,
@@ -9,11 +13,12 @@ use thedes_tui::{
 };
 use thiserror::Error;
 
-use crate::{SAVE_EXTENSION, session, settings};
+use crate::{GRAVEYARD_FILE, SAVE_EXTENSION, session, settings};
 
 pub mod new_game;
 pub mod game_creation;
 pub mod load_game;
+pub mod graveyard;
 
 #[derive(Debug, Error)]
 pub enum InitError {
@@ -71,8 +76,14 @@ pub enum Error {
     ),
     #[error("Failed to load game")]
     LoadGame(#[from] load_game::Error),
+    #[error("Failed to show graveyard")]
+    Graveyard(#[from] graveyard::Error),
     #[error("Failed to load asset")]
     LoadAsset(#[from] thedes_asset::LoadError),
+    #[error("Failed to load crafting recipes")]
+    Recipes(#[from] RecipeBookError),
+    #[error("Failed to load NPC dialogues")]
+    Dialogues(#[from] DialogueBookError),
     #[error("Failed to play audio")]
     AudioPlay(#[from] thedes_audio::ClientError<thedes_audio::PlayNowError>),
     #[error("Failed to run settings component")]
@@ -83,6 +94,7 @@ pub enum Error {
 enum MainMenuItem {
     NewGame,
     LoadGame,
+    Graveyard,
     Settings,
     Quit,
 }
@@ -92,6 +104,7 @@ impl fmt::Display for MainMenuItem {
         f.write_str(match self {
             Self::NewGame => "New Game",
             Self::LoadGame => "Load Game",
+            Self::Graveyard => "Graveyard",
             Self::Settings => "Settings",
             Self::Quit => "Quit",
         })
@@ -110,6 +123,7 @@ pub struct Component {
     new_game: new_game::Component,
     game_creation: game_creation::Component,
     load_game: load_game::Component,
+    graveyard: graveyard::Component,
     session_config: session::Config,
     saves_dir: PathBuf,
     audio_client: AudioClient,
@@ -121,6 +135,7 @@ impl Component {
         let main_menu_items = [
             MainMenuItem::NewGame,
             MainMenuItem::LoadGame,
+            MainMenuItem::Graveyard,
             MainMenuItem::Settings,
             MainMenuItem::Quit,
         ];
@@ -140,6 +155,7 @@ impl Component {
         let game_creation = game_creation::Component::new();
 
         let load_game = load_game::Component::new();
+        let graveyard = graveyard::Component::new();
 
         let audio_client = AudioClient::connect()?;
 
@@ -153,7 +169,9 @@ impl Component {
             new_game,
             game_creation,
             load_game,
-            session_config: session::Config::new(),
+            graveyard,
+            session_config: session::Config::new()
+                .with_graveyard_path(config.saves_dir.join(GRAVEYARD_FILE)),
             saves_dir: config.saves_dir,
             audio_client,
             settings,
@@ -170,6 +188,14 @@ impl Component {
             &assets.sound.main_theme[..],
         )?;
 
+        let recipes = RecipeBook::from_json(&assets.data.recipes)?;
+        let dialogues = DialogueBook::from_json(&assets.data.dialogues)?;
+        self.session_config = self
+            .session_config
+            .clone()
+            .with_recipes(recipes)
+            .with_dialogues(dialogues);
+
         loop {
             self.main_menu.run(app).await?;
 
@@ -180,8 +206,14 @@ impl Component {
                     if self.new_game.is_cancelling() {
                         continue;
                     }
-                    let seed = self.new_game.form().seed;
-                    let config = thedes_gen::Config::new().with_seed(seed);
+                    let form = self.new_game.form();
+                    let config = thedes_gen::Config::new()
+                        .with_seed(form.seed)
+                        .with_game(
+                            thedes_gen::game::Config::new()
+                                .with_simulation_mode(form.mode)
+                                .with_death_mode(form.death_mode),
+                        );
                     if let Some(game) =
                         self.game_creation.run(app, config).await?
                     {
@@ -211,6 +243,10 @@ impl Component {
                         session.run(&mut self.settings, app).await?;
                     }
                 },
+                MainMenuItem::Graveyard => {
+                    let path = self.saves_dir.join(GRAVEYARD_FILE);
+                    self.graveyard.run(path, app).await?;
+                },
                 MainMenuItem::Settings => {
                     self.settings.run(app).await?;
                 },
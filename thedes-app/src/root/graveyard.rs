@@ -0,0 +1,61 @@
+use std::path::Path;
+
+use thedes_domain::grave::{self, Graveyard};
+use thedes_tui::{
+    cancellability::Cancellable,
+    core::App,
+    info::{self, Info},
+    menu::{self, Menu},
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Failed to load graveyard")]
+    Load(#[from] grave::LoadError),
+    #[error("Failed to initialize menu")]
+    InitMenu(#[source] menu::Error),
+    #[error("Failed to run menu")]
+    RunMenu(#[source] menu::Error),
+    #[error("Failed to show empty graveyard info")]
+    EmptyInfo(#[source] info::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct Component {
+    empty_info: Info,
+}
+
+impl Default for Component {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Component {
+    pub fn new() -> Self {
+        Self { empty_info: Info::new("Graveyard", "No one rests here yet.") }
+    }
+
+    pub async fn run(
+        &mut self,
+        graveyard_path: impl AsRef<Path>,
+        app: &mut App,
+    ) -> Result<(), Error> {
+        let graveyard = Graveyard::load(graveyard_path.as_ref()).await?;
+        if graveyard.is_empty() {
+            self.empty_info.run(app).await.map_err(Error::EmptyInfo)?;
+            return Ok(());
+        }
+
+        let mut menu = Menu::from_cancellation(
+            "✝✝ Graveyard ✝✝",
+            graveyard.graves().iter().rev().cloned(),
+            Cancellable::new(false),
+        )
+        .map_err(Error::InitMenu)?;
+
+        menu.run(app).await.map_err(Error::RunMenu)?;
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use std::fmt;
 
+use thedes_domain::game::{DeathMode, SimulationMode};
 use thedes_tui::{
     cancellability::Cancellable,
     core::App,
@@ -21,6 +22,10 @@ pub enum InitError {
         #[from]
         menu::Error,
     ),
+    #[error("Failed to initialize simulation mode menu")]
+    ModeMenu(#[source] menu::Error),
+    #[error("Failed to initialize death mode menu")]
+    DeathModeMenu(#[source] menu::Error),
     #[error("Failed to create name input")]
     Name(#[source] input::Error),
     #[error("Failed to create seed input")]
@@ -39,16 +44,34 @@ pub enum Error {
     RunName(#[source] input::Error),
     #[error("Failed to run seed input")]
     RunSeed(#[source] input::Error),
+    #[error("Failed to run simulation mode menu")]
+    RunMode(#[source] menu::Error),
+    #[error("Failed to run death mode menu")]
+    RunDeathMode(#[source] menu::Error),
     #[error("Failed to display information regarding name input")]
     EmptyNameInfo(#[source] info::Error),
     #[error("Failed to display information regarding seed input")]
     EmptySeedInfo(#[source] info::Error),
 }
 
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Form {
     pub name: String,
     pub seed: Seed,
+    pub mode: SimulationMode,
+    pub death_mode: DeathMode,
+}
+
+impl Default for Form {
+    /// New games get the same death mode as a game not stating one.
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            seed: Seed::default(),
+            mode: SimulationMode::default(),
+            death_mode: DeathMode::default(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -56,6 +79,8 @@ enum NewGameMenuItem {
     Create,
     SetName,
     SetSeed,
+    SetMode,
+    SetDeathMode,
 }
 
 impl fmt::Display for NewGameMenuItem {
@@ -64,6 +89,8 @@ impl fmt::Display for NewGameMenuItem {
             Self::Create => "Create",
             Self::SetName => "Set Name",
             Self::SetSeed => "Set Seed",
+            Self::SetMode => "Set Simulation Mode",
+            Self::SetDeathMode => "Set Death Mode",
         })
     }
 }
@@ -76,6 +103,8 @@ pub struct Component {
     menu: Menu<NewGameMenuItem, Cancellable>,
     name_input: Input<fn(char) -> bool, Cancellable>,
     seed_input: Input<fn(char) -> bool, Cancellable>,
+    mode_menu: Menu<SimulationMode, Cancellable>,
+    death_mode_menu: Menu<DeathMode, Cancellable>,
     empty_name_info: Info,
     empty_seed_info: Info,
 }
@@ -88,6 +117,8 @@ impl Component {
                 NewGameMenuItem::Create,
                 NewGameMenuItem::SetName,
                 NewGameMenuItem::SetSeed,
+                NewGameMenuItem::SetMode,
+                NewGameMenuItem::SetDeathMode,
             ],
             Cancellable::new(false),
         )?;
@@ -116,6 +147,20 @@ impl Component {
         );
         let seed_input = result.map_err(InitError::Seed)?;
 
+        let mode_menu = Menu::from_cancellation(
+            "Simulation Mode",
+            SimulationMode::ALL,
+            Cancellable::new(false),
+        )
+        .map_err(InitError::ModeMenu)?;
+
+        let death_mode_menu = Menu::from_cancellation(
+            "Death Mode",
+            DeathMode::ALL,
+            Cancellable::new(false),
+        )
+        .map_err(InitError::DeathModeMenu)?;
+
         let empty_name_info = Info::new("Error!", "Game name cannot be empty");
         let empty_seed_info = Info::new("Error!", "Game seed cannot be empty");
 
@@ -124,6 +169,8 @@ impl Component {
             form,
             name_input,
             seed_input,
+            mode_menu,
+            death_mode_menu,
             empty_name_info,
             empty_seed_info,
         })
@@ -187,6 +234,12 @@ impl Component {
                     Some(NewGameMenuItem::SetSeed) => {
                         self.read_seed(app).await?;
                     },
+                    Some(NewGameMenuItem::SetMode) => {
+                        self.read_mode(app).await?;
+                    },
+                    Some(NewGameMenuItem::SetDeathMode) => {
+                        self.read_death_mode(app).await?;
+                    },
                     None => break,
                 }
             }
@@ -216,6 +269,36 @@ impl Component {
         Ok(())
     }
 
+    async fn read_mode(&mut self, app: &mut App) -> Result<(), Error> {
+        let selected = SimulationMode::ALL
+            .iter()
+            .position(|mode| *mode == self.form.mode)
+            .unwrap_or_default();
+        self.mode_menu.set_selected(selected).map_err(Error::RunMode)?;
+        self.mode_menu.set_cancelling(false);
+        self.mode_menu.run(app).await.map_err(Error::RunMode)?;
+        if let Some(mode) = self.mode_menu.output() {
+            self.form.mode = *mode;
+        }
+        Ok(())
+    }
+
+    async fn read_death_mode(&mut self, app: &mut App) -> Result<(), Error> {
+        let selected = DeathMode::ALL
+            .iter()
+            .position(|mode| *mode == self.form.death_mode)
+            .unwrap_or_default();
+        self.death_mode_menu
+            .set_selected(selected)
+            .map_err(Error::RunDeathMode)?;
+        self.death_mode_menu.set_cancelling(false);
+        self.death_mode_menu.run(app).await.map_err(Error::RunDeathMode)?;
+        if let Some(mode) = self.death_mode_menu.output() {
+            self.form.death_mode = *mode;
+        }
+        Ok(())
+    }
+
     async fn read_seed(&mut self, app: &mut App) -> Result<(), Error> {
         loop {
             self.seed_input.run(app).await.map_err(Error::RunSeed)?;
@@ -243,6 +326,7 @@ impl Component {
 mod test {
     use std::time::Duration;
 
+    use thedes_domain::game::{DeathMode, SimulationMode};
     use thedes_tui::core::{
         App,
         event::Key,
@@ -319,7 +403,12 @@ mod test {
             .unwrap();
         assert_eq!(
             output,
-            Some(super::Form { name: "w0".to_owned(), seed: 0 })
+            Some(super::Form {
+                name: "w0".to_owned(),
+                seed: 0,
+                mode: SimulationMode::RealTime,
+                death_mode: DeathMode::Respawn,
+            })
         );
     }
 
@@ -393,7 +482,12 @@ mod test {
             .unwrap();
         assert_eq!(
             output,
-            Some(super::Form { name: "w0".to_owned(), seed: 0x5a9 })
+            Some(super::Form {
+                name: "w0".to_owned(),
+                seed: 0x5a9,
+                mode: SimulationMode::RealTime,
+                death_mode: DeathMode::Respawn,
+            })
         );
     }
 
@@ -434,7 +528,117 @@ mod test {
             .unwrap();
         assert_eq!(
             output,
-            Some(super::Form { name: "w0".to_owned(), seed: 0 })
+            Some(super::Form {
+                name: "w0".to_owned(),
+                seed: 0,
+                mode: SimulationMode::RealTime,
+                death_mode: DeathMode::Respawn,
+            })
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn confirm_turn_based_mode() {
+        let device_mock = RuntimeDeviceMock::new(CoordPair { y: 24, x: 80 });
+        let device = device_mock.open();
+        let config = Config::new()
+            .with_screen(
+                screen::Config::new()
+                    .with_canvas_size(CoordPair { y: 22, x: 78 }),
+            )
+            .with_device(device);
+
+        device_mock.input().publish_ok([
+            Key::Char('w'),
+            Key::Char('0'),
+            Key::Enter,
+        ]);
+
+        let runtime_future = task::spawn(config.run(tui_main));
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        device_mock.input().publish_ok([
+            Key::Down,
+            Key::Down,
+            Key::Down,
+            Key::Enter,
+        ]);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        device_mock.input().publish_ok([Key::Down, Key::Enter]);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        device_mock.input().publish_ok([Key::Up, Key::Up, Key::Up, Key::Enter]);
+
+        let output = timeout(Duration::from_millis(200), runtime_future)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            output,
+            Some(super::Form {
+                name: "w0".to_owned(),
+                seed: 0,
+                mode: SimulationMode::TurnBased,
+                death_mode: DeathMode::Respawn,
+            })
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn confirm_permadeath_death_mode() {
+        let device_mock = RuntimeDeviceMock::new(CoordPair { y: 24, x: 80 });
+        let device = device_mock.open();
+        let config = Config::new()
+            .with_screen(
+                screen::Config::new()
+                    .with_canvas_size(CoordPair { y: 22, x: 78 }),
+            )
+            .with_device(device);
+
+        device_mock.input().publish_ok([
+            Key::Char('w'),
+            Key::Char('0'),
+            Key::Enter,
+        ]);
+
+        let runtime_future = task::spawn(config.run(tui_main));
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        device_mock.input().publish_ok([
+            Key::Down,
+            Key::Down,
+            Key::Down,
+            Key::Down,
+            Key::Enter,
+        ]);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        device_mock.input().publish_ok([Key::Up, Key::Up, Key::Enter]);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        device_mock.input().publish_ok([
+            Key::Up,
+            Key::Up,
+            Key::Up,
+            Key::Up,
+            Key::Enter,
+        ]);
+
+        let output = timeout(Duration::from_millis(200), runtime_future)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            output,
+            Some(super::Form {
+                name: "w0".to_owned(),
+                seed: 0,
+                mode: SimulationMode::RealTime,
+                death_mode: DeathMode::Permadeath,
+            })
         );
     }
 }
@@ -1,10 +1,32 @@
-use std::{fmt, path::PathBuf};
+use std::{
+    fmt,
+    fs,
+    io,
+    path::{Path, PathBuf},
+};
 
 use num::rational::Ratio;
-use thedes_domain::game::{Game, LoadError, SaveError};
+use thedes_domain::{
+    chunk::{ChunkStore, ChunkStoreError},
+    craft::{Recipe, RecipeBook},
+    dialogue::{Dialogue, DialogueBook},
+    game::{DeathMode, Game, LoadError, SimulationMode},
+    grave::{self, Grave, Graveyard},
+    map,
+    npc,
+    quest::{QuestContext, QuestEntry, QuestStatus},
+};
 use thedes_geometry::orientation::Direction;
-use thedes_session::{EventError, Session};
+use thedes_session::{
+    CraftError,
+    DropLootError,
+    EventError,
+    SaveError,
+    Session,
+    StreamChunksError,
+};
 use thedes_tui::{
+    cancellability::Cancellable,
     core::{
         App,
         event::{Event, Key, KeyEvent},
@@ -16,7 +38,7 @@ use thedes_tui::{
 };
 use thiserror::Error;
 
-use crate::settings;
+use crate::{GRAVEYARD_FILE, SAVE_EXTENSION, settings};
 
 pub mod dev;
 
@@ -41,6 +63,23 @@ pub fn default_key_bindings() -> KeyBindingMap {
             .with(head_key, ControlCommand::MovePlayerHead(direction));
     }
 
+    map = map.with(Key::Char(' '), ControlCommand::Attack);
+
+    map = map.with(Key::Char('g'), ControlCommand::PickUp);
+
+    map = map.with(Key::Char('e'), ControlCommand::Gather);
+
+    map = map
+        .with(Key::Char('x'), ControlCommand::Dig)
+        .with(Key::Char('b'), ControlCommand::Build)
+        .with(Key::Char('v'), ControlCommand::SelectNextMaterial);
+
+    map = map.with(Key::Char('k'), ControlCommand::CloseDoor);
+
+    map = map.with(Key::Char('c'), Command::Craft);
+
+    map = map.with(Key::Char('t'), Command::Talk);
+
     map = map.with(Key::Char('o'), Command::Script);
 
     map
@@ -56,8 +95,22 @@ pub enum InitError {
     ),
     #[error("Pause menu is inconsistent, quit not found")]
     MissingPauseQuit,
+    #[error("Failed to build crafting menu")]
+    CraftMenu(#[source] menu::Error),
     #[error("Failed to load game")]
     Load(#[from] LoadError),
+    #[error("Failed to discard chunks unloaded after last save")]
+    DiscardChunks(
+        #[from]
+        #[source]
+        ChunkStoreError,
+    ),
+    #[error("Failed to clear stale chunks at {path}")]
+    ClearChunks {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
 }
 
 #[derive(Debug, Error)]
@@ -88,6 +141,12 @@ pub enum Error {
         #[from]
         thedes_session::QuickStepError,
     ),
+    #[error("Failed to make player attack")]
+    Attack(
+        #[source]
+        #[from]
+        thedes_session::AttackError,
+    ),
     #[error("Pause menu failed to run")]
     PauseMenu(
         #[source]
@@ -116,8 +175,95 @@ pub enum Error {
     Save(#[from] SaveError),
     #[error("Failed to show death info")]
     DeathInfo(#[source] info::Error),
+    #[error("Failed to respawn player")]
+    Respawn(
+        #[source]
+        #[from]
+        thedes_session::RespawnError,
+    ),
+    #[error("Failed to load graveyard")]
+    LoadGraveyard(#[from] grave::LoadError),
+    #[error("Failed to save graveyard")]
+    SaveGraveyard(#[from] grave::SaveError),
+    #[error("Failed to delete dead game at {path}")]
+    DeleteSave {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
     #[error("Failed to run settings")]
     Settings(#[from] settings::Error),
+    #[error("Failed to get weather around the player")]
+    PlayerWeather(#[source] map::AccessError),
+    #[error("Failed to stream map chunks")]
+    StreamChunks(
+        #[from]
+        #[source]
+        StreamChunksError,
+    ),
+    #[error("Failed to drop loot")]
+    DropLoot(
+        #[from]
+        #[source]
+        DropLootError,
+    ),
+    #[error("Failed to pick up items")]
+    PickUp(
+        #[source]
+        #[from]
+        thedes_session::PickUpError,
+    ),
+    #[error("Failed to gather materials")]
+    Gather(
+        #[source]
+        #[from]
+        thedes_session::GatherError,
+    ),
+    #[error("Failed to dig")]
+    Dig(
+        #[source]
+        #[from]
+        thedes_session::DigError,
+    ),
+    #[error("Failed to build")]
+    Build(
+        #[source]
+        #[from]
+        thedes_session::BuildError,
+    ),
+    #[error("Failed to close door")]
+    CloseDoor(
+        #[source]
+        #[from]
+        thedes_session::CloseDoorError,
+    ),
+    #[error("Crafting menu failed to run")]
+    CraftMenu(#[source] menu::Error),
+    #[error("Failed to show crafting info")]
+    CraftInfo(#[source] info::Error),
+    #[error("Quest log menu failed to run")]
+    QuestMenu(#[source] menu::Error),
+    #[error("Failed to show quest details")]
+    QuestInfo(#[source] info::Error),
+    #[error("Failed to talk to NPC")]
+    Talk(
+        #[source]
+        #[from]
+        thedes_session::TalkError,
+    ),
+    #[error("NPC talked to is not registered")]
+    Npc(#[source] npc::InvalidId),
+    #[error("Dialogue menu failed to run")]
+    DialogueMenu(#[source] menu::Error),
+    #[error("Failed to show dialogue")]
+    DialogueInfo(#[source] info::Error),
+}
+
+/// Chunks unloaded from memory are stored next to the save file.
+fn chunk_dir(save_path: &Path) -> PathBuf {
+    let mut dir = save_path.as_os_str().to_owned();
+    dir.push(".chunks");
+    dir.into()
 }
 
 pub type KeyBindingMap = thedes_tui::key_bindings::KeyBindingMap<Command>;
@@ -126,6 +272,8 @@ pub type KeyBindingMap = thedes_tui::key_bindings::KeyBindingMap<Command>;
 pub enum Command {
     Pause,
     Script,
+    Craft,
+    Talk,
     Control(ControlCommand),
 }
 
@@ -139,11 +287,19 @@ impl From<ControlCommand> for Command {
 pub enum ControlCommand {
     MovePlayerHead(Direction),
     MovePlayerPointer(Direction),
+    Attack,
+    PickUp,
+    Gather,
+    Dig,
+    Build,
+    SelectNextMaterial,
+    CloseDoor,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum PauseMenuItem {
     Continue,
+    Quests,
     Save,
     Settings,
     Quit,
@@ -153,6 +309,7 @@ impl fmt::Display for PauseMenuItem {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str(match self {
             Self::Continue => "Continue Game",
+            Self::Quests => "Quest Log",
             Self::Save => "Save Game",
             Self::Settings => "Settings",
             Self::Quit => "Quit Game",
@@ -160,11 +317,45 @@ impl fmt::Display for PauseMenuItem {
     }
 }
 
+/// A quest as listed in the quest log.
+#[derive(Debug, Clone)]
+struct QuestItem {
+    name: String,
+    summary: String,
+    details: String,
+}
+
+impl QuestItem {
+    fn new(entry: &QuestEntry, context: QuestContext) -> Self {
+        let quest = entry.quest();
+        let (done, goal) = entry.progress(context);
+        let summary = match entry.status() {
+            QuestStatus::Active => {
+                format!("[ ] {} ({done}/{goal})", quest.name())
+            },
+            QuestStatus::Completed(_) => format!("[✓] {}", quest.name()),
+        };
+        let details = format!(
+            "Objective: {}. Progress: {done}/{goal}. Reward: {}.",
+            quest.objective(),
+            quest.reward(),
+        );
+        Self { name: quest.name().to_owned(), summary, details }
+    }
+}
+
+impl fmt::Display for QuestItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.summary)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Config {
     control_events_per_tick: Ratio<u32>,
     inner: thedes_session::Config,
     key_bindings: KeyBindingMap,
+    graveyard_path: PathBuf,
 }
 
 impl Default for Config {
@@ -179,6 +370,7 @@ impl Config {
             control_events_per_tick: Ratio::new(1, 8),
             inner: thedes_session::Config::new(),
             key_bindings: default_key_bindings(),
+            graveyard_path: PathBuf::from(GRAVEYARD_FILE),
         }
     }
 
@@ -193,17 +385,58 @@ impl Config {
         Self { key_bindings, ..self }
     }
 
+    pub fn with_graveyard_path(self, path: impl Into<PathBuf>) -> Self {
+        Self { graveyard_path: path.into(), ..self }
+    }
+
     pub fn with_inner(self, config: thedes_session::Config) -> Self {
         Self { inner: config, ..self }
     }
 
+    pub fn with_recipes(self, recipes: RecipeBook) -> Self {
+        Self { inner: self.inner.with_recipes(recipes), ..self }
+    }
+
+    pub fn with_dialogues(self, dialogues: DialogueBook) -> Self {
+        Self { inner: self.inner.with_dialogues(dialogues), ..self }
+    }
+
+    /// Finishes a session for a new game, discarding chunks left over by any
+    /// previous game saved under the same path.
     pub fn finish(
         self,
         save_path: impl Into<PathBuf>,
         game: Game,
+    ) -> Result<Component, InitError> {
+        let save_path = save_path.into();
+        let chunk_dir = chunk_dir(&save_path);
+        match fs::remove_dir_all(&chunk_dir) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                Err(InitError::ClearChunks { path: chunk_dir, source: error })?
+            },
+            _ => (),
+        }
+        self.finish_with(save_path, game)
+    }
+
+    pub async fn finish_loading(
+        self,
+        save_path: impl Into<PathBuf>,
+    ) -> Result<Component, InitError> {
+        let save_path = save_path.into();
+        let game = Game::load(&save_path).await?;
+        ChunkStore::new(chunk_dir(&save_path)).discard()?;
+        self.finish_with(save_path, game)
+    }
+
+    fn finish_with(
+        self,
+        save_path: PathBuf,
+        game: Game,
     ) -> Result<Component, InitError> {
         let pause_menu_items = [
             PauseMenuItem::Continue,
+            PauseMenuItem::Quests,
             PauseMenuItem::Save,
             PauseMenuItem::Settings,
             PauseMenuItem::Quit,
@@ -223,26 +456,47 @@ impl Config {
         let death_info =
             Info::new("You died!", "You cannot continue to this game.");
 
+        let respawn_info = Info::new(
+            "You died!",
+            "You wake up where you started, your belongings left where you \
+             fell.",
+        );
+
+        let inner = self
+            .inner
+            .with_chunk_store(ChunkStore::new(chunk_dir(&save_path)))
+            .finish(game);
+
+        let recipes = inner.recipes().recipes();
+        let craft_menu = if recipes.is_empty() {
+            None
+        } else {
+            let menu = Menu::from_cancellation(
+                "## Crafting ##",
+                recipes.iter().cloned(),
+                Cancellable::new(false),
+            )
+            .map_err(InitError::CraftMenu)?;
+            Some(menu)
+        };
+
+        let craft_info = Info::new("Cannot craft", "");
+
         Ok(Component {
-            inner: self.inner.finish(game),
-            save_path: save_path.into(),
+            inner,
+            save_path,
             control_events_per_tick: self.control_events_per_tick,
             controls_left: Ratio::new(0, 1),
             key_bindings: self.key_bindings,
             pause_menu,
             dev_mode: dev::Component::new(),
             death_info,
+            respawn_info,
+            graveyard_path: self.graveyard_path,
+            craft_menu,
+            craft_info,
         })
     }
-
-    pub async fn finish_loading(
-        self,
-        save_path: impl Into<PathBuf>,
-    ) -> Result<Component, InitError> {
-        let save_path = save_path.into();
-        let game = Game::load(&save_path).await?;
-        self.finish(save_path, game)
-    }
 }
 
 #[derive(Debug, Clone)]
@@ -255,6 +509,10 @@ pub struct Component {
     pause_menu: Menu<PauseMenuItem>,
     dev_mode: dev::Component,
     death_info: Info,
+    respawn_info: Info,
+    graveyard_path: PathBuf,
+    craft_menu: Option<Menu<Recipe, Cancellable>>,
+    craft_info: Info,
 }
 
 impl Component {
@@ -264,16 +522,28 @@ impl Component {
         app: &mut App,
     ) -> Result<(), Error> {
         while self.handle_input(settings, app).await? {
+            let movement_percent = self
+                .inner
+                .game()
+                .player_movement_percent()
+                .map_err(Error::PlayerWeather)?;
+            let control_events_per_tick = self.control_events_per_tick
+                * Ratio::new(movement_percent, 100);
             let more_controls_left =
-                self.controls_left + self.control_events_per_tick;
+                self.controls_left + control_events_per_tick;
             if more_controls_left < self.control_events_per_tick.ceil() * 2 {
                 self.controls_left = more_controls_left;
             }
-            if self.inner.game().player().hp().value() == 0 {
-                self.death_info.run(app).await.map_err(Error::DeathInfo)?;
+            if self.inner.game().player().hp().value() == 0
+                && !self.handle_death(app).await?
+            {
                 break;
             }
-            self.inner.tick_event()?;
+            if self.inner.game().simulation_mode() == SimulationMode::RealTime {
+                self.inner.tick_event()?;
+            }
+            self.inner.drop_loot()?;
+            self.inner.stream_chunks()?;
             self.inner.render(app)?;
             app.canvas.flush()?;
 
@@ -285,6 +555,50 @@ impl Component {
         Ok(())
     }
 
+    /// Returns whether the game goes on.
+    async fn handle_death(&mut self, app: &mut App) -> Result<bool, Error> {
+        let mode = self.inner.game().death_mode();
+        if !mode.is_permanent() {
+            self.inner.respawn()?;
+            self.respawn_info.run(app).await.map_err(Error::DeathInfo)?;
+            return Ok(true);
+        }
+        if mode == DeathMode::Graveyard {
+            let mut graveyard = Graveyard::load(&self.graveyard_path).await?;
+            graveyard.bury(Grave::new(self.save_name(), self.inner.game()));
+            graveyard.save(&self.graveyard_path).await?;
+        }
+        self.delete_save()?;
+        self.death_info.run(app).await.map_err(Error::DeathInfo)?;
+        Ok(false)
+    }
+
+    fn save_name(&self) -> String {
+        let file_name =
+            self.save_path.file_name().unwrap_or_default().to_string_lossy();
+        file_name.strip_suffix(SAVE_EXTENSION).unwrap_or(&file_name).to_owned()
+    }
+
+    /// A dead game cannot be loaded again.
+    fn delete_save(&self) -> Result<(), Error> {
+        let chunk_dir = chunk_dir(&self.save_path);
+        for (path, result) in [
+            (&self.save_path, fs::remove_file(&self.save_path)),
+            (&chunk_dir, fs::remove_dir_all(&chunk_dir)),
+        ] {
+            match result {
+                Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                    Err(Error::DeleteSave {
+                        path: path.clone(),
+                        source: error,
+                    })?
+                },
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
     async fn handle_input(
         &mut self,
         settings: &mut settings::Component,
@@ -318,8 +632,11 @@ impl Component {
                     self.pause_menu.run(app).await?;
                     match self.pause_menu.output() {
                         PauseMenuItem::Continue => (),
+                        PauseMenuItem::Quests => {
+                            self.show_quest_log(app).await?
+                        },
                         PauseMenuItem::Save => {
-                            self.inner.game().save(&self.save_path).await?
+                            self.inner.save(&self.save_path).await?
                         },
                         PauseMenuItem::Settings => settings.run(app).await?,
                         PauseMenuItem::Quit => return Ok(false),
@@ -330,6 +647,8 @@ impl Component {
                         .run(app, &mut self.inner.dev_command_context())
                         .await?;
                 },
+                Command::Craft => self.craft(app).await?,
+                Command::Talk => self.talk(app).await?,
                 Command::Control(command) => {
                     if self.controls_left >= Ratio::ONE {
                         self.controls_left -= Ratio::ONE;
@@ -342,6 +661,98 @@ impl Component {
         Ok(true)
     }
 
+    async fn craft(&mut self, app: &mut App) -> Result<(), Error> {
+        let Some(menu) = &mut self.craft_menu else {
+            return Ok(());
+        };
+        menu.set_cancelling(false);
+        menu.run(app).await.map_err(Error::CraftMenu)?;
+        let Some(recipe) = menu.output().cloned() else {
+            return Ok(());
+        };
+        if let Err(CraftError::Craft(error)) = self.inner.craft(&recipe) {
+            self.craft_info.set_message(&error.to_string());
+            self.craft_info.run(app).await.map_err(Error::CraftInfo)?;
+        }
+        Ok(())
+    }
+
+    /// Lists accepted quests, showing the details of the selected one until
+    /// the log is closed.
+    async fn show_quest_log(&mut self, app: &mut App) -> Result<(), Error> {
+        let game = self.inner.game();
+        let context = game.quest_context();
+        let items: Vec<_> = game
+            .quest_log()
+            .entries()
+            .iter()
+            .map(|entry| QuestItem::new(entry, context))
+            .collect();
+        if items.is_empty() {
+            let mut info = Info::new("Quest Log", "No quests yet.");
+            info.run(app).await.map_err(Error::QuestInfo)?;
+            return Ok(());
+        }
+        let mut menu = Menu::from_cancellation(
+            "## Quest Log ##",
+            items,
+            Cancellable::new(false),
+        )
+        .map_err(Error::QuestMenu)?;
+        loop {
+            menu.set_cancelling(false);
+            menu.run(app).await.map_err(Error::QuestMenu)?;
+            let Some(item) = menu.output() else {
+                break;
+            };
+            let mut info = Info::new(&item.name, &item.details);
+            info.run(app).await.map_err(Error::QuestInfo)?;
+        }
+        Ok(())
+    }
+
+    /// Walks the dialogue tree of the NPC in front of the player, until it
+    /// ends or the player walks away from it.
+    async fn talk(&mut self, app: &mut App) -> Result<(), Error> {
+        let Some(id) = self.inner.talk()? else {
+            return Ok(());
+        };
+        let npc = self
+            .inner
+            .game()
+            .npc_registry()
+            .get_by_id(id)
+            .map_err(Error::Npc)?;
+        let name = npc.name().to_owned();
+        let Some(dialogue) =
+            self.inner.dialogues().get(npc.dialogue()).cloned()
+        else {
+            let mut info = Info::new(&name, "...");
+            info.run(app).await.map_err(Error::DialogueInfo)?;
+            return Ok(());
+        };
+        let mut index = Dialogue::ROOT;
+        while let Some(node) = dialogue.node(index) {
+            if node.choices.is_empty() {
+                let mut info = Info::new(&name, &node.text);
+                info.run(app).await.map_err(Error::DialogueInfo)?;
+                break;
+            }
+            let mut menu = Menu::from_cancellation(
+                format!("{name}: {}", node.text),
+                node.choices.iter().cloned(),
+                Cancellable::new(false),
+            )
+            .map_err(Error::DialogueMenu)?;
+            menu.run(app).await.map_err(Error::DialogueMenu)?;
+            match menu.output().and_then(|choice| choice.next) {
+                Some(next) => index = next,
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
     fn handle_control(&mut self, command: ControlCommand) -> Result<(), Error> {
         match command {
             ControlCommand::MovePlayerHead(direction) => {
@@ -350,6 +761,30 @@ impl Component {
             ControlCommand::MovePlayerPointer(direction) => {
                 self.inner.move_around(direction)?;
             },
+            ControlCommand::Attack => {
+                self.inner.attack()?;
+            },
+            ControlCommand::PickUp => {
+                self.inner.pick_up()?;
+            },
+            ControlCommand::Gather => {
+                self.inner.gather()?;
+            },
+            ControlCommand::Dig => {
+                self.inner.dig()?;
+            },
+            ControlCommand::Build => {
+                self.inner.build()?;
+            },
+            ControlCommand::SelectNextMaterial => {
+                self.inner.select_next_material();
+            },
+            ControlCommand::CloseDoor => {
+                self.inner.close_door()?;
+            },
+        }
+        if self.inner.game().simulation_mode() == SimulationMode::TurnBased {
+            self.inner.advance_turn()?;
         }
         Ok(())
     }
@@ -83,10 +83,27 @@ impl SoundAssets {
     }
 }
 
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct DataAssets {
+    pub recipes: Asset,
+    pub dialogues: Asset,
+}
+
+impl DataAssets {
+    async fn load() -> Result<Self, LoadError> {
+        Ok(Self {
+            recipes: load!("../assets/data/recipes.json").await?,
+            dialogues: load!("../assets/data/dialogues.json").await?,
+        })
+    }
+}
+
 #[derive(Debug)]
 #[non_exhaustive]
 pub struct Assets {
     pub sound: SoundAssets,
+    pub data: DataAssets,
 }
 
 impl Assets {
@@ -96,6 +113,9 @@ impl Assets {
     }
 
     async fn load() -> Result<Self, LoadError> {
-        Ok(Self { sound: SoundAssets::load().await? })
+        Ok(Self {
+            sound: SoundAssets::load().await?,
+            data: DataAssets::load().await?,
+        })
     }
 }
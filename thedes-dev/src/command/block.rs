@@ -1,5 +1,11 @@
 use serde::{Deserialize, Serialize};
-use thedes_domain::{geometry::Coord, stat::StatValue};
+use thedes_domain::{
+    effect::{EffectKind, Intensity, StatusEffect},
+    geometry::Coord,
+    item::{ItemCount, ItemKind, ItemStack},
+    stat::StatValue,
+    weather::{Weather, WeatherRegion},
+};
 
 use crate::CommandContext;
 
@@ -13,6 +19,12 @@ pub struct CommandBlock {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     heal_player: Option<HealPlayerCommand>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    give_player_item: Option<GivePlayerItemCommand>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    set_weather: Option<SetWeatherCommand>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    apply_player_effect: Option<ApplyPlayerEffectCommand>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     set_monster_follow_limit_min: Option<SetMonsterFollowLimitMin>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     set_monster_follow_limit_max: Option<SetMonsterFollowLimitMax>,
@@ -24,6 +36,8 @@ pub struct CommandBlock {
     set_monster_follow_period_max: Option<SetMonsterFollowPeriodMax>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     set_monster_follow_period_peak: Option<SetMonsterFollowPeriodPeak>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    set_monster_follow_radius: Option<SetMonsterFollowRadius>,
 }
 
 impl Command for CommandBlock {
@@ -31,12 +45,16 @@ impl Command for CommandBlock {
         let Self {
             damage_player,
             heal_player,
+            give_player_item,
+            set_weather,
+            apply_player_effect,
             set_monster_follow_limit_min,
             set_monster_follow_limit_max,
             set_monster_follow_limit_peak,
             set_monster_follow_period_min,
             set_monster_follow_period_max,
             set_monster_follow_period_peak,
+            set_monster_follow_radius,
         } = self;
 
         if let Some(cmd) = damage_player {
@@ -45,6 +63,15 @@ impl Command for CommandBlock {
         if let Some(cmd) = heal_player {
             cmd.run(context)?;
         }
+        if let Some(cmd) = give_player_item {
+            cmd.run(context)?;
+        }
+        if let Some(cmd) = set_weather {
+            cmd.run(context)?;
+        }
+        if let Some(cmd) = apply_player_effect {
+            cmd.run(context)?;
+        }
 
         if let Some(cmd) = set_monster_follow_limit_min {
             cmd.run(context)?;
@@ -66,6 +93,10 @@ impl Command for CommandBlock {
             cmd.run(context)?;
         }
 
+        if let Some(cmd) = set_monster_follow_radius {
+            cmd.run(context)?;
+        }
+
         Ok(())
     }
 }
@@ -96,6 +127,57 @@ impl Command for HealPlayerCommand {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GivePlayerItemCommand {
+    kind: ItemKind,
+    count: ItemCount,
+}
+
+impl Command for GivePlayerItemCommand {
+    fn run(&self, context: &mut CommandContext) -> anyhow::Result<()> {
+        let stack = ItemStack::new(self.kind, self.count)?;
+        context.game.pick_up_item(stack)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SetWeatherCommand {
+    weather: Weather,
+}
+
+impl Command for SetWeatherCommand {
+    fn run(&self, context: &mut CommandContext) -> anyhow::Result<()> {
+        let head = context.game.player().position().head();
+        let region = WeatherRegion::containing(
+            context.game.current_map_id(),
+            head,
+            context.game.map().get_biome(head)?,
+        );
+        context.game.change_weather(region, self.weather);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ApplyPlayerEffectCommand {
+    kind: EffectKind,
+    intensity: Intensity,
+    duration: u32,
+}
+
+impl Command for ApplyPlayerEffectCommand {
+    fn run(&self, context: &mut CommandContext) -> anyhow::Result<()> {
+        let effect =
+            StatusEffect::new(self.kind, self.intensity, self.duration);
+        context.game.apply_player_effect(effect);
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(transparent)]
 struct SetMonsterFollowLimitMin {
@@ -175,3 +257,16 @@ impl Command for SetMonsterFollowPeriodMax {
         Ok(())
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+struct SetMonsterFollowRadius {
+    value: Coord,
+}
+
+impl Command for SetMonsterFollowRadius {
+    fn run(&self, context: &mut CommandContext) -> anyhow::Result<()> {
+        context.event_distr_config.set_monster_follow_radius(self.value)?;
+        Ok(())
+    }
+}
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 
-use crate::monster;
+use crate::{
+    item::{ItemCount, ItemKind},
+    monster,
+    npc,
+};
 
 #[derive(
     Debug,
@@ -26,6 +30,29 @@ impl Default for Block {
     }
 }
 
+impl Block {
+    pub fn is_passable(self, this: SpecialBlock) -> bool {
+        match self {
+            Self::Placeable(block) => block.is_passable(),
+            Self::Special(block) => block == this,
+        }
+    }
+
+    pub fn is_transparent(self) -> bool {
+        match self {
+            Self::Placeable(block) => block.is_transparent(),
+            Self::Special(_) => true,
+        }
+    }
+
+    pub fn is_destructible(self) -> bool {
+        match self {
+            Self::Placeable(block) => block.is_destructible(),
+            Self::Special(_) => false,
+        }
+    }
+}
+
 impl From<PlaceableBlock> for Block {
     fn from(block: PlaceableBlock) -> Self {
         Self::Placeable(block)
@@ -55,6 +82,93 @@ impl From<SpecialBlock> for Block {
 pub enum PlaceableBlock {
     #[default]
     Air = 0,
+    Wall = 1,
+    Tree = 2,
+    Rock = 3,
+    Water = 4,
+    ClosedDoor = 5,
+    OpenDoor = 6,
+    StairsDown = 7,
+    StairsUp = 8,
+}
+
+impl PlaceableBlock {
+    pub const COUNT: usize = 9;
+
+    pub const ALL: [Self; Self::COUNT] = [
+        Self::Air,
+        Self::Wall,
+        Self::Tree,
+        Self::Rock,
+        Self::Water,
+        Self::ClosedDoor,
+        Self::OpenDoor,
+        Self::StairsDown,
+        Self::StairsUp,
+    ];
+
+    pub const fn is_passable(self) -> bool {
+        match self {
+            Self::Air | Self::OpenDoor | Self::StairsDown | Self::StairsUp => {
+                true
+            },
+            Self::Wall
+            | Self::Tree
+            | Self::Rock
+            | Self::Water
+            | Self::ClosedDoor => false,
+        }
+    }
+
+    pub const fn is_transparent(self) -> bool {
+        match self {
+            Self::Air
+            | Self::Water
+            | Self::OpenDoor
+            | Self::StairsDown
+            | Self::StairsUp => true,
+            Self::Wall | Self::Tree | Self::Rock | Self::ClosedDoor => false,
+        }
+    }
+
+    pub const fn is_destructible(self) -> bool {
+        match self {
+            Self::Wall
+            | Self::Tree
+            | Self::Rock
+            | Self::ClosedDoor
+            | Self::OpenDoor => true,
+            Self::Air | Self::Water | Self::StairsDown | Self::StairsUp => {
+                false
+            },
+        }
+    }
+
+    /// Building material kept when the player digs this block out.
+    pub const fn dug_material(self) -> Option<Self> {
+        match self {
+            Self::OpenDoor => Some(Self::ClosedDoor),
+            Self::Wall | Self::Tree | Self::Rock | Self::ClosedDoor => {
+                Some(self)
+            },
+            Self::Air | Self::Water | Self::StairsDown | Self::StairsUp => None,
+        }
+    }
+
+    /// Material obtained by gathering this block, which removes it.
+    pub const fn gathered_material(self) -> Option<(ItemKind, ItemCount)> {
+        match self {
+            Self::Tree => Some((ItemKind::Wood, 3)),
+            Self::Rock => Some((ItemKind::Stone, 2)),
+            Self::Air
+            | Self::Wall
+            | Self::Water
+            | Self::ClosedDoor
+            | Self::OpenDoor
+            | Self::StairsDown
+            | Self::StairsUp => None,
+        }
+    }
 }
 
 #[derive(
@@ -75,4 +189,5 @@ pub enum SpecialBlock {
     #[default]
     Player = 0,
     Monster(monster::Id),
+    Npc(npc::Id),
 }
@@ -0,0 +1,265 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    block::{Block, PlaceableBlock},
+    geometry::{Coord, CoordPair},
+    item::ItemStack,
+    map::{MapId, PortalEnd},
+    matter::{Biome, Ground},
+    monster::Monster,
+};
+
+#[cfg(test)]
+mod test;
+
+/// Position of a chunk in the grid of chunks of a map, i.e. the offset of
+/// its top left point from the map's top left point divided by the chunk
+/// size.
+/// Indices share the `u16` cap of points, so a map spans at most 2048
+/// chunks along each axis.
+pub type ChunkIndex = CoordPair;
+
+#[derive(Debug, Error)]
+pub enum InvalidChunk {
+    #[error("Chunk layer has {given} tiles, expected {}", Chunk::AREA)]
+    LayerSize { given: usize },
+    #[error("Chunk contains player or monster blocks")]
+    SpecialBlock,
+    #[error(
+        "Chunk sparse layer has index {given}, expected below {}",
+        Chunk::AREA
+    )]
+    SparseIndex { given: usize },
+}
+
+#[derive(Debug, Error)]
+pub enum ChunkStoreErrorSource {
+    #[error("I/O error happened")]
+    Io(#[from] io::Error),
+    #[error("Failed to (de)serialize")]
+    Serde(#[from] serde_json::Error),
+    #[error("Stored chunk is malformed")]
+    Invalid(#[from] InvalidChunk),
+}
+
+#[derive(Debug, Error)]
+#[error("Failed to access chunk file {path}")]
+pub struct ChunkStoreError {
+    pub path: PathBuf,
+    #[source]
+    pub source: ChunkStoreErrorSource,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Chunk {
+    pub(crate) biome_layer: Vec<Biome>,
+    pub(crate) ground_layer: Vec<Ground>,
+    pub(crate) block_layer: Vec<Block>,
+    pub(crate) explored_layer: Vec<bool>,
+    /// Blocks under the player, monsters and NPCs, by flat index.
+    pub(crate) covered_blocks: BTreeMap<usize, PlaceableBlock>,
+    pub(crate) portals: BTreeMap<usize, PortalEnd>,
+    pub(crate) ground_items: BTreeMap<usize, Vec<ItemStack>>,
+    /// Monsters that were in the chunk when it was unloaded, put back once
+    /// it is loaded again. Always empty while loaded.
+    pub(crate) monsters: Vec<Monster>,
+}
+
+impl Default for Chunk {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Chunk {
+    pub const SIZE: Coord = 32;
+
+    pub const AREA: usize = Self::SIZE as usize * Self::SIZE as usize;
+
+    pub fn new() -> Self {
+        Self {
+            biome_layer: vec![Biome::default(); Self::AREA],
+            ground_layer: vec![Ground::default(); Self::AREA],
+            block_layer: vec![Block::default(); Self::AREA],
+            explored_layer: vec![false; Self::AREA],
+            covered_blocks: BTreeMap::new(),
+            portals: BTreeMap::new(),
+            ground_items: BTreeMap::new(),
+            monsters: Vec::new(),
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), InvalidChunk> {
+        for given in [
+            self.biome_layer.len(),
+            self.ground_layer.len(),
+            self.block_layer.len(),
+            self.explored_layer.len(),
+        ] {
+            if given != Self::AREA {
+                Err(InvalidChunk::LayerSize { given })?
+            }
+        }
+        if self
+            .block_layer
+            .iter()
+            .any(|block| matches!(block, Block::Special(_)))
+        {
+            Err(InvalidChunk::SpecialBlock)?
+        }
+        for given in self
+            .covered_blocks
+            .keys()
+            .chain(self.portals.keys())
+            .chain(self.ground_items.keys())
+            .copied()
+        {
+            if given >= Self::AREA {
+                Err(InvalidChunk::SparseIndex { given })?
+            }
+        }
+        Ok(())
+    }
+
+    /// Index into the layers of the given offset from the chunk's top left.
+    pub(crate) fn flat_index(offset: CoordPair) -> usize {
+        usize::from(offset.y) * usize::from(Self::SIZE) + usize::from(offset.x)
+    }
+}
+
+/// Directory where chunks unloaded from memory are persisted, one file per
+/// chunk, grouped by map.
+///
+/// Chunks are saved into a staging area first, and only replace the chunks
+/// of the saved game once committed, which should happen along with saving
+/// the game. Otherwise, quitting without saving would leave chunks ahead of
+/// the saved game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkStore {
+    dir: PathBuf,
+}
+
+impl ChunkStore {
+    const STAGING_DIR: &str = "staging";
+
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Stages the chunk, to be committed later.
+    pub fn save(
+        &self,
+        map: MapId,
+        index: ChunkIndex,
+        chunk: &Chunk,
+    ) -> Result<(), ChunkStoreError> {
+        let path = self.staging_dir().join(Self::chunk_file(map, index));
+        let result = (|| {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut file = BufWriter::new(File::create(&path)?);
+            serde_json::to_writer(&mut file, chunk)?;
+            Ok(())
+        })();
+        result.map_err(|source| ChunkStoreError { path, source })
+    }
+
+    /// Loads the chunk from the staging area, or from the committed chunks
+    /// if it was not staged.
+    pub fn load(
+        &self,
+        map: MapId,
+        index: ChunkIndex,
+    ) -> Result<Option<Chunk>, ChunkStoreError> {
+        let file = Self::chunk_file(map, index);
+        for dir in [self.staging_dir(), self.dir.clone()] {
+            if let Some(chunk) = Self::load_from(dir.join(&file))? {
+                return Ok(Some(chunk));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Makes every staged chunk part of the saved game.
+    pub fn commit(&self) -> Result<(), ChunkStoreError> {
+        let staging_dir = self.staging_dir();
+        let map_dirs = match fs::read_dir(&staging_dir) {
+            Ok(map_dirs) => map_dirs,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                return Ok(());
+            },
+            Err(error) => Err(io_error(&staging_dir)(error))?,
+        };
+        for map_dir in map_dirs {
+            let map_dir = map_dir.map_err(io_error(&staging_dir))?;
+            let target_dir = self.dir.join(map_dir.file_name());
+            fs::create_dir_all(&target_dir).map_err(io_error(&target_dir))?;
+            let map_path = map_dir.path();
+            for entry in fs::read_dir(&map_path).map_err(io_error(&map_path))? {
+                let entry = entry.map_err(io_error(&map_path))?;
+                let path = entry.path();
+                fs::rename(&path, target_dir.join(entry.file_name()))
+                    .map_err(io_error(&path))?;
+            }
+        }
+        self.discard()
+    }
+
+    /// Drops every staged chunk, going back to the chunks of the saved game.
+    pub fn discard(&self) -> Result<(), ChunkStoreError> {
+        let path = self.staging_dir();
+        match fs::remove_dir_all(&path) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                Err(io_error(&path)(error))
+            },
+            _ => Ok(()),
+        }
+    }
+
+    fn load_from(path: PathBuf) -> Result<Option<Chunk>, ChunkStoreError> {
+        let result = (|| {
+            let file = match File::open(&path) {
+                Ok(file) => file,
+                Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                    return Ok(None);
+                },
+                Err(error) => Err(error)?,
+            };
+            let chunk: Chunk =
+                serde_json::from_reader(&mut BufReader::new(file))?;
+            chunk.validate()?;
+            Ok(Some(chunk))
+        })();
+        result.map_err(|source| ChunkStoreError { path, source })
+    }
+
+    fn staging_dir(&self) -> PathBuf {
+        self.dir.join(Self::STAGING_DIR)
+    }
+
+    fn chunk_file(map: MapId, index: ChunkIndex) -> PathBuf {
+        let mut path = PathBuf::from(map.to_string());
+        path.push(format!("{}_{}.json", index.y, index.x));
+        path
+    }
+}
+
+fn io_error(path: &Path) -> impl FnOnce(io::Error) -> ChunkStoreError + '_ {
+    move |source| ChunkStoreError {
+        path: path.to_owned(),
+        source: source.into(),
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{
+    block::{Block, PlaceableBlock, SpecialBlock},
+    geometry::{CoordPair, Rect},
+    map::{AccessError, LoadChunkError, Map, PortalEnd},
+};
+
+use super::{Chunk, ChunkStore, InvalidChunk};
+
+fn lazy_map() -> Map {
+    let rect = Rect {
+        top_left: CoordPair { y: 10, x: 20 },
+        size: CoordPair { y: 100, x: 70 },
+    };
+    Map::new_lazy(rect, 0).unwrap()
+}
+
+#[test]
+fn grid_covers_partial_chunks() {
+    let map = lazy_map();
+    assert_eq!(map.chunk_grid_size(), CoordPair { y: 4, x: 3 });
+    assert_eq!(
+        map.chunk_rect(CoordPair { y: 3, x: 2 }),
+        Some(Rect {
+            top_left: CoordPair { y: 106, x: 84 },
+            size: CoordPair { y: 4, x: 6 },
+        }),
+    );
+    assert_eq!(map.chunk_rect(CoordPair { y: 4, x: 0 }), None);
+}
+
+#[test]
+fn unloaded_chunk_is_inaccessible() {
+    let map = lazy_map();
+    let point = CoordPair { y: 50, x: 60 };
+    assert!(!map.is_loaded(point));
+    assert!(matches!(
+        map.get_block(point),
+        Err(AccessError::UnloadedChunk(CoordPair { y: 1, x: 1 })),
+    ));
+    assert_eq!(map.get_loaded_block(point).unwrap(), None);
+}
+
+#[test]
+fn load_and_unload_roundtrip() {
+    let mut map = lazy_map();
+    let index = CoordPair { y: 1, x: 1 };
+    let point = CoordPair { y: 50, x: 60 };
+    map.load_chunk(index, Chunk::new()).unwrap();
+    map.set_placeable_block(point, PlaceableBlock::Wall).unwrap();
+    let portal = PortalEnd { map: 1, point: CoordPair { y: 5, x: 5 } };
+    map.set_portal(point, portal).unwrap();
+    assert_eq!(map.loaded_rect(), map.chunk_rect(index));
+
+    let chunk = map.unload_chunk(index).unwrap();
+    assert!(!map.is_loaded(point));
+    assert!(map.get_portal(point).is_err());
+    map.load_chunk(index, chunk).unwrap();
+    assert_eq!(map.get_block(point).unwrap(), PlaceableBlock::Wall.into());
+    assert_eq!(map.get_portal(point).unwrap(), Some(portal));
+}
+
+#[test]
+fn load_rejects_bad_chunks() {
+    let mut map = lazy_map();
+    let index = CoordPair { y: 0, x: 0 };
+    assert!(matches!(
+        map.load_chunk(CoordPair { y: 0, x: 3 }, Chunk::new()),
+        Err(LoadChunkError::InvalidIndex { .. }),
+    ));
+
+    let mut chunk = Chunk::new();
+    chunk.block_layer[0] = Block::Special(SpecialBlock::Player);
+    assert!(matches!(
+        map.load_chunk(index, chunk),
+        Err(LoadChunkError::Invalid(_)),
+    ));
+
+    let mut chunk = Chunk::new();
+    chunk.ground_items.insert(Chunk::AREA, Vec::new());
+    assert!(matches!(
+        map.load_chunk(index, chunk),
+        Err(LoadChunkError::Invalid(InvalidChunk::SparseIndex { .. })),
+    ));
+
+    map.load_chunk(index, Chunk::new()).unwrap();
+    assert!(matches!(
+        map.load_chunk(index, Chunk::new()),
+        Err(LoadChunkError::AlreadyLoaded(_)),
+    ));
+}
+
+#[test]
+fn store_keeps_staged_chunks_until_commit() {
+    let dir = std::env::temp_dir()
+        .join(format!("thedes-chunk-store-{}", std::process::id()));
+    let store = ChunkStore::new(&dir);
+    let index = CoordPair { y: 2, x: 1 };
+    let mut committed = Chunk::new();
+    committed.block_layer[0] = PlaceableBlock::Wall.into();
+    let mut staged = Chunk::new();
+    staged.block_layer[0] = PlaceableBlock::Rock.into();
+
+    store.save(0, index, &committed).unwrap();
+    store.commit().unwrap();
+    assert_eq!(store.load(0, index).unwrap(), Some(committed.clone()));
+
+    store.save(0, index, &staged).unwrap();
+    assert_eq!(store.load(0, index).unwrap(), Some(staged.clone()));
+    store.discard().unwrap();
+    assert_eq!(store.load(0, index).unwrap(), Some(committed));
+
+    store.save(0, index, &staged).unwrap();
+    store.commit().unwrap();
+    store.discard().unwrap();
+    assert_eq!(store.load(0, index).unwrap(), Some(staged));
+    assert_eq!(store.load(1, index).unwrap(), None);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
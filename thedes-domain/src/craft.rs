@@ -0,0 +1,186 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::item::{InvalidStackSize, ItemCount, ItemKind, ItemStack};
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Error)]
+pub enum InvalidRecipe {
+    #[error("Recipe {0:?} has no ingredients")]
+    NoIngredients(String),
+    #[error("Recipe {name:?} requires no {kind}")]
+    ZeroIngredient { name: String, kind: ItemKind },
+    #[error("Recipe {name:?} lists {kind} more than once")]
+    DuplicateIngredient { name: String, kind: ItemKind },
+    #[error("Recipe {name:?} has an invalid result")]
+    Result {
+        name: String,
+        #[source]
+        source: InvalidStackSize,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum RecipeBookError {
+    #[error("Failed to parse recipes")]
+    Parse(
+        #[from]
+        #[source]
+        serde_json::Error,
+    ),
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+#[serde(deny_unknown_fields)]
+pub struct Ingredient {
+    pub kind: ItemKind,
+    pub count: u32,
+}
+
+impl fmt::Display for Ingredient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x {}", self.count, self.kind)
+    }
+}
+
+/// Recipe as written in recipe assets, before validation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RecipeData {
+    name: String,
+    ingredients: Vec<Ingredient>,
+    result: ItemKind,
+    #[serde(default = "RecipeData::default_count")]
+    count: ItemCount,
+}
+
+impl RecipeData {
+    fn default_count() -> ItemCount {
+        1
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "RecipeData", into = "RecipeData")]
+pub struct Recipe {
+    name: String,
+    ingredients: Vec<Ingredient>,
+    result: ItemStack,
+}
+
+impl Recipe {
+    pub fn new(
+        name: impl Into<String>,
+        ingredients: impl IntoIterator<Item = Ingredient>,
+        result: ItemStack,
+    ) -> Result<Self, InvalidRecipe> {
+        let name = name.into();
+        let ingredients: Vec<_> = ingredients.into_iter().collect();
+        if ingredients.is_empty() {
+            Err(InvalidRecipe::NoIngredients(name.clone()))?
+        }
+        for (i, ingredient) in ingredients.iter().enumerate() {
+            let kind = ingredient.kind;
+            if ingredient.count == 0 {
+                Err(InvalidRecipe::ZeroIngredient { name: name.clone(), kind })?
+            }
+            if ingredients[.. i].iter().any(|other| other.kind == kind) {
+                Err(InvalidRecipe::DuplicateIngredient {
+                    name: name.clone(),
+                    kind,
+                })?
+            }
+        }
+        Ok(Self { name, ingredients, result })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn ingredients(&self) -> &[Ingredient] {
+        &self.ingredients
+    }
+
+    pub fn result(&self) -> ItemStack {
+        self.result
+    }
+}
+
+impl TryFrom<RecipeData> for Recipe {
+    type Error = InvalidRecipe;
+
+    fn try_from(data: RecipeData) -> Result<Self, Self::Error> {
+        let result =
+            ItemStack::new(data.result, data.count).map_err(|source| {
+                InvalidRecipe::Result { name: data.name.clone(), source }
+            })?;
+        Self::new(data.name, data.ingredients, result)
+    }
+}
+
+impl From<Recipe> for RecipeData {
+    fn from(recipe: Recipe) -> Self {
+        Self {
+            name: recipe.name,
+            ingredients: recipe.ingredients,
+            result: recipe.result.kind(),
+            count: recipe.result.count(),
+        }
+    }
+}
+
+impl fmt::Display for Recipe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.name)?;
+        for (i, ingredient) in self.ingredients.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{ingredient}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct RecipeBook {
+    recipes: Vec<Recipe>,
+}
+
+impl RecipeBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_json(bytes: &[u8]) -> Result<Self, RecipeBookError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    pub fn with_recipe(mut self, recipe: Recipe) -> Self {
+        self.recipes.push(recipe);
+        self
+    }
+
+    pub fn recipes(&self) -> &[Recipe] {
+        &self.recipes
+    }
+}
@@ -0,0 +1,92 @@
+use crate::item::{ItemKind, ItemStack};
+
+use super::{Ingredient, InvalidRecipe, Recipe, RecipeBook};
+
+#[test]
+fn parse_recipe_book() {
+    let json = br#"[
+        {
+            "name": "Stone Sword",
+            "ingredients": [
+                { "kind": "Stone", "count": 4 },
+                { "kind": "Wood", "count": 2 }
+            ],
+            "result": "StoneSword"
+        },
+        {
+            "name": "Planks",
+            "ingredients": [{ "kind": "Wood", "count": 1 }],
+            "result": "Wood",
+            "count": 2
+        }
+    ]"#;
+    let book = RecipeBook::from_json(json).unwrap();
+    assert_eq!(book.recipes().len(), 2);
+    let sword = &book.recipes()[0];
+    assert_eq!(sword.name(), "Stone Sword");
+    assert_eq!(
+        sword.ingredients(),
+        &[
+            Ingredient { kind: ItemKind::Stone, count: 4 },
+            Ingredient { kind: ItemKind::Wood, count: 2 },
+        ],
+    );
+    assert_eq!(sword.result(), ItemStack::single(ItemKind::StoneSword));
+    assert_eq!(
+        book.recipes()[1].result(),
+        ItemStack::new(ItemKind::Wood, 2).unwrap(),
+    );
+}
+
+#[test]
+fn parse_rejects_invalid_result() {
+    let json = br#"[{
+        "name": "Many Swords",
+        "ingredients": [{ "kind": "Wood", "count": 1 }],
+        "result": "WoodenSword",
+        "count": 2
+    }]"#;
+    assert!(RecipeBook::from_json(json).is_err());
+}
+
+#[test]
+fn new_rejects_bad_ingredients() {
+    let result = ItemStack::single(ItemKind::WoodenSword);
+    assert!(matches!(
+        Recipe::new("Nothing", [], result),
+        Err(InvalidRecipe::NoIngredients(_)),
+    ));
+    assert!(matches!(
+        Recipe::new(
+            "Zero",
+            [Ingredient { kind: ItemKind::Wood, count: 0 }],
+            result,
+        ),
+        Err(InvalidRecipe::ZeroIngredient { kind: ItemKind::Wood, .. }),
+    ));
+    assert!(matches!(
+        Recipe::new(
+            "Twice",
+            [
+                Ingredient { kind: ItemKind::Wood, count: 1 },
+                Ingredient { kind: ItemKind::Wood, count: 2 },
+            ],
+            result,
+        ),
+        Err(InvalidRecipe::DuplicateIngredient { kind: ItemKind::Wood, .. }),
+    ));
+}
+
+#[test]
+fn display_lists_ingredients() {
+    let recipe = Recipe::new(
+        "Stone Sword",
+        [
+            Ingredient { kind: ItemKind::Stone, count: 4 },
+            Ingredient { kind: ItemKind::Wood, count: 2 },
+        ],
+        ItemStack::single(ItemKind::StoneSword),
+    )
+    .unwrap();
+    assert_eq!(recipe.to_string(), "Stone Sword: 4x stone, 2x wood");
+}
@@ -0,0 +1,164 @@
+use std::{collections::BTreeMap, fmt};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[cfg(test)]
+mod test;
+
+pub type NodeIndex = usize;
+
+#[derive(Debug, Error)]
+pub enum InvalidDialogue {
+    #[error("Dialogue has no nodes")]
+    NoNodes,
+    #[error(
+        "Choice {choice} of dialogue node {node} leads to node {next}, but \
+         there are only {count} nodes"
+    )]
+    DanglingChoice {
+        node: NodeIndex,
+        choice: usize,
+        next: NodeIndex,
+        count: usize,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum DialogueBookError {
+    #[error("Failed to parse dialogues")]
+    Parse(
+        #[from]
+        #[source]
+        serde_json::Error,
+    ),
+}
+
+/// An answer the player can give. Without a next node, the conversation
+/// ends.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(deny_unknown_fields)]
+pub struct DialogueChoice {
+    pub text: String,
+    #[serde(default)]
+    pub next: Option<NodeIndex>,
+}
+
+impl fmt::Display for DialogueChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Something said by the NPC. Without choices, the conversation ends after
+/// it.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(deny_unknown_fields)]
+pub struct DialogueNode {
+    pub text: String,
+    #[serde(default)]
+    pub choices: Vec<DialogueChoice>,
+}
+
+/// Conversation tree, starting at the first node.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(try_from = "Vec<DialogueNode>", into = "Vec<DialogueNode>")]
+pub struct Dialogue {
+    nodes: Vec<DialogueNode>,
+}
+
+impl Dialogue {
+    pub const ROOT: NodeIndex = 0;
+
+    pub fn new(
+        nodes: impl IntoIterator<Item = DialogueNode>,
+    ) -> Result<Self, InvalidDialogue> {
+        let nodes: Vec<_> = nodes.into_iter().collect();
+        if nodes.is_empty() {
+            Err(InvalidDialogue::NoNodes)?
+        }
+        for (node_index, node) in nodes.iter().enumerate() {
+            for (choice_index, choice) in node.choices.iter().enumerate() {
+                if let Some(next) = choice.next
+                    && next >= nodes.len()
+                {
+                    Err(InvalidDialogue::DanglingChoice {
+                        node: node_index,
+                        choice: choice_index,
+                        next,
+                        count: nodes.len(),
+                    })?
+                }
+            }
+        }
+        Ok(Self { nodes })
+    }
+
+    pub fn nodes(&self) -> &[DialogueNode] {
+        &self.nodes
+    }
+
+    pub fn node(&self, index: NodeIndex) -> Option<&DialogueNode> {
+        self.nodes.get(index)
+    }
+
+    pub fn root(&self) -> &DialogueNode {
+        &self.nodes[Self::ROOT]
+    }
+}
+
+impl TryFrom<Vec<DialogueNode>> for Dialogue {
+    type Error = InvalidDialogue;
+
+    fn try_from(nodes: Vec<DialogueNode>) -> Result<Self, Self::Error> {
+        Self::new(nodes)
+    }
+}
+
+impl From<Dialogue> for Vec<DialogueNode> {
+    fn from(dialogue: Dialogue) -> Self {
+        dialogue.nodes
+    }
+}
+
+/// Dialogues by name, as referred to by NPCs.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct DialogueBook {
+    dialogues: BTreeMap<String, Dialogue>,
+}
+
+impl DialogueBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_json(bytes: &[u8]) -> Result<Self, DialogueBookError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    pub fn with_dialogue(
+        mut self,
+        name: impl Into<String>,
+        dialogue: Dialogue,
+    ) -> Self {
+        self.dialogues.insert(name.into(), dialogue);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Dialogue> {
+        self.dialogues.get(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.dialogues.keys().map(String::as_str)
+    }
+}
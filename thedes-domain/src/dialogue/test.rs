@@ -0,0 +1,61 @@
+use super::{
+    Dialogue,
+    DialogueBook,
+    DialogueChoice,
+    DialogueNode,
+    InvalidDialogue,
+};
+
+#[test]
+fn parse_dialogue_book() {
+    let json = br#"{
+        "greeter": [
+            {
+                "text": "Hello!",
+                "choices": [
+                    { "text": "Who are you?", "next": 1 },
+                    { "text": "Bye." }
+                ]
+            },
+            { "text": "Just a greeter." }
+        ]
+    }"#;
+    let book = DialogueBook::from_json(json).unwrap();
+    let dialogue = book.get("greeter").unwrap();
+    assert_eq!(dialogue.root().text, "Hello!");
+    assert_eq!(dialogue.root().choices[0].next, Some(1));
+    assert_eq!(dialogue.root().choices[1].next, None);
+    assert!(dialogue.node(1).unwrap().choices.is_empty());
+    assert!(book.get("stranger").is_none());
+}
+
+#[test]
+fn parse_rejects_dangling_choice() {
+    let json = br#"{
+        "broken": [
+            { "text": "Hi", "choices": [{ "text": "...", "next": 3 }] }
+        ]
+    }"#;
+    assert!(DialogueBook::from_json(json).is_err());
+}
+
+#[test]
+fn new_rejects_invalid_trees() {
+    assert!(matches!(Dialogue::new([]), Err(InvalidDialogue::NoNodes)));
+    let node = DialogueNode {
+        text: "Hi".to_owned(),
+        choices: vec![DialogueChoice {
+            text: "Go on".to_owned(),
+            next: Some(1),
+        }],
+    };
+    assert!(matches!(
+        Dialogue::new([node]),
+        Err(InvalidDialogue::DanglingChoice {
+            node: 0,
+            choice: 0,
+            next: 1,
+            count: 1
+        }),
+    ));
+}
@@ -0,0 +1,236 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::monster;
+
+#[cfg(test)]
+mod test;
+
+pub type Intensity = u8;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub enum EffectKind {
+    Poison,
+    Regeneration,
+    Slow,
+    Stun,
+}
+
+impl EffectKind {
+    pub const COUNT: usize = 4;
+
+    pub const ALL: [Self; Self::COUNT] =
+        [Self::Poison, Self::Regeneration, Self::Slow, Self::Stun];
+
+    /// Event ticks between two consecutive applications of the effect.
+    pub const fn period(self) -> u32 {
+        match self {
+            Self::Poison | Self::Regeneration => 5000,
+            Self::Slow | Self::Stun => 2500,
+        }
+    }
+
+    /// Intensity limit reached by stacking applications of the effect.
+    pub const fn max_intensity(self) -> Intensity {
+        match self {
+            Self::Poison => 5,
+            Self::Regeneration => 3,
+            Self::Slow | Self::Stun => 1,
+        }
+    }
+}
+
+impl fmt::Display for EffectKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Poison => "poison",
+            Self::Regeneration => "regeneration",
+            Self::Slow => "slow",
+            Self::Stun => "stun",
+        })
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub enum EffectTarget {
+    Player,
+    Monster(monster::Id),
+}
+
+/// An effect about to be applied to someone.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub struct StatusEffect {
+    kind: EffectKind,
+    intensity: Intensity,
+    duration: u32,
+}
+
+impl StatusEffect {
+    /// Intensity is clamped to the range allowed by the effect kind.
+    pub const fn new(
+        kind: EffectKind,
+        intensity: Intensity,
+        duration: u32,
+    ) -> Self {
+        let intensity = if intensity == 0 {
+            1
+        } else if intensity > kind.max_intensity() {
+            kind.max_intensity()
+        } else {
+            intensity
+        };
+        Self { kind, intensity, duration }
+    }
+
+    pub const fn kind(&self) -> EffectKind {
+        self.kind
+    }
+
+    pub const fn intensity(&self) -> Intensity {
+        self.intensity
+    }
+
+    /// In event ticks.
+    pub const fn duration(&self) -> u32 {
+        self.duration
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub struct ActiveEffect {
+    intensity: Intensity,
+    expires_at: u64,
+}
+
+impl ActiveEffect {
+    pub const fn intensity(&self) -> Intensity {
+        self.intensity
+    }
+
+    /// Event epoch at which the effect wears off.
+    pub const fn expires_at(&self) -> u64 {
+        self.expires_at
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+pub struct StatusEffects {
+    by_kind: [Option<ActiveEffect>; EffectKind::COUNT],
+}
+
+impl StatusEffects {
+    pub const SLOW_MOVEMENT_PERCENT: u32 = 50;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, kind: EffectKind) -> Option<ActiveEffect> {
+        self.by_kind[kind as usize]
+    }
+
+    pub fn is_active(&self, kind: EffectKind) -> bool {
+        self.get(kind).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (EffectKind, ActiveEffect)> {
+        EffectKind::ALL
+            .into_iter()
+            .filter_map(|kind| Some((kind, self.get(kind)?)))
+    }
+
+    pub fn movement_percent(&self) -> u32 {
+        if self.is_active(EffectKind::Slow) {
+            Self::SLOW_MOVEMENT_PERCENT
+        } else {
+            100
+        }
+    }
+
+    /// Applying an effect that is already active stacks intensities, up to the
+    /// kind's limit, and keeps whichever expiry is the latest.
+    ///
+    /// Returns whether the effect was not active before, in which case its
+    /// ticks must be scheduled.
+    pub(crate) fn apply(&mut self, effect: StatusEffect, now: u64) -> bool {
+        let expires_at = now + u64::from(effect.duration());
+        match &mut self.by_kind[effect.kind() as usize] {
+            Some(active) => {
+                active.intensity = active
+                    .intensity
+                    .saturating_add(effect.intensity())
+                    .min(effect.kind().max_intensity());
+                active.expires_at = active.expires_at.max(expires_at);
+                false
+            },
+            slot @ None => {
+                *slot = Some(ActiveEffect {
+                    intensity: effect.intensity(),
+                    expires_at,
+                });
+                true
+            },
+        }
+    }
+
+    pub(crate) fn remove(&mut self, kind: EffectKind) -> Option<ActiveEffect> {
+        self.by_kind[kind as usize].take()
+    }
+}
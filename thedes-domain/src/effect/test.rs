@@ -0,0 +1,51 @@
+use super::{EffectKind, StatusEffect, StatusEffects};
+
+#[test]
+fn new_clamps_intensity() {
+    assert_eq!(StatusEffect::new(EffectKind::Poison, 0, 10).intensity(), 1);
+    assert_eq!(StatusEffect::new(EffectKind::Poison, 9, 10).intensity(), 5);
+    assert_eq!(StatusEffect::new(EffectKind::Stun, 3, 10).intensity(), 1);
+}
+
+#[test]
+fn first_application_needs_scheduling() {
+    let mut effects = StatusEffects::new();
+    assert!(effects.apply(StatusEffect::new(EffectKind::Poison, 2, 100), 50));
+    let active = effects.get(EffectKind::Poison).unwrap();
+    assert_eq!(active.intensity(), 2);
+    assert_eq!(active.expires_at(), 150);
+    assert!(!effects.is_active(EffectKind::Regeneration));
+}
+
+#[test]
+fn stacking_adds_intensity_up_to_limit() {
+    let mut effects = StatusEffects::new();
+    effects.apply(StatusEffect::new(EffectKind::Poison, 3, 100), 0);
+    assert!(!effects.apply(StatusEffect::new(EffectKind::Poison, 1, 100), 0));
+    assert_eq!(effects.get(EffectKind::Poison).unwrap().intensity(), 4);
+    effects.apply(StatusEffect::new(EffectKind::Poison, 3, 100), 0);
+    assert_eq!(effects.get(EffectKind::Poison).unwrap().intensity(), 5);
+}
+
+#[test]
+fn stacking_keeps_latest_expiry() {
+    let mut effects = StatusEffects::new();
+    effects.apply(StatusEffect::new(EffectKind::Slow, 1, 100), 0);
+    effects.apply(StatusEffect::new(EffectKind::Slow, 1, 20), 10);
+    assert_eq!(effects.get(EffectKind::Slow).unwrap().expires_at(), 100);
+    effects.apply(StatusEffect::new(EffectKind::Slow, 1, 200), 10);
+    assert_eq!(effects.get(EffectKind::Slow).unwrap().expires_at(), 210);
+}
+
+#[test]
+fn slow_reduces_movement() {
+    let mut effects = StatusEffects::new();
+    assert_eq!(effects.movement_percent(), 100);
+    effects.apply(StatusEffect::new(EffectKind::Slow, 1, 100), 0);
+    assert_eq!(
+        effects.movement_percent(),
+        StatusEffects::SLOW_MOVEMENT_PERCENT,
+    );
+    effects.remove(EffectKind::Slow);
+    assert_eq!(effects.movement_percent(), 100);
+}
@@ -3,16 +3,21 @@ use thedes_geometry::orientation::Direction;
 use thiserror::Error;
 
 use crate::{
+    effect::{EffectKind, EffectTarget},
     game::{
+        EffectTickError,
         Game,
         MonsterAttackError,
         MonsterFollowError,
         MoveMonsterError,
+        MoveNpcError,
         SpawnMonsterError,
         VanishMonsterError,
     },
     geometry::Coord,
-    monster::{self, MonsterPosition},
+    monster::{self, MonsterPosition, Species},
+    npc,
+    weather::{Weather, WeatherRegion},
 };
 
 #[derive(Debug, Error)]
@@ -47,32 +52,90 @@ pub enum ApplyError {
         #[source]
         MonsterFollowError,
     ),
+    #[error("Failed to move an NPC")]
+    TryMoveNpc(
+        #[from]
+        #[source]
+        MoveNpcError,
+    ),
+    #[error("Failed to tick status effect")]
+    EffectTick(
+        #[from]
+        #[source]
+        EffectTickError,
+    ),
 }
 
 #[derive(
     Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
 )]
 pub enum Event {
-    TrySpawnMonster(MonsterPosition),
+    TrySpawnMonster(MonsterPosition, Species),
     VanishMonster(monster::Id),
     TryMoveMonster(monster::Id, Direction),
     MonsterAttack(monster::Id),
-    FollowPlayer { id: monster::Id, period: Coord, limit: u32 },
+    FollowPlayer { id: monster::Id, period: Coord, limit: u32, radius: Coord },
+    PlayerHunger,
+    ChangeWeather { region: WeatherRegion, weather: Weather },
+    EffectTick { target: EffectTarget, kind: EffectKind },
+    TryMoveNpc(npc::Id, Direction),
 }
 
 impl Event {
+    pub fn monster_id(&self) -> Option<monster::Id> {
+        match self {
+            Self::TrySpawnMonster(..)
+            | Self::PlayerHunger
+            | Self::ChangeWeather { .. }
+            | Self::EffectTick { target: EffectTarget::Player, .. }
+            | Self::TryMoveNpc(..) => None,
+            Self::VanishMonster(id)
+            | Self::TryMoveMonster(id, _)
+            | Self::MonsterAttack(id)
+            | Self::FollowPlayer { id, .. }
+            | Self::EffectTick { target: EffectTarget::Monster(id), .. } => {
+                Some(*id)
+            },
+        }
+    }
+
+    pub fn npc_id(&self) -> Option<npc::Id> {
+        match self {
+            Self::TryMoveNpc(id, _) => Some(*id),
+            _ => None,
+        }
+    }
+
+    /// Whether this event concerns the map it was scheduled on, and so it
+    /// should stay with that map while the player is elsewhere.
+    pub fn is_map_scoped(&self) -> bool {
+        matches!(self, Self::TrySpawnMonster(..))
+            || self.monster_id().is_some()
+            || self.npc_id().is_some()
+    }
+
     pub(crate) fn apply(self, game: &mut Game) -> Result<(), ApplyError> {
         match self {
-            Self::TrySpawnMonster(position) => {
-                game.try_spawn_moster(position)?
+            Self::TrySpawnMonster(position, species) => {
+                game.try_spawn_moster(position, species)?
             },
             Self::VanishMonster(id) => game.vanish_monster(id)?,
             Self::TryMoveMonster(id, direction) => {
                 game.try_move_monster(id, direction)?
             },
             Self::MonsterAttack(id) => game.monster_attack(id)?,
-            Self::FollowPlayer { id, period: speed, limit } => {
-                game.monster_follow_player(id, speed, limit)?
+            Self::FollowPlayer { id, period: speed, limit, radius } => {
+                game.monster_follow_player(id, speed, limit, radius)?
+            },
+            Self::PlayerHunger => game.player_hunger(),
+            Self::ChangeWeather { region, weather } => {
+                game.change_weather(region, weather)
+            },
+            Self::EffectTick { target, kind } => {
+                game.tick_effect(target, kind)?
+            },
+            Self::TryMoveNpc(id, direction) => {
+                game.try_move_npc(id, direction)?
             },
         }
         Ok(())
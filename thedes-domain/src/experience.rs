@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+mod test;
+
+pub type Level = u32;
+
+pub type XpValue = u32;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub struct Experience {
+    level: Level,
+    points: XpValue,
+}
+
+impl Default for Experience {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Experience {
+    pub const FIRST_LEVEL: Level = 1;
+
+    pub const XP_PER_LEVEL: XpValue = 20;
+
+    pub const fn new() -> Self {
+        Self { level: Self::FIRST_LEVEL, points: 0 }
+    }
+
+    pub const fn level(&self) -> Level {
+        self.level
+    }
+
+    pub const fn points(&self) -> XpValue {
+        self.points
+    }
+
+    pub const fn threshold(&self) -> XpValue {
+        Self::threshold_for(self.level)
+    }
+
+    pub const fn threshold_for(level: Level) -> XpValue {
+        Self::XP_PER_LEVEL.saturating_mul(level)
+    }
+
+    /// Returns how many levels were gained.
+    pub fn gain(&mut self, amount: XpValue) -> Level {
+        let old_level = self.level;
+        self.points = self.points.saturating_add(amount);
+        while self.points >= self.threshold() && self.level < Level::MAX {
+            self.points -= self.threshold();
+            self.level += 1;
+        }
+        self.level - old_level
+    }
+}
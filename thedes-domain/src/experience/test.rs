@@ -0,0 +1,29 @@
+use super::Experience;
+
+#[test]
+fn gain_below_threshold() {
+    let mut experience = Experience::new();
+    assert_eq!(experience.gain(Experience::threshold_for(1) - 1), 0);
+    assert_eq!(experience.level(), 1);
+    assert_eq!(experience.points(), Experience::threshold_for(1) - 1);
+}
+
+#[test]
+fn gain_exact_threshold() {
+    let mut experience = Experience::new();
+    assert_eq!(experience.gain(Experience::threshold_for(1)), 1);
+    assert_eq!(experience.level(), 2);
+    assert_eq!(experience.points(), 0);
+}
+
+#[test]
+fn gain_multiple_levels() {
+    let mut experience = Experience::new();
+    let amount = Experience::threshold_for(1)
+        + Experience::threshold_for(2)
+        + Experience::threshold_for(3)
+        + 5;
+    assert_eq!(experience.gain(amount), 3);
+    assert_eq!(experience.level(), 4);
+    assert_eq!(experience.points(), 5);
+}
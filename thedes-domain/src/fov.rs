@@ -0,0 +1,130 @@
+use crate::geometry::{Coord, CoordPair, Rect};
+
+#[cfg(test)]
+mod test;
+
+const OCTANTS: [[i32; 4]; 8] = [
+    [1, 0, 0, 1],
+    [0, 1, 1, 0],
+    [0, -1, 1, 0],
+    [-1, 0, 0, 1],
+    [-1, 0, 0, -1],
+    [0, -1, -1, 0],
+    [0, 1, -1, 0],
+    [1, 0, 0, -1],
+];
+
+/// Recursive shadowcasting over the eight octants around `origin`. Points
+/// outside of `rect` are treated as opaque and never reported as visible.
+pub fn shadowcast<F, G, E>(
+    rect: Rect,
+    origin: CoordPair,
+    radius: Coord,
+    mut is_transparent: F,
+    mut mark_visible: G,
+) -> Result<(), E>
+where
+    F: FnMut(CoordPair) -> Result<bool, E>,
+    G: FnMut(CoordPair),
+{
+    if !rect.contains_point(origin) {
+        return Ok(());
+    }
+    mark_visible(origin);
+    let mut caster = Caster {
+        rect,
+        origin,
+        radius: i32::from(radius),
+        is_transparent: &mut is_transparent,
+        mark_visible: &mut mark_visible,
+    };
+    for octant in OCTANTS {
+        caster.cast(1, 1.0, 0.0, octant)?;
+    }
+    Ok(())
+}
+
+struct Caster<'f, F, G> {
+    rect: Rect,
+    origin: CoordPair,
+    radius: i32,
+    is_transparent: &'f mut F,
+    mark_visible: &'f mut G,
+}
+
+impl<'f, F, G, E> Caster<'f, F, G>
+where
+    F: FnMut(CoordPair) -> Result<bool, E>,
+    G: FnMut(CoordPair),
+{
+    fn to_point(&self, dy: i32, dx: i32) -> Option<CoordPair> {
+        let y = Coord::try_from(i32::from(self.origin.y) + dy).ok()?;
+        let x = Coord::try_from(i32::from(self.origin.x) + dx).ok()?;
+        let point = CoordPair { y, x };
+        self.rect.contains_point(point).then_some(point)
+    }
+
+    fn is_opaque(&mut self, point: Option<CoordPair>) -> Result<bool, E> {
+        match point {
+            Some(point) => Ok(!(self.is_transparent)(point)?),
+            None => Ok(true),
+        }
+    }
+
+    fn cast(
+        &mut self,
+        row: i32,
+        mut start_slope: f64,
+        end_slope: f64,
+        [xx, xy, yx, yy]: [i32; 4],
+    ) -> Result<(), E> {
+        if start_slope < end_slope {
+            return Ok(());
+        }
+        let mut next_start_slope = start_slope;
+        for distance in row ..= self.radius {
+            let mut blocked = false;
+            let dy = -distance;
+            for dx in -distance ..= 0 {
+                let left_slope = (f64::from(dx) - 0.5) / (f64::from(dy) + 0.5);
+                let right_slope = (f64::from(dx) + 0.5) / (f64::from(dy) - 0.5);
+                if start_slope < right_slope {
+                    continue;
+                }
+                if end_slope > left_slope {
+                    break;
+                }
+
+                let point = self.to_point(dx * yx + dy * yy, dx * xx + dy * xy);
+                if dx * dx + dy * dy <= self.radius * self.radius
+                    && let Some(point) = point
+                {
+                    (self.mark_visible)(point);
+                }
+
+                let opaque = self.is_opaque(point)?;
+                if blocked {
+                    if opaque {
+                        next_start_slope = right_slope;
+                    } else {
+                        blocked = false;
+                        start_slope = next_start_slope;
+                    }
+                } else if opaque && distance < self.radius {
+                    blocked = true;
+                    self.cast(
+                        distance + 1,
+                        start_slope,
+                        left_slope,
+                        [xx, xy, yx, yy],
+                    )?;
+                    next_start_slope = right_slope;
+                }
+            }
+            if blocked {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use std::{collections::HashSet, convert::Infallible};
+
+use crate::geometry::{CoordPair, Rect};
+
+use super::shadowcast;
+
+fn rect() -> Rect {
+    Rect {
+        top_left: CoordPair { y: 0, x: 0 },
+        size: CoordPair { y: 21, x: 21 },
+    }
+}
+
+fn visible_set<F>(
+    origin: CoordPair,
+    radius: u16,
+    is_wall: F,
+) -> HashSet<CoordPair>
+where
+    F: Fn(CoordPair) -> bool,
+{
+    let mut visible = HashSet::new();
+    shadowcast(
+        rect(),
+        origin,
+        radius,
+        |point| Ok::<_, Infallible>(!is_wall(point)),
+        |point| {
+            visible.insert(point);
+        },
+    )
+    .unwrap();
+    visible
+}
+
+#[test]
+fn open_field_within_radius() {
+    let origin = CoordPair { y: 10, x: 10 };
+    let visible = visible_set(origin, 3, |_| false);
+    assert!(visible.contains(&origin));
+    assert!(visible.contains(&CoordPair { y: 10, x: 13 }));
+    assert!(visible.contains(&CoordPair { y: 7, x: 10 }));
+    assert!(visible.contains(&CoordPair { y: 12, x: 12 }));
+    assert!(!visible.contains(&CoordPair { y: 10, x: 14 }));
+    assert!(!visible.contains(&CoordPair { y: 13, x: 13 }));
+}
+
+#[test]
+fn wall_casts_shadow() {
+    let origin = CoordPair { y: 10, x: 10 };
+    let wall = CoordPair { y: 10, x: 12 };
+    let visible = visible_set(origin, 8, |point| point == wall);
+    assert!(visible.contains(&wall));
+    assert!(!visible.contains(&CoordPair { y: 10, x: 13 }));
+    assert!(!visible.contains(&CoordPair { y: 10, x: 16 }));
+    assert!(visible.contains(&CoordPair { y: 8, x: 16 }));
+}
+
+#[test]
+fn enclosed_sees_only_walls() {
+    let origin = CoordPair { y: 10, x: 10 };
+    let visible = visible_set(origin, 8, |point| point != origin);
+    assert_eq!(visible.len(), 9);
+}
+
+#[test]
+fn clipped_by_rect() {
+    let origin = CoordPair { y: 0, x: 0 };
+    let visible = visible_set(origin, 5, |_| false);
+    assert!(visible.iter().all(|point| rect().contains_point(*point)));
+    assert!(visible.contains(&CoordPair { y: 0, x: 5 }));
+    assert!(visible.contains(&CoordPair { y: 5, x: 0 }));
+}
@@ -1,7 +1,9 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
     fs::File,
     io::{self, BufReader, BufWriter},
+    mem,
     path::{Path, PathBuf},
 };
 
@@ -12,20 +14,43 @@ use tokio::task;
 
 use crate::{
     block::{Block, PlaceableBlock, SpecialBlock},
+    chunk::{Chunk, ChunkIndex},
+    craft::Recipe,
+    effect::{EffectKind, EffectTarget, StatusEffect, StatusEffects},
     event::{self, Event},
+    experience::XpValue,
+    fov,
     geometry::{Coord, CoordPair, Rect},
-    map::{AccessError, Map},
-    monster::{self, IdShortageError, Monster, MonsterPosition},
+    inventory::{InventoryFull, NotEnoughItems, TakeError},
+    item::{EquipSlot, ItemCount, ItemKind, ItemStack, ItemUsage},
+    map::{self, AccessError, Map, MapId, PortalEnd},
+    material::NotEnoughMaterial,
+    monster::{self, IdShortageError, Kill, Monster, MonsterPosition, Species},
+    npc::{self, Npc},
+    path,
     player::{Player, PlayerPosition},
+    quest::{Quest, QuestContext, QuestLog},
     stat::StatValue,
+    time::WorldTime,
+    weather::{Weather, WeatherRegion, WeatherState},
 };
 
+#[cfg(test)]
+mod test;
+
 #[derive(Debug, Error)]
 pub enum LoadErrorSource {
     #[error("I/O error happened")]
     Io(#[from] io::Error),
     #[error("Failed to deserialize")]
     Deserialize(#[from] serde_json::Error),
+    #[error("Save has no format version, it is from an older game version")]
+    MissingVersion,
+    #[error(
+        "Save format version {found} is not supported, expected {}",
+        Game::FORMAT_VERSION
+    )]
+    UnsupportedVersion { found: u64 },
 }
 
 #[derive(Debug, Error)]
@@ -74,6 +99,110 @@ pub enum MovePlayerError {
         #[source]
         AccessError,
     ),
+    #[error("Failed to travel through portal")]
+    Travel(
+        #[from]
+        #[source]
+        TravelError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum UnloadChunkError {
+    #[error("Chunk {0} cannot be unloaded while the player is in it")]
+    PlayerInside(ChunkIndex),
+    #[error("Monster identifier is not short")]
+    MonsterId(
+        #[from]
+        #[source]
+        IdShortageError,
+    ),
+    #[error("Monster in chunk is not registered")]
+    InvalidMonster(
+        #[from]
+        #[source]
+        monster::InvalidId,
+    ),
+    #[error("Failed to vanish monster in chunk")]
+    VanishMonster(
+        #[from]
+        #[source]
+        VanishMonsterError,
+    ),
+    #[error("NPC identifier is not short")]
+    NpcId(#[source] IdShortageError),
+    #[error("Failed to take NPCs out of chunk")]
+    MapAccess(
+        #[from]
+        #[source]
+        AccessError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum LoadChunkError {
+    #[error("Failed to load chunk into map")]
+    Load(
+        #[from]
+        #[source]
+        map::LoadChunkError,
+    ),
+    #[error("NPC identifier is not short")]
+    NpcId(#[source] IdShortageError),
+    #[error("Failed to register monster back")]
+    MonsterId(
+        #[from]
+        #[source]
+        IdShortageError,
+    ),
+    #[error("Failed to put NPCs, monsters or the player back in chunk")]
+    MapAccess(
+        #[from]
+        #[source]
+        AccessError,
+    ),
+}
+
+#[derive(Debug, Error)]
+#[error("Map identifier {0} is not valid")]
+pub struct InvalidMapId(pub MapId);
+
+#[derive(Debug, Error)]
+pub enum AddMapError {
+    #[error("Run out of map identifiers")]
+    IdShortage,
+}
+
+#[derive(Debug, Error)]
+pub enum LinkPortalsError {
+    #[error("Portal end refers to an invalid map")]
+    InvalidMap(
+        #[from]
+        #[source]
+        InvalidMapId,
+    ),
+    #[error("Portal end is outside of its map")]
+    Access(
+        #[from]
+        #[source]
+        AccessError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum TravelError {
+    #[error("Portal leads to an invalid map")]
+    InvalidMap(
+        #[from]
+        #[source]
+        InvalidMapId,
+    ),
+    #[error("Failed to access map location")]
+    Access(
+        #[from]
+        #[source]
+        AccessError,
+    ),
 }
 
 #[derive(Debug, Error)]
@@ -92,6 +221,54 @@ pub enum SpawnMonsterError {
     ),
 }
 
+#[derive(Debug, Error)]
+pub enum SpawnNpcError {
+    #[error("Failed to access map location")]
+    MapAccess(
+        #[from]
+        #[source]
+        AccessError,
+    ),
+    #[error("Run out of identifiers")]
+    IdShortage(
+        #[from]
+        #[source]
+        IdShortageError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum MoveNpcError {
+    #[error("Invalid NPC ID")]
+    InvalidId(
+        #[from]
+        #[source]
+        npc::InvalidId,
+    ),
+    #[error("Failed to access map location")]
+    MapAccess(
+        #[from]
+        #[source]
+        AccessError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum TalkError {
+    #[error("Invalid NPC ID")]
+    InvalidId(
+        #[from]
+        #[source]
+        npc::InvalidId,
+    ),
+    #[error("Failed to access map location")]
+    MapAccess(
+        #[from]
+        #[source]
+        AccessError,
+    ),
+}
+
 #[derive(Debug, Error)]
 pub enum VanishMonsterError {
     #[error("Invalid monster ID")]
@@ -140,6 +317,28 @@ pub enum MonsterAttackError {
     ),
 }
 
+#[derive(Debug, Error)]
+pub enum PlayerAttackError {
+    #[error("Failed to access map location")]
+    MapAccess(
+        #[from]
+        #[source]
+        AccessError,
+    ),
+    #[error("Invalid monster ID")]
+    InvalidId(
+        #[from]
+        #[source]
+        monster::InvalidId,
+    ),
+    #[error("Failed to vanish killed monster")]
+    Vanish(
+        #[from]
+        #[source]
+        VanishMonsterError,
+    ),
+}
+
 #[derive(Debug, Error)]
 pub enum MonsterFollowError {
     #[error("Invalid monster ID")]
@@ -150,36 +349,383 @@ pub enum MonsterFollowError {
     ),
     #[error("Failed to move monster")]
     MoveMonster(#[from] MoveMonsterError),
+    #[error("Failed to access map location")]
+    MapAccess(
+        #[from]
+        #[source]
+        AccessError,
+    ),
 }
 
-fn blocks_movement(block: Block, this: SpecialBlock) -> bool {
-    match block {
-        Block::Placeable(block) => block != PlaceableBlock::Air,
-        Block::Special(block) => {
-            block != this
-                && matches!(
-                    block,
-                    SpecialBlock::Player | SpecialBlock::Monster(_)
-                )
-        },
+#[derive(Debug, Error)]
+pub enum PickUpItemError {
+    #[error("Inventory is full")]
+    InventoryFull(
+        #[from]
+        #[source]
+        InventoryFull,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum DropItemError {
+    #[error("Failed to take item from inventory")]
+    Take(
+        #[from]
+        #[source]
+        TakeError,
+    ),
+    #[error("Failed to access map location")]
+    MapAccess(
+        #[from]
+        #[source]
+        AccessError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum PickUpGroundError {
+    #[error("Failed to access map location")]
+    MapAccess(
+        #[from]
+        #[source]
+        AccessError,
+    ),
+    #[error("Inventory is full")]
+    InventoryFull(
+        #[from]
+        #[source]
+        InventoryFull,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum CraftError {
+    #[error("Missing ingredients for recipe {recipe:?}")]
+    MissingIngredient {
+        recipe: String,
+        #[source]
+        source: NotEnoughItems,
+    },
+    #[error("No room for the result of recipe {recipe:?}")]
+    InventoryFull {
+        recipe: String,
+        #[source]
+        source: InventoryFull,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum GatherError {
+    #[error("Failed to access map location")]
+    MapAccess(
+        #[from]
+        #[source]
+        AccessError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum PlaceBlockError {
+    #[error("Failed to access map location")]
+    MapAccess(
+        #[from]
+        #[source]
+        AccessError,
+    ),
+    #[error("Cannot place block on {point}, occupied by {occupant:?}")]
+    Occupied { point: CoordPair, occupant: SpecialBlock },
+    #[error("Cannot place block on {point}, obstructed by {block:?}")]
+    Obstructed { point: CoordPair, block: PlaceableBlock },
+}
+
+#[derive(Debug, Error)]
+pub enum DigError {
+    #[error("Failed to access map location")]
+    MapAccess(
+        #[from]
+        #[source]
+        AccessError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum CloseDoorError {
+    #[error("Failed to access map location")]
+    MapAccess(
+        #[from]
+        #[source]
+        AccessError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum BuildError {
+    #[error("Failed to place block")]
+    Place(
+        #[from]
+        #[source]
+        PlaceBlockError,
+    ),
+    #[error("Failed to spend building material")]
+    Material(
+        #[from]
+        #[source]
+        NotEnoughMaterial,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum TrackQuestsError {
+    #[error("Failed to drop quest reward on the ground")]
+    MapAccess(
+        #[from]
+        #[source]
+        AccessError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum RespawnError {
+    #[error("Spawn point is in an invalid map")]
+    InvalidMap(
+        #[from]
+        #[source]
+        InvalidMapId,
+    ),
+    #[error("Failed to access map location")]
+    MapAccess(
+        #[from]
+        #[source]
+        AccessError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum UseItemError {
+    #[error("Failed to take item from inventory")]
+    Take(
+        #[from]
+        #[source]
+        TakeError,
+    ),
+    #[error("Inventory slot {0} is empty")]
+    EmptySlot(usize),
+    #[error("Item {0} cannot be used")]
+    NotUsable(ItemKind),
+}
+
+#[derive(Debug, Error)]
+pub enum EquipItemError {
+    #[error("Failed to take item from inventory")]
+    Take(
+        #[from]
+        #[source]
+        TakeError,
+    ),
+    #[error("Inventory slot {0} is empty")]
+    EmptySlot(usize),
+    #[error("Item {0} cannot be equipped")]
+    NotEquipable(ItemKind),
+    #[error("No room to store previously equipped item")]
+    InventoryFull(
+        #[from]
+        #[source]
+        InventoryFull,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum UnequipItemError {
+    #[error("Nothing is equipped in slot {0:?}")]
+    NothingEquipped(EquipSlot),
+    #[error("No room to store unequipped item")]
+    InventoryFull(
+        #[from]
+        #[source]
+        InventoryFull,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum ApplyEffectError {
+    #[error("Invalid monster ID")]
+    InvalidId(
+        #[from]
+        #[source]
+        monster::InvalidId,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum EffectTickError {
+    #[error("Invalid monster ID")]
+    InvalidId(
+        #[from]
+        #[source]
+        monster::InvalidId,
+    ),
+    #[error("Failed to vanish killed monster")]
+    Vanish(
+        #[from]
+        #[source]
+        VanishMonsterError,
+    ),
+}
+
+fn blocks_movement(block: Option<Block>, this: SpecialBlock) -> bool {
+    !block.is_some_and(|block| block.is_passable(this))
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+pub enum SimulationMode {
+    /// Events advance on every session tick.
+    #[default]
+    RealTime,
+    /// Events only advance when the player takes an action.
+    TurnBased,
+}
+
+impl SimulationMode {
+    pub const COUNT: usize = 2;
+
+    pub const ALL: [Self; Self::COUNT] = [Self::RealTime, Self::TurnBased];
+}
+
+impl fmt::Display for SimulationMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::RealTime => "Real-Time",
+            Self::TurnBased => "Turn-Based",
+        })
+    }
+}
+
+/// What happens to a game once its player dies. Defaults to the only mode
+/// that keeps the save around, so that a game that does not say otherwise is
+/// never deleted.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+pub enum DeathMode {
+    /// The game is over and its save is deleted.
+    Permadeath,
+    /// Like permadeath, but the character is remembered in the graveyard.
+    Graveyard,
+    /// The player is brought back to their spawn point, leaving everything
+    /// they carried where they died.
+    #[default]
+    Respawn,
+}
+
+impl DeathMode {
+    pub const COUNT: usize = 3;
+
+    pub const ALL: [Self; Self::COUNT] =
+        [Self::Permadeath, Self::Graveyard, Self::Respawn];
+
+    pub fn is_permanent(self) -> bool {
+        self != Self::Respawn
+    }
+}
+
+impl fmt::Display for DeathMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Permadeath => "Permadeath",
+            Self::Graveyard => "Permadeath with Graveyard",
+            Self::Respawn => "Respawn",
+        })
     }
 }
 
+/// Where the player is brought back to when respawning.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct SpawnPoint {
+    pub map: MapId,
+    pub position: PlayerPosition,
+}
+
+/// A map the player is not currently on, kept along with its monsters, NPCs
+/// and events, which are frozen until the player comes back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ParkedMap {
+    map: Map,
+    monster_registry: monster::Registry,
+    npc_registry: npc::Registry,
+    event_schedule: HashMap<u64, Vec<Event>>,
+    parked_at: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Game {
+    /// Always [`Game::FORMAT_VERSION`] once loaded.
+    format_version: u64,
     map: Map,
+    current_map: MapId,
+    parked_maps: BTreeMap<MapId, ParkedMap>,
     player: Player,
     monster_registry: monster::Registry,
+    npc_registry: npc::Registry,
     event_schedule: HashMap<u64, Vec<Event>>,
     event_epoch: u64,
+    weather: WeatherState,
+    simulation_mode: SimulationMode,
+    death_mode: DeathMode,
+    spawn: SpawnPoint,
+    kills: Vec<Kill>,
+    quests: QuestLog,
+    #[serde(skip)]
+    visible: HashSet<CoordPair>,
 }
 
 impl Game {
-    pub fn new(mut map: Map, player: Player) -> Result<Self, InitError> {
+    /// Version of the save format, bumped whenever saves of the previous
+    /// version can no longer be loaded. Saves of any other version are
+    /// rejected instead of being loaded with made up data.
+    pub const FORMAT_VERSION: u64 = 1;
+
+    pub const NEW_BIOME_XP: XpValue = 50;
+
+    pub const HUNGER_DECAY_PERIOD: u32 = 37500;
+
+    pub const STARVATION_PERIOD: u32 = 12500;
+
+    pub const STARVATION_DAMAGE: StatValue = 1;
+
+    pub const QUICK_STEP_STAMINA: StatValue = 5;
+
+    pub const WALK_STAMINA_RECOVERY: StatValue = 1;
+
+    pub fn new(mut map: Map, mut player: Player) -> Result<Self, InitError> {
         if let Err(source) = map
-            .set_block(player.position().head(), SpecialBlock::Player)
+            .occupy(player.position().head(), SpecialBlock::Player)
             .and_then(|_| {
-                map.set_block(player.position().pointer(), SpecialBlock::Player)
+                map.occupy(player.position().pointer(), SpecialBlock::Player)
             })
         {
             return Err(InitError::PlayerOutsideMap {
@@ -188,13 +734,30 @@ impl Game {
                 source,
             });
         }
-        Ok(Self {
+        if let Ok(biome) = map.get_biome(player.position().head()) {
+            player.discover_biome(biome);
+        }
+        let spawn = SpawnPoint { map: 0, position: player.position().clone() };
+        let mut this = Self {
+            format_version: Self::FORMAT_VERSION,
             map,
+            current_map: 0,
+            parked_maps: BTreeMap::new(),
             player,
             monster_registry: monster::Registry::new(),
+            npc_registry: npc::Registry::new(),
             event_schedule: HashMap::new(),
             event_epoch: 0,
-        })
+            weather: WeatherState::new(),
+            simulation_mode: SimulationMode::default(),
+            death_mode: DeathMode::default(),
+            spawn,
+            kills: Vec::new(),
+            quests: QuestLog::new(),
+            visible: HashSet::new(),
+        };
+        this.schedule_event(Event::PlayerHunger, Self::HUNGER_DECAY_PERIOD);
+        Ok(this)
     }
 
     pub async fn load(path: &Path) -> Result<Self, LoadError> {
@@ -206,10 +769,24 @@ impl Game {
             let mut file = BufReader::new(file);
             serde_json::from_reader(&mut file)
                 .map_err(LoadErrorSource::from)
+                .and_then(Self::from_json)
                 .map_err(|source| LoadError { path: path.to_owned(), source })
         })
     }
 
+    /// Checks the format version before anything else, so that saves from
+    /// other versions fail with a clear error.
+    fn from_json(value: serde_json::Value) -> Result<Self, LoadErrorSource> {
+        let version = value
+            .get("format_version")
+            .and_then(serde_json::Value::as_u64)
+            .ok_or(LoadErrorSource::MissingVersion)?;
+        if version != Self::FORMAT_VERSION {
+            Err(LoadErrorSource::UnsupportedVersion { found: version })?
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
     pub async fn save(&self, path: &Path) -> Result<(), SaveError> {
         task::block_in_place(|| {
             let file =
@@ -227,47 +804,491 @@ impl Game {
         })
     }
 
-    pub fn schedule_event(&mut self, event: Event, event_ticks: u32) {
-        let event_ticks = u64::from(event_ticks) + self.event_epoch;
-        self.event_schedule.entry(event_ticks).or_default().push(event);
+    pub fn schedule_event(&mut self, event: Event, event_ticks: u32) {
+        let event_ticks = u64::from(event_ticks) + self.event_epoch;
+        self.event_schedule.entry(event_ticks).or_default().push(event);
+    }
+
+    pub fn execute_events(&mut self) -> Result<(), event::ApplyError> {
+        let old_epoch = self.event_epoch;
+        self.event_epoch += 1;
+        for event in
+            self.event_schedule.remove(&old_epoch).into_iter().flatten()
+        {
+            // The batch is already out of the schedule, so events of monsters
+            // that died earlier in it were not dropped along with them.
+            if let Some(id) = event.monster_id()
+                && self.monster_registry.get_by_id(id).is_err()
+            {
+                continue;
+            }
+            event.apply(self)?;
+        }
+        Ok(())
+    }
+
+    pub fn time(&self) -> WorldTime {
+        WorldTime::from_epoch(self.event_epoch)
+    }
+
+    pub fn simulation_mode(&self) -> SimulationMode {
+        self.simulation_mode
+    }
+
+    pub fn set_simulation_mode(&mut self, mode: SimulationMode) {
+        self.simulation_mode = mode;
+    }
+
+    pub fn death_mode(&self) -> DeathMode {
+        self.death_mode
+    }
+
+    pub fn set_death_mode(&mut self, mode: DeathMode) {
+        self.death_mode = mode;
+    }
+
+    pub fn spawn_point(&self) -> &SpawnPoint {
+        &self.spawn
+    }
+
+    pub fn weather(&self) -> &WeatherState {
+        &self.weather
+    }
+
+    /// Weather at a point of the current map.
+    pub fn weather_at(&self, point: CoordPair) -> Result<Weather, AccessError> {
+        let biome = self.map.get_biome(point)?;
+        Ok(self.weather.at(self.current_map, point, biome))
+    }
+
+    pub fn player_weather(&self) -> Result<Weather, AccessError> {
+        self.weather_at(self.player.position().head())
+    }
+
+    /// Percentage of normal speed the player currently moves at, given the
+    /// weather around them and their status effects.
+    pub fn player_movement_percent(&self) -> Result<u32, AccessError> {
+        let weather = self.player_weather()?;
+        Ok(weather.movement_percent()
+            * self.player.effects().movement_percent()
+            / 100)
+    }
+
+    pub fn change_weather(&mut self, region: WeatherRegion, weather: Weather) {
+        self.weather.set(region, weather);
+    }
+
+    pub fn map(&self) -> &Map {
+        &self.map
+    }
+
+    /// Loads a chunk into the current map, putting back the NPCs living in
+    /// it and the monsters it was unloaded with, as well as the player, if
+    /// they respawned into it. Monsters whose tile got taken meanwhile are
+    /// lost.
+    pub fn load_chunk(
+        &mut self,
+        index: ChunkIndex,
+        mut chunk: Chunk,
+    ) -> Result<(), LoadChunkError> {
+        let monsters = mem::take(&mut chunk.monsters);
+        self.map.load_chunk(index, chunk)?;
+        for (id, body) in
+            self.npcs_in_chunk(index).map_err(LoadChunkError::NpcId)?
+        {
+            self.map.occupy(body, SpecialBlock::Npc(id))?;
+        }
+        if let Some(rect) = self.map.chunk_rect(index) {
+            let position = self.player.position();
+            for point in [position.head(), position.pointer()] {
+                if rect.contains_point(point) {
+                    self.map.occupy(point, SpecialBlock::Player)?;
+                }
+            }
+        }
+        for monster in monsters {
+            let body = monster.position().body();
+            if let Some(Block::Placeable(block)) =
+                self.map.get_loaded_block(body)?
+                && block.is_passable()
+            {
+                let id = self.monster_registry.create_as(monster)?;
+                self.map.occupy(body, SpecialBlock::Monster(id))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn npcs_in_chunk(
+        &self,
+        index: ChunkIndex,
+    ) -> Result<Vec<(npc::Id, CoordPair)>, IdShortageError> {
+        let Some(rect) = self.map.chunk_rect(index) else {
+            return Ok(Vec::new());
+        };
+        self.npc_registry
+            .iter()
+            .filter(|(_, npc)| rect.contains_point(npc.body()))
+            .map(|(id, npc)| Ok((npc::Id::try_from(id)?, npc.body())))
+            .collect()
+    }
+
+    /// Removes a chunk of the current map from memory, taking the monsters
+    /// in it along, without their events nor status effects. NPCs are kept,
+    /// frozen until the chunk is loaded again. Yields `None` if the chunk was
+    /// not loaded.
+    pub fn unload_chunk(
+        &mut self,
+        index: ChunkIndex,
+    ) -> Result<Option<Chunk>, UnloadChunkError> {
+        let Some(rect) = self.map.chunk_rect(index) else {
+            return Ok(None);
+        };
+        let player_pos = self.player.position();
+        if rect.contains_point(player_pos.head())
+            || rect.contains_point(player_pos.pointer())
+        {
+            Err(UnloadChunkError::PlayerInside(index))?
+        }
+        let stranded = self
+            .monster_registry
+            .iter()
+            .filter(|(_, monster)| {
+                rect.contains_point(monster.position().body())
+            })
+            .map(|(id, _)| monster::Id::try_from(id))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut monsters = Vec::with_capacity(stranded.len());
+        for id in stranded {
+            let mut monster = self.monster_registry.get_by_id(id)?.clone();
+            *monster.effects_mut() = StatusEffects::new();
+            monsters.push(monster);
+            self.vanish_monster(id)?;
+        }
+        if self.map.is_chunk_loaded(index) {
+            for (_, body) in
+                self.npcs_in_chunk(index).map_err(UnloadChunkError::NpcId)?
+            {
+                self.map.vacate(body)?;
+            }
+        }
+        let Some(mut chunk) = self.map.unload_chunk(index) else {
+            return Ok(None);
+        };
+        chunk.monsters = monsters;
+        Ok(Some(chunk))
+    }
+
+    pub fn current_map_id(&self) -> MapId {
+        self.current_map
+    }
+
+    pub fn map_count(&self) -> usize {
+        self.parked_maps.len() + 1
+    }
+
+    /// Adds another map to the game, without any monsters or events.
+    pub fn add_map(&mut self, map: Map) -> Result<MapId, AddMapError> {
+        let id = MapId::try_from(self.map_count())
+            .map_err(|_| AddMapError::IdShortage)?;
+        self.parked_maps.insert(
+            id,
+            ParkedMap {
+                map,
+                monster_registry: monster::Registry::new(),
+                npc_registry: npc::Registry::new(),
+                event_schedule: HashMap::new(),
+                parked_at: self.event_epoch,
+            },
+        );
+        Ok(id)
+    }
+
+    /// Links two portal ends both ways, so that stepping into either of them
+    /// brings the player to the other.
+    pub fn link_portals(
+        &mut self,
+        first: PortalEnd,
+        second: PortalEnd,
+    ) -> Result<(), LinkPortalsError> {
+        self.map_by_id(first.map)?.get_block(first.point)?;
+        self.map_by_id(second.map)?.get_block(second.point)?;
+        self.map_by_id_mut(first.map)?.set_portal(first.point, second)?;
+        self.map_by_id_mut(second.map)?.set_portal(second.point, first)?;
+        Ok(())
+    }
+
+    fn map_by_id(&self, id: MapId) -> Result<&Map, InvalidMapId> {
+        if id == self.current_map {
+            return Ok(&self.map);
+        }
+        self.parked_maps
+            .get(&id)
+            .map(|parked| &parked.map)
+            .ok_or(InvalidMapId(id))
+    }
+
+    fn map_by_id_mut(&mut self, id: MapId) -> Result<&mut Map, InvalidMapId> {
+        if id == self.current_map {
+            return Ok(&mut self.map);
+        }
+        self.parked_maps
+            .get_mut(&id)
+            .map(|parked| &mut parked.map)
+            .ok_or(InvalidMapId(id))
+    }
+
+    fn travel(&mut self, destination: PortalEnd) -> Result<(), TravelError> {
+        let map = self.map_by_id(destination.map)?;
+        if blocks_movement(
+            map.get_loaded_block(destination.point)?,
+            SpecialBlock::Player,
+        ) {
+            return Ok(());
+        }
+        let facing = self.player.position().facing();
+        let mut arrival_facing = None;
+        for direction in [facing].into_iter().chain(Direction::ALL) {
+            let Ok(pointer) = map
+                .rect()
+                .checked_move_point_unit(destination.point, direction)
+            else {
+                continue;
+            };
+            if !blocks_movement(
+                map.get_loaded_block(pointer)?,
+                SpecialBlock::Player,
+            ) {
+                arrival_facing = Some(direction);
+                break;
+            }
+        }
+        let Some(arrival_facing) = arrival_facing else {
+            return Ok(());
+        };
+
+        self.map.vacate(self.player.position().head())?;
+        self.map.vacate(self.player.position().pointer())?;
+        if destination.map != self.current_map {
+            self.switch_map(destination.map)?;
+        }
+        self.player.position_mut().set_head(destination.point);
+        self.player.position_mut().face(arrival_facing);
+        self.map.occupy(self.player.position().head(), SpecialBlock::Player)?;
+        self.map
+            .occupy(self.player.position().pointer(), SpecialBlock::Player)?;
+        Ok(())
+    }
+
+    fn switch_map(&mut self, id: MapId) -> Result<(), InvalidMapId> {
+        let incoming = self.parked_maps.remove(&id).ok_or(InvalidMapId(id))?;
+
+        let mut event_schedule = HashMap::<_, Vec<_>>::new();
+        let mut outgoing_schedule = HashMap::<_, Vec<_>>::new();
+        for (epoch, events) in self.event_schedule.drain() {
+            for event in events {
+                let schedule = if event.is_map_scoped() {
+                    &mut outgoing_schedule
+                } else {
+                    &mut event_schedule
+                };
+                schedule.entry(epoch).or_default().push(event);
+            }
+        }
+        let parked_time = self.event_epoch - incoming.parked_at;
+        for (epoch, events) in incoming.event_schedule {
+            event_schedule
+                .entry(epoch + parked_time)
+                .or_default()
+                .extend(events);
+        }
+
+        let outgoing = ParkedMap {
+            map: mem::replace(&mut self.map, incoming.map),
+            monster_registry: mem::replace(
+                &mut self.monster_registry,
+                incoming.monster_registry,
+            ),
+            npc_registry: mem::replace(
+                &mut self.npc_registry,
+                incoming.npc_registry,
+            ),
+            event_schedule: outgoing_schedule,
+            parked_at: self.event_epoch,
+        };
+        self.event_schedule = event_schedule;
+        self.parked_maps.insert(self.current_map, outgoing);
+        self.current_map = id;
+        self.visible.clear();
+        Ok(())
+    }
+
+    /// Blocks can only be placed on empty tiles, never on monsters nor on
+    /// the player.
+    pub fn place_block(
+        &mut self,
+        point: CoordPair,
+        block: PlaceableBlock,
+    ) -> Result<(), PlaceBlockError> {
+        match self.map.get_block(point)? {
+            Block::Placeable(PlaceableBlock::Air) => (),
+            Block::Placeable(block) => {
+                Err(PlaceBlockError::Obstructed { point, block })?
+            },
+            Block::Special(occupant) => {
+                Err(PlaceBlockError::Occupied { point, occupant })?
+            },
+        }
+        self.map.set_placeable_block(point, block)?;
+        Ok(())
+    }
+
+    /// Digs out the block targeted by the player, keeping it as building
+    /// material.
+    pub fn player_dig(&mut self) -> Result<(), DigError> {
+        if self.player.effects().is_active(EffectKind::Stun) {
+            return Ok(());
+        }
+        let Some(target) = self.player_target() else {
+            return Ok(());
+        };
+        let Some(Block::Placeable(block)) =
+            self.map.get_loaded_block(target)?
+        else {
+            return Ok(());
+        };
+        let Some(material) = block.dug_material() else {
+            return Ok(());
+        };
+        self.map.set_placeable_block(target, PlaceableBlock::Air)?;
+        self.player.materials_mut().add(material);
+        Ok(())
+    }
+
+    /// Builds the selected material on the tile targeted by the player, if
+    /// there is any material left and the tile is empty.
+    pub fn player_build(&mut self) -> Result<(), BuildError> {
+        if self.player.effects().is_active(EffectKind::Stun) {
+            return Ok(());
+        }
+        let Some(material) = self.player.materials().selected() else {
+            return Ok(());
+        };
+        if self.player.materials().count(material) == 0 {
+            return Ok(());
+        }
+        let Some(target) = self.player_target() else {
+            return Ok(());
+        };
+        let Some(Block::Placeable(PlaceableBlock::Air)) = self
+            .map
+            .get_loaded_block(target)
+            .map_err(PlaceBlockError::MapAccess)?
+        else {
+            return Ok(());
+        };
+        self.place_block(target, material)?;
+        self.player.materials_mut().spend(material)?;
+        Ok(())
     }
 
-    pub fn execute_events(&mut self) -> Result<(), event::ApplyError> {
-        let old_epoch = self.event_epoch;
-        self.event_epoch += 1;
-        for event in
-            self.event_schedule.remove(&old_epoch).into_iter().flatten()
+    /// Closes the open door targeted by the player. Doors only open as the
+    /// player walks into them, and stay open until closed this way.
+    pub fn player_close_door(&mut self) -> Result<(), CloseDoorError> {
+        if self.player.effects().is_active(EffectKind::Stun) {
+            return Ok(());
+        }
+        let Some(target) = self.player_target() else {
+            return Ok(());
+        };
+        if self.map.get_loaded_block(target)?
+            != Some(PlaceableBlock::OpenDoor.into())
         {
-            event.apply(self)?;
+            return Ok(());
         }
+        self.map.set_placeable_block(target, PlaceableBlock::ClosedDoor)?;
         Ok(())
     }
 
-    pub fn map(&self) -> &Map {
-        &self.map
-    }
-
-    pub fn place_block(
-        &mut self,
-        point: CoordPair,
-        block: PlaceableBlock,
-    ) -> Result<(), AccessError> {
-        self.map.set_placeable_block(point, block)
+    pub fn select_next_material(&mut self) -> Option<PlaceableBlock> {
+        self.player.materials_mut().select_next()
     }
 
     pub fn player(&self) -> &Player {
         &self.player
     }
 
+    /// Tile right in front of the player's pointer, which is what the player
+    /// acts upon. The pointer itself is occupied by the player.
+    pub fn player_target(&self) -> Option<CoordPair> {
+        let position = self.player.position();
+        self.map
+            .rect()
+            .checked_move_point_unit(position.pointer(), position.facing())
+            .ok()
+    }
+
+    pub fn is_visible(&self, point: CoordPair) -> bool {
+        self.visible.contains(&point)
+    }
+
+    pub fn update_fov(&mut self) -> Result<(), AccessError> {
+        self.visible.clear();
+        let weather = self.player_weather()?;
+        let radius =
+            self.player.sight_radius() * weather.visibility_percent() / 100;
+        let map = &self.map;
+        let visible = &mut self.visible;
+        fov::shadowcast(
+            map.rect(),
+            self.player.position().head(),
+            radius,
+            |point| {
+                let block = map.get_loaded_block(point)?;
+                Ok::<_, AccessError>(block.is_some_and(Block::is_transparent))
+            },
+            |point| {
+                if map.is_loaded(point) {
+                    visible.insert(point);
+                }
+            },
+        )?;
+        for &point in &self.visible {
+            self.map.explore(point)?;
+        }
+        Ok(())
+    }
+
     pub fn move_player_pointer(
         &mut self,
         direction: Direction,
     ) -> Result<(), MovePlayerError> {
+        if self.player.effects().is_active(EffectKind::Stun) {
+            return Ok(());
+        }
         if self.player.position().facing() == direction {
             self.move_player_head(direction)?;
         } else {
             self.make_player_face(direction)?;
         }
+        self.player.recover_stamina(Self::WALK_STAMINA_RECOVERY);
+        Ok(())
+    }
+
+    pub fn player_quick_step(
+        &mut self,
+        direction: Direction,
+    ) -> Result<(), MovePlayerError> {
+        if self.player.stamina().value() < Self::QUICK_STEP_STAMINA
+            || self.player.effects().is_active(EffectKind::Stun)
+        {
+            return Ok(());
+        }
+        let old_head = self.player.position().head();
+        self.move_player_head(direction)?;
+        if self.player.position().head() != old_head {
+            self.player.spend_stamina(Self::QUICK_STEP_STAMINA);
+        }
         Ok(())
     }
 
@@ -289,27 +1310,34 @@ impl Game {
         else {
             return Ok(());
         };
-        if blocks_movement(self.map.get_block(new_head)?, SpecialBlock::Player)
-        {
+        if self.try_open_door(new_head)? || self.try_open_door(new_pointer)? {
             return Ok(());
         }
         if blocks_movement(
-            self.map.get_block(new_pointer)?,
+            self.map.get_loaded_block(new_head)?,
             SpecialBlock::Player,
         ) {
             return Ok(());
         }
-        self.map
-            .set_block(self.player.position().head(), PlaceableBlock::Air)?;
-        self.map
-            .set_block(self.player.position().pointer(), PlaceableBlock::Air)?;
+        if blocks_movement(
+            self.map.get_loaded_block(new_pointer)?,
+            SpecialBlock::Player,
+        ) {
+            return Ok(());
+        }
+        self.map.vacate(self.player.position().head())?;
+        self.map.vacate(self.player.position().pointer())?;
         self.player.position_mut().set_head(new_head);
+        self.map.occupy(self.player.position().head(), SpecialBlock::Player)?;
         self.map
-            .set_block(self.player.position().head(), SpecialBlock::Player)?;
-        self.map.set_block(
-            self.player.position().pointer(),
-            SpecialBlock::Player,
-        )?;
+            .occupy(self.player.position().pointer(), SpecialBlock::Player)?;
+        if let Some(destination) = self.map.get_portal(new_head)? {
+            self.travel(destination)?;
+        }
+        let biome = self.map.get_biome(self.player.position().head())?;
+        if self.player.discover_biome(biome) {
+            self.player.gain_experience(Self::NEW_BIOME_XP);
+        }
         Ok(())
     }
 
@@ -324,20 +1352,32 @@ impl Game {
         else {
             return Ok(());
         };
-        if blocks_movement(self.map.get_block(new_head)?, SpecialBlock::Player)
-        {
+        if self.try_open_door(new_head)? {
             return Ok(());
         }
-        self.map
-            .set_block(self.player.position().pointer(), PlaceableBlock::Air)?;
-        self.player.position_mut().face(direction);
-        self.map.set_block(
-            self.player.position().pointer(),
+        if blocks_movement(
+            self.map.get_loaded_block(new_head)?,
             SpecialBlock::Player,
-        )?;
+        ) {
+            return Ok(());
+        }
+        self.map.vacate(self.player.position().pointer())?;
+        self.player.position_mut().face(direction);
+        self.map
+            .occupy(self.player.position().pointer(), SpecialBlock::Player)?;
         Ok(())
     }
 
+    fn try_open_door(&mut self, point: CoordPair) -> Result<bool, AccessError> {
+        if self.map.get_loaded_block(point)?
+            != Some(PlaceableBlock::ClosedDoor.into())
+        {
+            return Ok(false);
+        }
+        self.map.set_placeable_block(point, PlaceableBlock::OpenDoor)?;
+        Ok(true)
+    }
+
     pub fn monster_registry(&self) -> &monster::Registry {
         &self.monster_registry
     }
@@ -345,32 +1385,176 @@ impl Game {
     pub fn try_spawn_moster(
         &mut self,
         pos: MonsterPosition,
+        species: Species,
     ) -> Result<(), SpawnMonsterError> {
-        let block_value = self.map().get_block(pos.body())?;
-        if block_value == Block::Placeable(PlaceableBlock::Air) {
-            let monster = Monster::new(pos);
+        let block_value = self.map().get_loaded_block(pos.body())?;
+        if let Some(Block::Placeable(block)) = block_value
+            && block.is_passable()
+        {
+            let monster = Monster::new(pos, species);
             let monster_id = self.monster_registry.create_as(monster)?;
-            self.map
-                .set_block(pos.body(), SpecialBlock::Monster(monster_id))?;
+            self.map.occupy(pos.body(), SpecialBlock::Monster(monster_id))?;
+        }
+        Ok(())
+    }
+
+    pub fn npc_registry(&self) -> &npc::Registry {
+        &self.npc_registry
+    }
+
+    /// Spawns the NPC if its body lies on a free loaded tile, yielding its
+    /// identifier.
+    pub fn try_spawn_npc(
+        &mut self,
+        npc: Npc,
+    ) -> Result<Option<npc::Id>, SpawnNpcError> {
+        let body = npc.body();
+        let Some(Block::Placeable(block)) = self.map.get_loaded_block(body)?
+        else {
+            return Ok(None);
+        };
+        if !block.is_passable() {
+            return Ok(None);
+        }
+        let id = self.npc_registry.create_as(npc)?;
+        self.map.occupy(body, SpecialBlock::Npc(id))?;
+        Ok(Some(id))
+    }
+
+    /// Turns the NPC towards the direction, or steps into it if already
+    /// facing it. NPCs never wander too far from home, and stay still while
+    /// their chunk is not loaded.
+    pub fn try_move_npc(
+        &mut self,
+        id: npc::Id,
+        direction: Direction,
+    ) -> Result<(), MoveNpcError> {
+        let npc = self.npc_registry.get_by_id(id)?;
+        if npc.facing() != direction {
+            self.npc_registry.get_by_id_mut(id)?.face(direction);
+            return Ok(());
+        }
+        let body = npc.body();
+        if self.map.get_loaded_block(body)?.is_none() {
+            return Ok(());
+        }
+        let Ok(new_body) =
+            self.map.rect().checked_move_point_unit(body, direction)
+        else {
+            return Ok(());
+        };
+        if !npc.can_wander_to(new_body)
+            || blocks_movement(
+                self.map.get_loaded_block(new_body)?,
+                SpecialBlock::Npc(id),
+            )
+        {
+            return Ok(());
         }
+        self.map.vacate(body)?;
+        self.npc_registry.get_by_id_mut(id)?.set_body(new_body);
+        self.map.occupy(new_body, SpecialBlock::Npc(id))?;
         Ok(())
     }
 
+    /// Yields the NPC the player is facing, if any, which turns to face the
+    /// player back.
+    pub fn talk_to_npc(&mut self) -> Result<Option<npc::Id>, TalkError> {
+        if self.player.effects().is_active(EffectKind::Stun) {
+            return Ok(None);
+        }
+        let Some(target) = self.player_target() else {
+            return Ok(None);
+        };
+        let Some(Block::Special(SpecialBlock::Npc(id))) =
+            self.map.get_loaded_block(target)?
+        else {
+            return Ok(None);
+        };
+        let facing = self.player.position().facing();
+        self.npc_registry.get_by_id_mut(id)?.face(-facing);
+        Ok(Some(id))
+    }
+
     pub fn vanish_monster(
         &mut self,
         id: monster::Id,
     ) -> Result<(), VanishMonsterError> {
         let monster = self.monster_registry.remove(id)?;
-        self.map.set_block(monster.position().body(), PlaceableBlock::Air)?;
+        self.map.vacate(monster.position().body())?;
+        for events in self.event_schedule.values_mut() {
+            events.retain(|event| event.monster_id() != Some(id));
+        }
+        Ok(())
+    }
+
+    /// Vanishes the monster, recording the kill so that it can drop loot.
+    fn kill_monster(
+        &mut self,
+        id: monster::Id,
+    ) -> Result<(), VanishMonsterError> {
+        let monster = self.monster_registry.get_by_id(id)?;
+        let kill = Kill {
+            species: monster.species(),
+            point: monster.position().body(),
+        };
+        self.vanish_monster(id)?;
+        self.quests.record_kill(kill.species);
+        self.kills.push(kill);
+        Ok(())
+    }
+
+    pub fn quest_log(&self) -> &QuestLog {
+        &self.quests
+    }
+
+    pub fn quest_context(&self) -> QuestContext<'_> {
+        QuestContext {
+            now: self.event_epoch,
+            map: self.current_map,
+            player: &self.player,
+        }
+    }
+
+    pub fn accept_quest(&mut self, quest: Quest) {
+        self.quests.accept(quest, self.event_epoch);
+    }
+
+    /// Completes quests whose objectives were fulfilled, granting their
+    /// rewards. Items that do not fit in the inventory drop under the player.
+    pub fn track_quests(&mut self) -> Result<(), TrackQuestsError> {
+        let context = QuestContext {
+            now: self.event_epoch,
+            map: self.current_map,
+            player: &self.player,
+        };
+        for reward in self.quests.complete_fulfilled(context) {
+            self.player.gain_experience(reward.xp);
+            for stack in reward.items {
+                if self.player.inventory_mut().insert(stack).is_err() {
+                    let head = self.player.position().head();
+                    self.map.put_ground_items(head, stack)?;
+                }
+            }
+        }
         Ok(())
     }
 
+    /// Drains monsters killed since the last call.
+    pub fn take_kills(&mut self) -> Vec<Kill> {
+        mem::take(&mut self.kills)
+    }
+
     pub fn try_move_monster(
         &mut self,
         id: monster::Id,
         direction: Direction,
     ) -> Result<(), MoveMonsterError> {
-        let pos = self.monster_registry.get_by_id(id)?.position();
+        let monster = self.monster_registry.get_by_id(id)?;
+        if monster.effects().is_active(EffectKind::Stun) {
+            return Ok(());
+        }
+        let pos = monster.position();
         if pos.facing() == direction {
             self.move_monster_head(id, direction)?;
         } else {
@@ -391,17 +1575,17 @@ impl Game {
             return Ok(());
         };
         if blocks_movement(
-            self.map.get_block(new_body)?,
+            self.map.get_loaded_block(new_body)?,
             SpecialBlock::Monster(id),
         ) {
             return Ok(());
         }
-        self.map.set_block(pos.body(), PlaceableBlock::Air)?;
+        self.map.vacate(pos.body())?;
         self.monster_registry
             .get_by_id_mut(id)?
             .position_mut()
             .set_body(new_body);
-        self.map.set_block(new_body, SpecialBlock::Monster(id))?;
+        self.map.occupy(new_body, SpecialBlock::Monster(id))?;
         Ok(())
     }
 
@@ -419,6 +1603,9 @@ impl Game {
         id: monster::Id,
     ) -> Result<(), MonsterAttackError> {
         let monster = self.monster_registry.get_by_id(id)?;
+        if monster.effects().is_active(EffectKind::Stun) {
+            return Ok(());
+        }
         let Some(next_block) = monster
             .position()
             .body()
@@ -431,42 +1618,98 @@ impl Game {
         };
         match block {
             Block::Special(SpecialBlock::Player) => {
-                self.player.damage(1);
+                let damage = monster.species().damage();
+                let effect = monster.species().attack_effect();
+                self.player.damage(damage);
+                if self.player.hp().value() > 0 {
+                    self.player.gain_experience(damage);
+                }
+                if let Some(effect) = effect {
+                    self.apply_player_effect(effect);
+                }
             },
             _ => (),
         }
         Ok(())
     }
 
+    pub fn player_attack(&mut self) -> Result<(), PlayerAttackError> {
+        if self.player.effects().is_active(EffectKind::Stun) {
+            return Ok(());
+        }
+        let Some(target) = self.player_target() else {
+            return Ok(());
+        };
+        if let Block::Special(SpecialBlock::Monster(id)) =
+            self.map.get_block(target)?
+        {
+            let damage = self.player.attack_damage();
+            let monster = self.monster_registry.get_by_id_mut(id)?;
+            monster.damage(damage);
+            if monster.hp().value() == 0 {
+                self.kill_monster(id)?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn monster_follow_player(
         &mut self,
         id: monster::Id,
         speed: Coord,
         limit: u32,
+        radius: Coord,
     ) -> Result<(), MonsterFollowError> {
         let monster = self.monster_registry.get_by_id(id)?;
 
-        let (_, Some(vec)) = self
-            .player()
-            .position()
-            .head()
-            .diagonal_direction_from(monster.position().body())
-            .max_with_axis_by_key(|opt| opt.map(|vec| vec.magnitude))
+        let Some(direction) = path::first_step(
+            self.map.rect(),
+            monster.position().body(),
+            self.player.position().head(),
+            radius,
+            |point| {
+                let block = self.map.get_loaded_block(point)?;
+                Ok::<_, AccessError>(!blocks_movement(
+                    block,
+                    SpecialBlock::Monster(id),
+                ))
+            },
+        )?
         else {
             return Ok(());
         };
 
-        self.try_move_monster(id, vec.direction)?;
+        self.try_move_monster(id, direction)?;
 
         if let Some(new_limit) = limit.checked_sub(1) {
+            let movement_percent = self
+                .monster_registry
+                .get_by_id(id)?
+                .effects()
+                .movement_percent();
             self.schedule_event(
-                Event::FollowPlayer { id, period: speed, limit: new_limit },
-                u32::from(speed),
+                Event::FollowPlayer {
+                    id,
+                    period: speed,
+                    limit: new_limit,
+                    radius,
+                },
+                u32::from(speed) * 100 / movement_percent,
             );
         }
         Ok(())
     }
 
+    pub fn player_hunger(&mut self) {
+        if self.player.hunger().value() == 0 {
+            self.player.damage(Self::STARVATION_DAMAGE);
+            self.schedule_event(Event::PlayerHunger, Self::STARVATION_PERIOD);
+        } else {
+            self.player.starve(1);
+            self.schedule_event(Event::PlayerHunger, Self::HUNGER_DECAY_PERIOD);
+        }
+    }
+
     pub fn damage_player(&mut self, amount: StatValue) {
         self.player.damage(amount);
     }
@@ -474,4 +1717,296 @@ impl Game {
     pub fn heal_player(&mut self, amount: StatValue) {
         self.player.heal(amount);
     }
+
+    pub fn apply_player_effect(&mut self, effect: StatusEffect) {
+        let now = self.event_epoch;
+        if self.player.effects_mut().apply(effect, now) {
+            self.schedule_effect_tick(EffectTarget::Player, effect);
+        }
+    }
+
+    pub fn apply_monster_effect(
+        &mut self,
+        id: monster::Id,
+        effect: StatusEffect,
+    ) -> Result<(), ApplyEffectError> {
+        let now = self.event_epoch;
+        let monster = self.monster_registry.get_by_id_mut(id)?;
+        if monster.effects_mut().apply(effect, now) {
+            self.schedule_effect_tick(EffectTarget::Monster(id), effect);
+        }
+        Ok(())
+    }
+
+    fn schedule_effect_tick(
+        &mut self,
+        target: EffectTarget,
+        effect: StatusEffect,
+    ) {
+        let kind = effect.kind();
+        self.schedule_event(
+            Event::EffectTick { target, kind },
+            kind.period().min(effect.duration()),
+        );
+    }
+
+    /// Applies one tick of an active effect, and either schedules the next
+    /// tick or removes the effect once it expires.
+    pub fn tick_effect(
+        &mut self,
+        target: EffectTarget,
+        kind: EffectKind,
+    ) -> Result<(), EffectTickError> {
+        let now = self.event_epoch;
+        let effects = match target {
+            EffectTarget::Player => self.player.effects_mut(),
+            EffectTarget::Monster(id) => {
+                self.monster_registry.get_by_id_mut(id)?.effects_mut()
+            },
+        };
+        let Some(active) = effects.get(kind) else {
+            return Ok(());
+        };
+        let remaining = active.expires_at().saturating_sub(now);
+        if remaining == 0 {
+            effects.remove(kind);
+        }
+
+        let amount = StatValue::from(active.intensity());
+        match (kind, target) {
+            (EffectKind::Poison, EffectTarget::Player) => {
+                self.player.damage(amount)
+            },
+            (EffectKind::Regeneration, EffectTarget::Player) => {
+                self.player.heal(amount)
+            },
+            (EffectKind::Poison, EffectTarget::Monster(id)) => {
+                let monster = self.monster_registry.get_by_id_mut(id)?;
+                monster.damage(amount);
+                if monster.hp().value() == 0 {
+                    self.kill_monster(id)?;
+                    return Ok(());
+                }
+            },
+            (EffectKind::Regeneration, EffectTarget::Monster(id)) => {
+                self.monster_registry.get_by_id_mut(id)?.heal(amount)
+            },
+            (EffectKind::Slow | EffectKind::Stun, _) => (),
+        }
+
+        if remaining > 0 {
+            let next_tick = u64::from(kind.period()).min(remaining);
+            self.schedule_event(
+                Event::EffectTick { target, kind },
+                next_tick as u32,
+            );
+        }
+        Ok(())
+    }
+
+    pub fn pick_up_item(
+        &mut self,
+        stack: ItemStack,
+    ) -> Result<(), PickUpItemError> {
+        self.player.inventory_mut().insert(stack)?;
+        Ok(())
+    }
+
+    pub fn drop_item(
+        &mut self,
+        slot: usize,
+        count: ItemCount,
+    ) -> Result<ItemStack, DropItemError> {
+        let stack = self.player.inventory_mut().take(slot, count)?;
+        self.map.put_ground_items(self.player.position().head(), stack)?;
+        Ok(stack)
+    }
+
+    pub fn put_ground_items(
+        &mut self,
+        point: CoordPair,
+        stack: ItemStack,
+    ) -> Result<(), AccessError> {
+        self.map.put_ground_items(point, stack)
+    }
+
+    /// Picks up everything lying under the player's head that fits in the
+    /// inventory, leaving the rest on the ground.
+    pub fn pick_up_ground_items(&mut self) -> Result<(), PickUpGroundError> {
+        let head = self.player.position().head();
+        for stack in self.map.take_ground_items(head)? {
+            let room = self.player.inventory().room_for(stack.kind());
+            let taken = ItemCount::try_from(room)
+                .unwrap_or(ItemCount::MAX)
+                .min(stack.count());
+            if taken > 0 {
+                let taken_stack = ItemStack::new(stack.kind(), taken)
+                    .expect("taken count is within the stack count");
+                self.player.inventory_mut().insert(taken_stack)?;
+            }
+            if taken < stack.count() {
+                let left = ItemStack::new(stack.kind(), stack.count() - taken)
+                    .expect("left count is within the stack count");
+                self.map.put_ground_items(head, left)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Consumes the recipe's ingredients and stores its result. Nothing
+    /// changes if any of it fails.
+    pub fn craft(&mut self, recipe: &Recipe) -> Result<ItemStack, CraftError> {
+        let mut inventory = self.player.inventory().clone();
+        for ingredient in recipe.ingredients() {
+            inventory.take_kind(ingredient.kind, ingredient.count).map_err(
+                |source| CraftError::MissingIngredient {
+                    recipe: recipe.name().to_owned(),
+                    source,
+                },
+            )?;
+        }
+        inventory.insert(recipe.result()).map_err(|source| {
+            CraftError::InventoryFull {
+                recipe: recipe.name().to_owned(),
+                source,
+            }
+        })?;
+        *self.player.inventory_mut() = inventory;
+        Ok(recipe.result())
+    }
+
+    /// Removes the block targeted by the player, if it yields some material,
+    /// and stores the material. Whatever does not fit in the inventory is
+    /// left on the ground.
+    pub fn gather(&mut self) -> Result<(), GatherError> {
+        if self.player.effects().is_active(EffectKind::Stun) {
+            return Ok(());
+        }
+        let Some(target) = self.player_target() else {
+            return Ok(());
+        };
+        let Some(Block::Placeable(block)) =
+            self.map.get_loaded_block(target)?
+        else {
+            return Ok(());
+        };
+        let Some((kind, count)) = block.gathered_material() else {
+            return Ok(());
+        };
+        self.map.set_placeable_block(target, PlaceableBlock::Air)?;
+        let stack = ItemStack::new(kind, count)
+            .expect("gathered material should fit in a stack");
+        if self.player.inventory_mut().insert(stack).is_err() {
+            self.map.put_ground_items(target, stack)?;
+        }
+        Ok(())
+    }
+
+    /// Revives a dead player at their spawn point, dropping their inventory
+    /// where they died. Should the spawn point be taken, the player is
+    /// revived in place.
+    ///
+    /// The spawn point may lie in chunks that are not loaded, in which case
+    /// the player only shows up on the map once they are.
+    pub fn respawn_player(&mut self) -> Result<(), RespawnError> {
+        let old_head = self.player.position().head();
+        let old_pointer = self.player.position().pointer();
+        for stack in self.player.revive() {
+            self.map.put_ground_items(old_head, stack)?;
+        }
+        let spawn = self.spawn.clone();
+        if !self.is_spawn_free(&spawn)? {
+            return Ok(());
+        }
+        self.map.vacate(old_head)?;
+        self.map.vacate(old_pointer)?;
+        if spawn.map != self.current_map {
+            self.switch_map(spawn.map)?;
+        }
+        self.player.position_mut().set_head(spawn.position.head());
+        self.player.position_mut().face(spawn.position.facing());
+        for point in [spawn.position.head(), spawn.position.pointer()] {
+            if self.map.is_loaded(point) {
+                self.map.occupy(point, SpecialBlock::Player)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Unloaded chunks hold no monsters, but might hold frozen NPCs.
+    fn is_spawn_free(&self, spawn: &SpawnPoint) -> Result<bool, RespawnError> {
+        let map = self.map_by_id(spawn.map)?;
+        let npc_registry = if spawn.map == self.current_map {
+            &self.npc_registry
+        } else {
+            &self.parked_maps[&spawn.map].npc_registry
+        };
+        for point in [spawn.position.head(), spawn.position.pointer()] {
+            if map.is_loaded(point) {
+                let block = map.get_block(point)?;
+                if blocks_movement(Some(block), SpecialBlock::Player) {
+                    return Ok(false);
+                }
+            } else if npc_registry.iter().any(|(_, npc)| npc.body() == point) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    pub fn use_item(&mut self, slot: usize) -> Result<(), UseItemError> {
+        let kind = self
+            .player
+            .inventory()
+            .get(slot)
+            .map_err(TakeError::from)?
+            .ok_or(UseItemError::EmptySlot(slot))?
+            .kind();
+        let usage = kind.usage().ok_or(UseItemError::NotUsable(kind))?;
+        self.player.inventory_mut().take(slot, 1)?;
+        match usage {
+            ItemUsage::Heal(amount) => self.player.heal(amount),
+            ItemUsage::Feed(amount) => self.player.feed(amount),
+        }
+        Ok(())
+    }
+
+    pub fn equip_item(&mut self, slot: usize) -> Result<(), EquipItemError> {
+        let kind = self
+            .player
+            .inventory()
+            .get(slot)
+            .map_err(TakeError::from)?
+            .ok_or(EquipItemError::EmptySlot(slot))?
+            .kind();
+        let equip_slot =
+            kind.equip_slot().ok_or(EquipItemError::NotEquipable(kind))?;
+        let taken = self.player.inventory_mut().take(slot, 1)?;
+        if let Some(previous) = self.player.equipment().get(equip_slot)
+            && let Err(error) =
+                self.player.inventory_mut().insert(ItemStack::single(previous))
+        {
+            self.player
+                .inventory_mut()
+                .insert(taken)
+                .expect("taken item should fit back into its slot");
+            Err(error)?
+        }
+        self.player.equipment_mut().replace(equip_slot, Some(kind));
+        Ok(())
+    }
+
+    pub fn unequip_item(
+        &mut self,
+        equip_slot: EquipSlot,
+    ) -> Result<(), UnequipItemError> {
+        let kind = self
+            .player
+            .equipment()
+            .get(equip_slot)
+            .ok_or(UnequipItemError::NothingEquipped(equip_slot))?;
+        self.player.inventory_mut().insert(ItemStack::single(kind))?;
+        self.player.equipment_mut().replace(equip_slot, None);
+        Ok(())
+    }
 }
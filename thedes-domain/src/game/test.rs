@@ -0,0 +1,352 @@
+use std::slice;
+
+use thedes_geometry::orientation::Direction;
+
+use crate::{
+    block::{Block, PlaceableBlock, SpecialBlock},
+    chunk::Chunk,
+    effect::{EffectKind, StatusEffect},
+    event::Event,
+    geometry::{CoordPair, Rect},
+    item::{ItemKind, ItemStack},
+    map::{Map, PortalEnd},
+    monster::{self, MonsterPosition, Species},
+    npc::Npc,
+    player::{Player, PlayerPosition},
+};
+
+use super::{DeathMode, Game, LoadErrorSource};
+
+fn game() -> Game {
+    let rect = Rect {
+        top_left: CoordPair { y: 0, x: 0 },
+        size: CoordPair { y: 100, x: 100 },
+    };
+    let position =
+        PlayerPosition::new(CoordPair { y: 10, x: 10 }, Direction::Right)
+            .unwrap();
+    let player = Player::new(position, Player::DEFAULT_HP);
+    Game::new(Map::new(rect).unwrap(), player).unwrap()
+}
+
+fn spawn_monster(
+    game: &mut Game,
+    body: CoordPair,
+    species: Species,
+) -> monster::Id {
+    game.try_spawn_moster(MonsterPosition::new(body, Direction::Left), species)
+        .unwrap();
+    let Block::Special(SpecialBlock::Monster(id)) =
+        game.map().get_block(body).unwrap()
+    else {
+        panic!("monster should have spawned at {body}");
+    };
+    id
+}
+
+/// Adds a second map and links a portal right ahead of the player, in the
+/// first map, to a portal in the middle of the second one.
+fn game_with_stairs() -> (Game, PortalEnd, PortalEnd) {
+    let mut game = game();
+    let rect = Rect { top_left: CoordPair { y: 0, x: 0 }, size: Map::MIN_SIZE };
+    let id = game.add_map(Map::new(rect).unwrap()).unwrap();
+    let upstairs = PortalEnd { map: 0, point: CoordPair { y: 10, x: 12 } };
+    let downstairs = PortalEnd { map: id, point: CoordPair { y: 30, x: 30 } };
+    game.link_portals(upstairs, downstairs).unwrap();
+    (game, upstairs, downstairs)
+}
+
+fn walk(game: &mut Game, direction: Direction, steps: usize) {
+    for _ in 0 .. steps {
+        game.move_player_pointer(direction).unwrap();
+    }
+}
+
+fn has_events_for(game: &Game, id: monster::Id) -> bool {
+    game.event_schedule
+        .values()
+        .flatten()
+        .any(|event| event.monster_id() == Some(id))
+}
+
+#[test]
+fn player_kills_adjacent_monster() {
+    let mut game = game();
+    let target = game.player_target().unwrap();
+    assert_eq!(target, CoordPair { y: 10, x: 12 });
+    let id = spawn_monster(&mut game, target, Species::Wolf);
+    game.schedule_event(Event::MonsterAttack(id), 5);
+    assert!(has_events_for(&game, id));
+
+    let max_hits = Species::Wolf.hp().value();
+    for _ in 0 .. max_hits {
+        if game.monster_registry().get_by_id(id).is_err() {
+            break;
+        }
+        game.player_attack().unwrap();
+    }
+
+    assert!(game.monster_registry().get_by_id(id).is_err());
+    assert_eq!(game.map().get_block(target).unwrap(), Block::default());
+    assert!(!has_events_for(&game, id));
+    assert_eq!(game.take_kills().len(), 1);
+}
+
+#[test]
+fn player_attack_out_of_map_is_no_op() {
+    let rect = Rect {
+        top_left: CoordPair { y: 0, x: 0 },
+        size: CoordPair { y: 100, x: 100 },
+    };
+    let position =
+        PlayerPosition::new(CoordPair { y: 10, x: 98 }, Direction::Right)
+            .unwrap();
+    let player = Player::new(position, Player::DEFAULT_HP);
+    let mut game = Game::new(Map::new(rect).unwrap(), player).unwrap();
+    assert_eq!(game.player_target(), None);
+    game.player_attack().unwrap();
+}
+
+#[test]
+fn poison_kill_drops_rest_of_batch() {
+    let mut game = game();
+    let body = CoordPair { y: 50, x: 50 };
+    let id = spawn_monster(&mut game, body, Species::Bat);
+    let poison = StatusEffect::new(
+        EffectKind::Poison,
+        EffectKind::Poison.max_intensity(),
+        EffectKind::Poison.period(),
+    );
+    game.apply_monster_effect(id, poison).unwrap();
+    let tick = EffectKind::Poison.period();
+    game.schedule_event(Event::MonsterAttack(id), tick);
+    game.schedule_event(Event::TryMoveMonster(id, Direction::Up), tick);
+    game.schedule_event(
+        Event::FollowPlayer { id, period: 10, limit: 3, radius: 20 },
+        tick,
+    );
+
+    for _ in 0 ..= tick {
+        game.execute_events().unwrap();
+    }
+
+    assert!(game.monster_registry().get_by_id(id).is_err());
+    assert_eq!(game.map().get_block(body).unwrap(), Block::default());
+    assert_eq!(game.take_kills().len(), 1);
+}
+
+#[test]
+fn doors_open_on_walk_and_close_on_demand() {
+    let mut game = game();
+    let door = CoordPair { y: 10, x: 12 };
+    game.place_block(door, PlaceableBlock::ClosedDoor).unwrap();
+    let block_at = |game: &Game| game.map().get_block(door).unwrap();
+
+    game.move_player_pointer(Direction::Right).unwrap();
+    assert_eq!(block_at(&game), PlaceableBlock::OpenDoor.into());
+    assert_eq!(game.player().position().head(), CoordPair { y: 10, x: 10 });
+
+    game.player_close_door().unwrap();
+    assert_eq!(block_at(&game), PlaceableBlock::ClosedDoor.into());
+
+    game.move_player_pointer(Direction::Right).unwrap();
+    for _ in 0 .. 4 {
+        game.move_player_pointer(Direction::Right).unwrap();
+    }
+    assert_eq!(game.player().position().head(), CoordPair { y: 10, x: 14 });
+    assert_eq!(block_at(&game), PlaceableBlock::OpenDoor.into());
+
+    game.move_player_pointer(Direction::Left).unwrap();
+    assert_eq!(game.player_target(), Some(door));
+    game.player_close_door().unwrap();
+    assert_eq!(block_at(&game), PlaceableBlock::ClosedDoor.into());
+}
+
+#[test]
+fn death_mode_defaults_to_respawn() {
+    let game = game();
+    assert_eq!(game.death_mode(), DeathMode::Respawn);
+    assert!(!game.death_mode().is_permanent());
+}
+
+#[test]
+fn saves_of_other_versions_are_rejected() {
+    let game = game();
+    let json = serde_json::to_value(&game).unwrap();
+    let loaded = Game::from_json(json.clone()).unwrap();
+    assert_eq!(loaded.player(), game.player());
+
+    let mut old = json.clone();
+    old.as_object_mut().unwrap().remove("format_version").unwrap();
+    assert!(matches!(
+        Game::from_json(old),
+        Err(LoadErrorSource::MissingVersion),
+    ));
+
+    let mut newer = json;
+    newer["format_version"] = (Game::FORMAT_VERSION + 1).into();
+    assert!(matches!(
+        Game::from_json(newer),
+        Err(LoadErrorSource::UnsupportedVersion { found })
+            if found == Game::FORMAT_VERSION + 1,
+    ));
+}
+
+#[test]
+fn stairs_lead_both_ways() {
+    let (mut game, upstairs, downstairs) = game_with_stairs();
+
+    walk(&mut game, Direction::Right, 2);
+    assert_eq!(game.current_map_id(), downstairs.map);
+    assert_eq!(game.player().position().head(), downstairs.point);
+    assert_eq!(
+        game.map().get_portal(downstairs.point).unwrap(),
+        Some(upstairs)
+    );
+    let parked = &game.parked_maps[&upstairs.map].map;
+    assert_eq!(parked.get_block(upstairs.point).unwrap(), Block::default());
+    assert_eq!(
+        parked.get_block(CoordPair { y: 10, x: 11 }).unwrap(),
+        Block::default(),
+    );
+
+    walk(&mut game, Direction::Right, 2);
+    walk(&mut game, Direction::Left, 3);
+    assert_eq!(game.current_map_id(), upstairs.map);
+    assert_eq!(game.player().position().head(), upstairs.point);
+    assert_eq!(game.player().position().facing(), Direction::Left);
+    assert_eq!(
+        game.map().get_portal(upstairs.point).unwrap(),
+        Some(downstairs)
+    );
+    assert_eq!(
+        game.map().get_block(upstairs.point).unwrap(),
+        SpecialBlock::Player.into(),
+    );
+    let parked = &game.parked_maps[&downstairs.map].map;
+    assert_eq!(parked.get_block(downstairs.point).unwrap(), Block::default());
+}
+
+#[test]
+fn parked_map_is_frozen_until_unparked() {
+    let (mut game, upstairs, downstairs) = game_with_stairs();
+    let id = spawn_monster(&mut game, CoordPair { y: 50, x: 50 }, Species::Bat);
+    let spawn = Event::TrySpawnMonster(
+        MonsterPosition::new(CoordPair { y: 60, x: 60 }, Direction::Up),
+        Species::Bat,
+    );
+    game.schedule_event(spawn.clone(), 5);
+
+    walk(&mut game, Direction::Right, 2);
+    assert_eq!(game.current_map_id(), downstairs.map);
+    assert!(game.monster_registry().get_by_id(id).is_err());
+    assert!(!game.event_schedule.values().flatten().any(|e| *e == spawn));
+    let parked = &game.parked_maps[&upstairs.map];
+    assert_eq!(parked.parked_at, 0);
+    assert_eq!(parked.event_schedule[&5], slice::from_ref(&spawn));
+
+    for _ in 0 .. 100 {
+        game.execute_events().unwrap();
+    }
+    walk(&mut game, Direction::Right, 2);
+    walk(&mut game, Direction::Left, 3);
+    assert_eq!(game.current_map_id(), upstairs.map);
+    assert!(game.monster_registry().get_by_id(id).is_ok());
+    assert_eq!(game.parked_maps[&downstairs.map].parked_at, 100);
+    assert!(!game.event_schedule.contains_key(&5));
+    assert_eq!(game.event_schedule[&105], [spawn]);
+    assert!(
+        game.event_schedule[&u64::from(Game::HUNGER_DECAY_PERIOD)]
+            .contains(&Event::PlayerHunger)
+    );
+}
+
+#[test]
+fn respawn_leaves_parked_map() {
+    let (mut game, upstairs, downstairs) = game_with_stairs();
+    walk(&mut game, Direction::Right, 2);
+    assert_eq!(game.current_map_id(), downstairs.map);
+    game.pick_up_item(ItemStack::single(ItemKind::Wood)).unwrap();
+
+    game.damage_player(Player::DEFAULT_HP.value());
+    game.respawn_player().unwrap();
+
+    let spawn = game.spawn_point().clone();
+    assert_eq!(spawn.map, upstairs.map);
+    assert_eq!(game.current_map_id(), upstairs.map);
+    assert_eq!(game.player().position(), &spawn.position);
+    assert_eq!(
+        game.map().get_block(spawn.position.head()).unwrap(),
+        SpecialBlock::Player.into(),
+    );
+    assert!(game.player().inventory().is_empty());
+    let parked = &game.parked_maps[&downstairs.map].map;
+    assert_eq!(
+        parked.ground_items(downstairs.point).unwrap(),
+        [ItemStack::single(ItemKind::Wood)],
+    );
+    assert_eq!(parked.get_block(downstairs.point).unwrap(), Block::default());
+}
+
+#[test]
+fn chunk_reload_keeps_its_dwellers() {
+    let rect = Rect {
+        top_left: CoordPair { y: 0, x: 0 },
+        size: CoordPair { y: 128, x: 128 },
+    };
+    let mut map = Map::new_lazy(rect, 0).unwrap();
+    for y in 0 .. 4 {
+        for x in 0 .. 4 {
+            map.load_chunk(CoordPair { y, x }, Chunk::new()).unwrap();
+        }
+    }
+    let position =
+        PlayerPosition::new(CoordPair { y: 10, x: 10 }, Direction::Right)
+            .unwrap();
+    let player = Player::new(position, Player::DEFAULT_HP);
+    let mut game = Game::new(map, player).unwrap();
+
+    let index = CoordPair { y: 2, x: 2 };
+    let monster_body = CoordPair { y: 70, x: 70 };
+    let npc_body = CoordPair { y: 72, x: 72 };
+    let items_point = CoordPair { y: 75, x: 75 };
+    let stack = ItemStack::new(ItemKind::Berry, 3).unwrap();
+    let id = spawn_monster(&mut game, monster_body, Species::Bat);
+    game.schedule_event(Event::MonsterAttack(id), 5);
+    let npc = Npc::new("Ana", "Hi", npc_body, Direction::Up);
+    let npc_id = game.try_spawn_npc(npc).unwrap().unwrap();
+    game.put_ground_items(items_point, stack).unwrap();
+
+    for _ in 0 .. 2 {
+        let chunk = game.unload_chunk(index).unwrap().unwrap();
+        assert!(!game.map().is_loaded(monster_body));
+        assert_eq!(game.monster_registry().len(), 0);
+        assert!(
+            game.event_schedule
+                .values()
+                .flatten()
+                .all(|event| { event.monster_id().is_none() })
+        );
+        assert_eq!(game.npc_registry().len(), 1);
+
+        let json = serde_json::to_value(&chunk).unwrap();
+        let chunk: Chunk = serde_json::from_value(json).unwrap();
+        game.load_chunk(index, chunk).unwrap();
+
+        assert_eq!(game.monster_registry().len(), 1);
+        let (id, monster) = game.monster_registry().iter().next().unwrap();
+        assert_eq!(monster.species(), Species::Bat);
+        assert_eq!(monster.position().body(), monster_body);
+        let id = monster::Id::try_from(id).unwrap();
+        assert_eq!(
+            game.map().get_block(monster_body).unwrap(),
+            SpecialBlock::Monster(id).into(),
+        );
+        assert_eq!(game.npc_registry().len(), 1);
+        assert_eq!(
+            game.map().get_block(npc_body).unwrap(),
+            SpecialBlock::Npc(npc_id).into(),
+        );
+        assert_eq!(game.map().ground_items(items_point).unwrap(), [stack]);
+    }
+}
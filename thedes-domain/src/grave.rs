@@ -0,0 +1,168 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::task;
+
+use crate::{
+    experience::Level,
+    game::{Game, LoadErrorSource, SaveErrorSource},
+    quest::QuestStatus,
+    time::WorldTime,
+};
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Error)]
+#[error("Failed to load graveyard from {path}")]
+pub struct LoadError {
+    pub path: PathBuf,
+    #[source]
+    pub source: LoadErrorSource,
+}
+
+#[derive(Debug, Error)]
+#[error("Failed to save graveyard to {path}")]
+pub struct SaveError {
+    pub path: PathBuf,
+    #[source]
+    pub source: SaveErrorSource,
+}
+
+/// Record of a character who died for good.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Grave {
+    name: String,
+    level: Level,
+    survived: u64,
+    biomes: usize,
+    quests: usize,
+}
+
+impl Grave {
+    pub fn new(name: impl Into<String>, game: &Game) -> Self {
+        let quests = game
+            .quest_log()
+            .entries()
+            .iter()
+            .filter(|entry| matches!(entry.status(), QuestStatus::Completed(_)))
+            .count();
+        Self {
+            name: name.into(),
+            level: game.player().experience().level(),
+            survived: game.time().epoch(),
+            biomes: game.player().discovered_biomes().count(),
+            quests,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// In event ticks.
+    pub fn survived(&self) -> u64 {
+        self.survived
+    }
+
+    pub fn days_survived(&self) -> u64 {
+        self.survived / WorldTime::EPOCHS_PER_DAY
+    }
+
+    pub fn biomes(&self) -> usize {
+        self.biomes
+    }
+
+    pub fn quests(&self) -> usize {
+        self.quests
+    }
+}
+
+impl fmt::Display for Grave {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: level {}, {} days, {} biomes, {} quests",
+            self.name,
+            self.level,
+            self.days_survived(),
+            self.biomes,
+            self.quests,
+        )
+    }
+}
+
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Graveyard {
+    graves: Vec<Grave>,
+}
+
+impl Graveyard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A missing file is an empty graveyard.
+    pub async fn load(path: &Path) -> Result<Self, LoadError> {
+        task::block_in_place(|| {
+            let file = match File::open(path) {
+                Ok(file) => file,
+                Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                    return Ok(Self::new());
+                },
+                Err(error) => Err(LoadError {
+                    path: path.to_owned(),
+                    source: error.into(),
+                })?,
+            };
+            let mut file = BufReader::new(file);
+            serde_json::from_reader(&mut file)
+                .map_err(LoadErrorSource::from)
+                .map_err(|source| LoadError { path: path.to_owned(), source })
+        })
+    }
+
+    pub async fn save(&self, path: &Path) -> Result<(), SaveError> {
+        task::block_in_place(|| {
+            let file =
+                File::create(path).map_err(SaveErrorSource::from).map_err(
+                    |source| SaveError { path: path.to_owned(), source },
+                )?;
+            let mut file = BufWriter::new(file);
+            serde_json::to_writer(&mut file, self)
+                .map_err(SaveErrorSource::from)
+                .map_err(|source| SaveError {
+                    path: path.to_owned(),
+                    source,
+                })?;
+            Ok(())
+        })
+    }
+
+    pub fn graves(&self) -> &[Grave] {
+        &self.graves
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.graves.is_empty()
+    }
+
+    pub fn bury(&mut self, grave: Grave) {
+        self.graves.push(grave);
+    }
+}
@@ -0,0 +1,50 @@
+use thedes_geometry::orientation::Direction;
+
+use crate::{
+    game::Game,
+    geometry::{CoordPair, Rect},
+    map::Map,
+    player::{Player, PlayerPosition},
+    time::WorldTime,
+};
+
+use super::{Grave, Graveyard};
+
+fn game() -> Game {
+    let rect = Rect {
+        top_left: CoordPair { y: 0, x: 0 },
+        size: CoordPair { y: 100, x: 100 },
+    };
+    let position =
+        PlayerPosition::new(CoordPair { y: 10, x: 10 }, Direction::Right)
+            .unwrap();
+    let player = Player::new(position, Player::DEFAULT_HP);
+    Game::new(Map::new(rect).unwrap(), player).unwrap()
+}
+
+#[test]
+fn grave_records_character() {
+    let mut game = game();
+    for _ in 0 .. WorldTime::EPOCHS_PER_DAY * 2 {
+        game.execute_events().unwrap();
+    }
+    let grave = Grave::new("hero", &game);
+    assert_eq!(grave.name(), "hero");
+    assert_eq!(grave.level(), 1);
+    assert_eq!(grave.days_survived(), 2);
+    assert_eq!(grave.biomes(), 1);
+    assert_eq!(grave.quests(), 0);
+    assert_eq!(grave.to_string(), "hero: level 1, 2 days, 1 biomes, 0 quests",);
+}
+
+#[test]
+fn graveyard_keeps_burial_order() {
+    let game = game();
+    let mut graveyard = Graveyard::new();
+    assert!(graveyard.is_empty());
+    graveyard.bury(Grave::new("first", &game));
+    graveyard.bury(Grave::new("second", &game));
+    let names: Vec<_> =
+        graveyard.graves().iter().map(|grave| grave.name()).collect();
+    assert_eq!(names, ["first", "second"]);
+}
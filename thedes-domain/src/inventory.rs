@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::item::{ItemCount, ItemKind, ItemStack};
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Error)]
+#[error("Inventory slot {slot} is out of bounds of capacity {capacity}")]
+pub struct InvalidSlot {
+    pub slot: usize,
+    pub capacity: usize,
+}
+
+#[derive(Debug, Error)]
+#[error("Inventory has no room for {stack}, only {room} more items fit")]
+pub struct InventoryFull {
+    pub stack: ItemStack,
+    pub room: u32,
+}
+
+#[derive(Debug, Error)]
+#[error("Inventory has only {available} {kind} items, requested {requested}")]
+pub struct NotEnoughItems {
+    pub kind: ItemKind,
+    pub available: u32,
+    pub requested: u32,
+}
+
+#[derive(Debug, Error)]
+pub enum TakeError {
+    #[error("Invalid inventory slot")]
+    InvalidSlot(
+        #[from]
+        #[source]
+        InvalidSlot,
+    ),
+    #[error("Inventory slot {0} is empty")]
+    EmptySlot(usize),
+    #[error(
+        "Inventory slot {slot} has only {available} items, requested \
+         {requested}"
+    )]
+    NotEnough { slot: usize, available: ItemCount, requested: ItemCount },
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Inventory {
+    slots: Vec<Option<ItemStack>>,
+}
+
+impl Inventory {
+    pub fn new(capacity: usize) -> Self {
+        Self { slots: vec![None; capacity] }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn slots(&self) -> &[Option<ItemStack>] {
+        &self.slots
+    }
+
+    pub fn get(&self, slot: usize) -> Result<Option<ItemStack>, InvalidSlot> {
+        self.slots
+            .get(slot)
+            .copied()
+            .ok_or(InvalidSlot { slot, capacity: self.capacity() })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(Option::is_none)
+    }
+
+    pub fn count(&self, kind: ItemKind) -> u32 {
+        self.stacks()
+            .filter(|stack| stack.kind() == kind)
+            .map(|stack| u32::from(stack.count()))
+            .sum()
+    }
+
+    pub fn room_for(&self, kind: ItemKind) -> u32 {
+        self.slots
+            .iter()
+            .map(|slot| match slot {
+                Some(stack) if stack.kind() == kind => u32::from(stack.room()),
+                Some(_) => 0,
+                None => u32::from(kind.max_stack()),
+            })
+            .sum()
+    }
+
+    pub fn insert(&mut self, stack: ItemStack) -> Result<(), InventoryFull> {
+        let room = self.room_for(stack.kind());
+        if room < u32::from(stack.count()) {
+            Err(InventoryFull { stack, room })?
+        }
+
+        let mut remaining = stack.count();
+        for slot_stack in self.slots.iter_mut().flatten() {
+            if remaining == 0 {
+                break;
+            }
+            if slot_stack.kind() == stack.kind() {
+                let amount = remaining.min(slot_stack.room());
+                slot_stack.grow(amount);
+                remaining -= amount;
+            }
+        }
+        for slot in &mut self.slots {
+            if remaining == 0 {
+                break;
+            }
+            if slot.is_none() {
+                let amount = remaining.min(stack.kind().max_stack());
+                let mut new_stack = ItemStack::single(stack.kind());
+                new_stack.grow(amount - 1);
+                *slot = Some(new_stack);
+                remaining -= amount;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn take(
+        &mut self,
+        slot: usize,
+        count: ItemCount,
+    ) -> Result<ItemStack, TakeError> {
+        let capacity = self.capacity();
+        let entry =
+            self.slots.get_mut(slot).ok_or(InvalidSlot { slot, capacity })?;
+        let Some(stack) = entry else { Err(TakeError::EmptySlot(slot))? };
+        if count == 0 || count > stack.count() {
+            Err(TakeError::NotEnough {
+                slot,
+                available: stack.count(),
+                requested: count,
+            })?
+        }
+        let taken = ItemStack::new(stack.kind(), count)
+            .expect("count should be bounded by the slot's stack");
+        if count == stack.count() {
+            *entry = None;
+        } else {
+            stack.shrink(count);
+        }
+        Ok(taken)
+    }
+
+    pub fn take_kind(
+        &mut self,
+        kind: ItemKind,
+        count: u32,
+    ) -> Result<(), NotEnoughItems> {
+        let available = self.count(kind);
+        if available < count {
+            Err(NotEnoughItems { kind, available, requested: count })?
+        }
+
+        let mut remaining = count;
+        for entry in self.slots.iter_mut().rev() {
+            if remaining == 0 {
+                break;
+            }
+            if let Some(stack) = entry
+                && stack.kind() == kind
+            {
+                let stack_count = u32::from(stack.count());
+                if stack_count <= remaining {
+                    *entry = None;
+                    remaining -= stack_count;
+                } else {
+                    stack.shrink(remaining as ItemCount);
+                    remaining = 0;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Empties every slot, yielding what was in them.
+    pub fn take_all(&mut self) -> Vec<ItemStack> {
+        self.slots.iter_mut().filter_map(Option::take).collect()
+    }
+
+    pub fn stacks(&self) -> impl Iterator<Item = ItemStack> + '_ {
+        self.slots.iter().flatten().copied()
+    }
+}
@@ -0,0 +1,100 @@
+use crate::item::{ItemKind, ItemStack};
+
+use super::{Inventory, TakeError};
+
+#[test]
+fn insert_merges_into_existing_stack() {
+    let mut inventory = Inventory::new(3);
+    inventory.insert(ItemStack::new(ItemKind::Wood, 10).unwrap()).unwrap();
+    inventory.insert(ItemStack::new(ItemKind::Wood, 5).unwrap()).unwrap();
+    assert_eq!(
+        inventory.slots(),
+        &[Some(ItemStack::new(ItemKind::Wood, 15).unwrap()), None, None],
+    );
+}
+
+#[test]
+fn insert_overflows_into_empty_slot() {
+    let mut inventory = Inventory::new(3);
+    inventory.insert(ItemStack::new(ItemKind::Berry, 12).unwrap()).unwrap();
+    inventory.insert(ItemStack::new(ItemKind::Berry, 10).unwrap()).unwrap();
+    assert_eq!(
+        inventory.slots(),
+        &[
+            Some(ItemStack::new(ItemKind::Berry, 16).unwrap()),
+            Some(ItemStack::new(ItemKind::Berry, 6).unwrap()),
+            None,
+        ],
+    );
+    assert_eq!(inventory.count(ItemKind::Berry), 22);
+}
+
+#[test]
+fn insert_when_full_leaves_inventory_untouched() {
+    let mut inventory = Inventory::new(2);
+    inventory.insert(ItemStack::single(ItemKind::WoodenSword)).unwrap();
+    inventory.insert(ItemStack::new(ItemKind::Stone, 60).unwrap()).unwrap();
+    let before = inventory.clone();
+    let error = inventory
+        .insert(ItemStack::new(ItemKind::Stone, 5).unwrap())
+        .unwrap_err();
+    assert_eq!(error.room, 4);
+    assert_eq!(inventory, before);
+}
+
+#[test]
+fn take_partial_and_whole_stack() {
+    let mut inventory = Inventory::new(2);
+    inventory
+        .insert(ItemStack::new(ItemKind::HealingPotion, 3).unwrap())
+        .unwrap();
+    let taken = inventory.take(0, 2).unwrap();
+    assert_eq!(taken, ItemStack::new(ItemKind::HealingPotion, 2).unwrap());
+    assert_eq!(
+        inventory.get(0).unwrap(),
+        Some(ItemStack::single(ItemKind::HealingPotion)),
+    );
+    inventory.take(0, 1).unwrap();
+    assert!(inventory.is_empty());
+}
+
+#[test]
+fn take_invalid() {
+    let mut inventory = Inventory::new(2);
+    inventory.insert(ItemStack::new(ItemKind::Wood, 3).unwrap()).unwrap();
+    assert!(matches!(inventory.take(2, 1), Err(TakeError::InvalidSlot(_))));
+    assert!(matches!(inventory.take(1, 1), Err(TakeError::EmptySlot(1))));
+    assert!(matches!(
+        inventory.take(0, 4),
+        Err(TakeError::NotEnough { slot: 0, available: 3, requested: 4 }),
+    ));
+}
+
+#[test]
+fn take_kind_across_stacks() {
+    let mut inventory = Inventory::new(3);
+    inventory.insert(ItemStack::new(ItemKind::Stone, 64).unwrap()).unwrap();
+    inventory.insert(ItemStack::new(ItemKind::Stone, 10).unwrap()).unwrap();
+    assert!(inventory.take_kind(ItemKind::Stone, 75).is_err());
+    inventory.take_kind(ItemKind::Stone, 20).unwrap();
+    assert_eq!(inventory.count(ItemKind::Stone), 54);
+    assert_eq!(
+        inventory.slots(),
+        &[Some(ItemStack::new(ItemKind::Stone, 54).unwrap()), None, None],
+    );
+}
+
+#[test]
+fn take_all_empties_slots() {
+    let mut inventory = Inventory::new(3);
+    inventory.insert(ItemStack::new(ItemKind::Wood, 10).unwrap()).unwrap();
+    inventory.insert(ItemStack::single(ItemKind::StoneSword)).unwrap();
+    assert_eq!(
+        inventory.take_all(),
+        [
+            ItemStack::new(ItemKind::Wood, 10).unwrap(),
+            ItemStack::single(ItemKind::StoneSword),
+        ],
+    );
+    assert!(inventory.is_empty());
+}
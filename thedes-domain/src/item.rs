@@ -0,0 +1,236 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::stat::StatValue;
+
+pub type ItemCount = u16;
+
+#[derive(Debug, Error)]
+#[error(
+    "Item stack of {kind} must have between 1 and {} items, given {count}",
+    .kind.max_stack(),
+)]
+pub struct InvalidStackSize {
+    pub kind: ItemKind,
+    pub count: ItemCount,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub enum ItemKind {
+    Wood,
+    Stone,
+    Berry,
+    HealingPotion,
+    WoodenSword,
+    StoneSword,
+}
+
+impl ItemKind {
+    pub const COUNT: usize = 6;
+
+    pub const ALL: [Self; Self::COUNT] = [
+        Self::Wood,
+        Self::Stone,
+        Self::Berry,
+        Self::HealingPotion,
+        Self::WoodenSword,
+        Self::StoneSword,
+    ];
+
+    pub const fn max_stack(self) -> ItemCount {
+        match self {
+            Self::Wood | Self::Stone => 64,
+            Self::Berry => 16,
+            Self::HealingPotion => 8,
+            Self::WoodenSword | Self::StoneSword => 1,
+        }
+    }
+
+    pub const fn usage(self) -> Option<ItemUsage> {
+        match self {
+            Self::Berry => Some(ItemUsage::Feed(15)),
+            Self::HealingPotion => Some(ItemUsage::Heal(20)),
+            Self::Wood | Self::Stone | Self::WoodenSword | Self::StoneSword => {
+                None
+            },
+        }
+    }
+
+    pub const fn equip_slot(self) -> Option<EquipSlot> {
+        match self {
+            Self::WoodenSword | Self::StoneSword => Some(EquipSlot::Weapon),
+            Self::Wood | Self::Stone | Self::Berry | Self::HealingPotion => {
+                None
+            },
+        }
+    }
+
+    pub const fn attack_bonus(self) -> StatValue {
+        match self {
+            Self::WoodenSword => 2,
+            Self::StoneSword => 4,
+            Self::Wood | Self::Stone | Self::Berry | Self::HealingPotion => 0,
+        }
+    }
+}
+
+impl fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Wood => "wood",
+            Self::Stone => "stone",
+            Self::Berry => "berry",
+            Self::HealingPotion => "healing potion",
+            Self::WoodenSword => "wooden sword",
+            Self::StoneSword => "stone sword",
+        })
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub enum ItemUsage {
+    Heal(StatValue),
+    Feed(StatValue),
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub enum EquipSlot {
+    Weapon,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub struct ItemStack {
+    kind: ItemKind,
+    count: ItemCount,
+}
+
+impl ItemStack {
+    pub fn new(
+        kind: ItemKind,
+        count: ItemCount,
+    ) -> Result<Self, InvalidStackSize> {
+        if count < 1 || count > kind.max_stack() {
+            Err(InvalidStackSize { kind, count })?
+        }
+        Ok(Self { kind, count })
+    }
+
+    pub fn single(kind: ItemKind) -> Self {
+        Self { kind, count: 1 }
+    }
+
+    pub fn kind(self) -> ItemKind {
+        self.kind
+    }
+
+    pub fn count(self) -> ItemCount {
+        self.count
+    }
+
+    pub fn room(self) -> ItemCount {
+        self.kind.max_stack() - self.count
+    }
+
+    pub(crate) fn grow(&mut self, amount: ItemCount) {
+        self.count += amount;
+    }
+
+    pub(crate) fn shrink(&mut self, amount: ItemCount) {
+        self.count -= amount;
+    }
+}
+
+impl fmt::Display for ItemStack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x {}", self.count, self.kind)
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+pub struct Equipment {
+    weapon: Option<ItemKind>,
+}
+
+impl Equipment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, slot: EquipSlot) -> Option<ItemKind> {
+        match slot {
+            EquipSlot::Weapon => self.weapon,
+        }
+    }
+
+    pub(crate) fn replace(
+        &mut self,
+        slot: EquipSlot,
+        item: Option<ItemKind>,
+    ) -> Option<ItemKind> {
+        match slot {
+            EquipSlot::Weapon => std::mem::replace(&mut self.weapon, item),
+        }
+    }
+
+    pub fn attack_bonus(&self) -> StatValue {
+        self.weapon.map_or(0, ItemKind::attack_bonus)
+    }
+}
@@ -1,9 +1,24 @@
 pub mod stat;
+pub mod experience;
 pub mod geometry;
 pub mod matter;
 pub mod block;
+pub mod item;
+pub mod inventory;
+pub mod craft;
+pub mod material;
+pub mod chunk;
 pub mod map;
 pub mod player;
 pub mod monster;
+pub mod npc;
+pub mod dialogue;
+pub mod quest;
+pub mod effect;
 pub mod game;
+pub mod grave;
 pub mod event;
+pub mod time;
+pub mod weather;
+pub mod path;
+pub mod fov;
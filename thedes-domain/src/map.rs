@@ -1,13 +1,20 @@
-use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use thedes_geometry::rect;
 use thiserror::Error;
 
 use crate::{
-    block::{Block, PlaceableBlock},
-    geometry::{CoordPair, Rect},
+    block::{Block, PlaceableBlock, SpecialBlock},
+    chunk::{Chunk, ChunkIndex, InvalidChunk},
+    geometry::{Coord, CoordPair, Rect},
+    item::ItemStack,
     matter::{Biome, Ground},
 };
 
+#[cfg(test)]
+mod test;
+
 #[derive(Debug, Error)]
 pub enum InitError {
     #[error("Map size {given_size} is below the minimum of {}", Map::MIN_SIZE)]
@@ -33,14 +40,78 @@ pub enum AccessError {
     GetGround(CoordPair, u8),
     #[error("Bits {1} in point {0} are not valid to decode block value")]
     GetBlock(CoordPair, u8),
+    #[error("Chunk {0} is not loaded")]
+    UnloadedChunk(ChunkIndex),
+}
+
+#[derive(Debug, Error)]
+pub enum LoadChunkError {
+    #[error("Chunk index {index} is outside of map grid of size {grid_size}")]
+    InvalidIndex { index: ChunkIndex, grid_size: CoordPair },
+    #[error("Chunk {0} is already loaded")]
+    AlreadyLoaded(ChunkIndex),
+    #[error("Chunk is malformed")]
+    Invalid(
+        #[from]
+        #[source]
+        InvalidChunk,
+    ),
+}
+
+pub type MapId = u16;
+
+fn serialize_chunks<S>(
+    chunks: &HashMap<ChunkIndex, Chunk>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_seq(chunks)
+}
+
+fn deserialize_chunks<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<ChunkIndex, Chunk>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let entries = Vec::<(ChunkIndex, Chunk)>::deserialize(deserializer)?;
+    Ok(entries.into_iter().collect())
 }
 
+/// Where a portal leads to: a point in some map of the game.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub struct PortalEnd {
+    pub map: MapId,
+    pub point: CoordPair,
+}
+
+/// A map made of chunks, loaded and unloaded independently.
+///
+/// Maps are still capped by the `u16` [`Coord`]: a map is at most
+/// `u16::MAX` tiles along each axis. Chunking lifts the up-front memory cost
+/// instead, since only loaded chunks take memory, whatever the map's size.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Map {
     rect: Rect,
-    biome_layer: Vec<Biome>,
-    ground_layer: Vec<Ground>,
-    block_layer: Vec<Block>,
+    seed: Option<u64>,
+    #[serde(
+        serialize_with = "serialize_chunks",
+        deserialize_with = "deserialize_chunks"
+    )]
+    chunks: HashMap<ChunkIndex, Chunk>,
 }
 
 impl Map {
@@ -58,23 +129,116 @@ impl Map {
             Err(InitError::BottomRightOverflow { given_rect: rect })?
         }
 
-        let total_area = usize::from(rect.map(usize::from).total_area());
+        let mut this = Self::new_unloaded(rect, None);
+        let grid_size = this.chunk_grid_size();
+        for y in 0 .. grid_size.y {
+            for x in 0 .. grid_size.x {
+                this.chunks.insert(CoordPair { y, x }, Chunk::new());
+            }
+        }
+        Ok(this)
+    }
+
+    /// Creates a map with no chunk loaded. If a seed is given, chunks
+    /// missing from memory and from storage are meant to be generated from
+    /// it on demand.
+    pub fn new_lazy(rect: Rect, seed: u64) -> Result<Self, InitError> {
+        if rect.checked_bottom_right().is_none() {
+            Err(InitError::BottomRightOverflow { given_rect: rect })?
+        }
+        Ok(Self::new_unloaded(rect, Some(seed)))
+    }
 
-        Ok(Self {
-            rect,
-            biome_layer: vec![Biome::default(); total_area],
-            ground_layer: vec![Ground::default(); total_area],
-            block_layer: vec![Block::default(); total_area],
-        })
+    fn new_unloaded(rect: Rect, seed: Option<u64>) -> Self {
+        Self { rect, seed, chunks: HashMap::new() }
     }
 
     pub fn rect(&self) -> Rect {
         self.rect
     }
 
+    /// Seed for lazy generation of chunks, if this map is lazily generated.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Number of chunks along each axis.
+    pub fn chunk_grid_size(&self) -> CoordPair {
+        self.rect.size.map(|size| size.div_ceil(Chunk::SIZE))
+    }
+
+    pub fn chunk_index(
+        &self,
+        point: CoordPair,
+    ) -> Result<ChunkIndex, InvalidPoint> {
+        let (index, _) = self.locate(point)?;
+        Ok(index)
+    }
+
+    /// Rectangle covered by the given chunk, clipped to the map rectangle.
+    pub fn chunk_rect(&self, index: ChunkIndex) -> Option<Rect> {
+        let grid_size = self.chunk_grid_size();
+        if index.zip2(grid_size).any(|(index, size)| index >= size) {
+            return None;
+        }
+        let top_left = self.rect.top_left + index * Chunk::SIZE;
+        let bottom_right = self.rect.bottom_right();
+        let size = top_left.zip2_with(bottom_right, |start, end| {
+            (end - start).min(Chunk::SIZE)
+        });
+        Some(Rect { top_left, size })
+    }
+
+    pub fn is_chunk_loaded(&self, index: ChunkIndex) -> bool {
+        self.chunks.contains_key(&index)
+    }
+
+    pub fn is_loaded(&self, point: CoordPair) -> bool {
+        self.chunk_index(point).is_ok_and(|index| self.is_chunk_loaded(index))
+    }
+
+    pub fn loaded_chunks(&self) -> impl Iterator<Item = ChunkIndex> + '_ {
+        self.chunks.keys().copied()
+    }
+
+    /// Smallest rectangle containing every loaded chunk.
+    pub fn loaded_rect(&self) -> Option<Rect> {
+        let mut indices = self.loaded_chunks();
+        let first = indices.next()?;
+        let (min, max) = indices.fold((first, first), |(min, max), index| {
+            (min.zip2_with(index, Coord::min), max.zip2_with(index, Coord::max))
+        });
+        let top_left = self.chunk_rect(min)?.top_left;
+        let bottom_right = self.chunk_rect(max)?.bottom_right();
+        Some(Rect { top_left, size: bottom_right - top_left })
+    }
+
+    pub fn load_chunk(
+        &mut self,
+        index: ChunkIndex,
+        chunk: Chunk,
+    ) -> Result<(), LoadChunkError> {
+        if self.chunk_rect(index).is_none() {
+            Err(LoadChunkError::InvalidIndex {
+                index,
+                grid_size: self.chunk_grid_size(),
+            })?
+        }
+        if self.is_chunk_loaded(index) {
+            Err(LoadChunkError::AlreadyLoaded(index))?
+        }
+        chunk.validate()?;
+        self.chunks.insert(index, chunk);
+        Ok(())
+    }
+
+    pub fn unload_chunk(&mut self, index: ChunkIndex) -> Option<Chunk> {
+        self.chunks.remove(&index)
+    }
+
     pub fn get_biome(&self, point: CoordPair) -> Result<Biome, AccessError> {
-        let index = self.to_flat_index(point)?;
-        Ok(self.biome_layer[index])
+        let (chunk, index) = self.chunk(point)?;
+        Ok(chunk.biome_layer[index])
     }
 
     pub fn set_biome(
@@ -82,14 +246,14 @@ impl Map {
         point: CoordPair,
         biome: Biome,
     ) -> Result<(), AccessError> {
-        let index = self.to_flat_index(point)?;
-        self.biome_layer[index] = biome;
+        let (chunk, index) = self.chunk_mut(point)?;
+        chunk.biome_layer[index] = biome;
         Ok(())
     }
 
     pub fn get_ground(&self, point: CoordPair) -> Result<Ground, AccessError> {
-        let index = self.to_flat_index(point)?;
-        Ok(self.ground_layer[index])
+        let (chunk, index) = self.chunk(point)?;
+        Ok(chunk.ground_layer[index])
     }
 
     pub fn set_ground(
@@ -97,14 +261,27 @@ impl Map {
         point: CoordPair,
         ground: Ground,
     ) -> Result<(), AccessError> {
-        let index = self.to_flat_index(point)?;
-        self.ground_layer[index] = ground;
+        let (chunk, index) = self.chunk_mut(point)?;
+        chunk.ground_layer[index] = ground;
         Ok(())
     }
 
     pub fn get_block(&self, point: CoordPair) -> Result<Block, AccessError> {
-        let index = self.to_flat_index(point)?;
-        Ok(self.block_layer[index])
+        let (chunk, index) = self.chunk(point)?;
+        Ok(chunk.block_layer[index])
+    }
+
+    /// Like [`Map::get_block`], but yields `None` for points in chunks that
+    /// are not loaded.
+    pub fn get_loaded_block(
+        &self,
+        point: CoordPair,
+    ) -> Result<Option<Block>, AccessError> {
+        match self.get_block(point) {
+            Ok(block) => Ok(Some(block)),
+            Err(AccessError::UnloadedChunk(_)) => Ok(None),
+            Err(error) => Err(error),
+        }
     }
 
     pub(crate) fn set_block<T>(
@@ -115,8 +292,8 @@ impl Map {
     where
         T: Into<Block>,
     {
-        let index = self.to_flat_index(point)?;
-        self.block_layer[index] = block.into();
+        let (chunk, index) = self.chunk_mut(point)?;
+        chunk.block_layer[index] = block.into();
         Ok(())
     }
 
@@ -125,14 +302,158 @@ impl Map {
         point: CoordPair,
         block: PlaceableBlock,
     ) -> Result<(), AccessError> {
-        self.set_block(point, block)
+        self.set_block(point, block)?;
+        let (chunk, index) = self.chunk_mut(point)?;
+        chunk.covered_blocks.remove(&index);
+        Ok(())
+    }
+
+    pub fn get_covered_block(
+        &self,
+        point: CoordPair,
+    ) -> Result<PlaceableBlock, AccessError> {
+        let (chunk, index) = self.chunk(point)?;
+        let block = match chunk.block_layer[index] {
+            Block::Placeable(block) => block,
+            Block::Special(_) => {
+                chunk.covered_blocks.get(&index).copied().unwrap_or_default()
+            },
+        };
+        Ok(block)
     }
 
-    fn to_flat_index(&self, point: CoordPair) -> Result<usize, InvalidPoint> {
-        let index = self
-            .rect
+    pub fn get_portal(
+        &self,
+        point: CoordPair,
+    ) -> Result<Option<PortalEnd>, AccessError> {
+        let (chunk, index) = self.chunk(point)?;
+        Ok(chunk.portals.get(&index).copied())
+    }
+
+    pub(crate) fn set_portal(
+        &mut self,
+        point: CoordPair,
+        destination: PortalEnd,
+    ) -> Result<(), AccessError> {
+        let (chunk, index) = self.chunk_mut(point)?;
+        chunk.portals.insert(index, destination);
+        Ok(())
+    }
+
+    /// Items lying on the given tile.
+    pub fn ground_items(
+        &self,
+        point: CoordPair,
+    ) -> Result<&[ItemStack], AccessError> {
+        let (chunk, index) = self.chunk(point)?;
+        Ok(chunk.ground_items.get(&index).map_or(&[], Vec::as_slice))
+    }
+
+    /// Merges the stack into stacks of the same kind already lying on the
+    /// tile, as far as they have room.
+    pub(crate) fn put_ground_items(
+        &mut self,
+        point: CoordPair,
+        stack: ItemStack,
+    ) -> Result<(), AccessError> {
+        let (chunk, index) = self.chunk_mut(point)?;
+        let stacks = chunk.ground_items.entry(index).or_default();
+        let mut remaining = stack.count();
+        for ground_stack in stacks.iter_mut() {
+            if ground_stack.kind() == stack.kind() {
+                let amount = remaining.min(ground_stack.room());
+                ground_stack.grow(amount);
+                remaining -= amount;
+            }
+        }
+        while remaining > 0 {
+            let amount = remaining.min(stack.kind().max_stack());
+            let mut new_stack = ItemStack::single(stack.kind());
+            new_stack.grow(amount - 1);
+            stacks.push(new_stack);
+            remaining -= amount;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn take_ground_items(
+        &mut self,
+        point: CoordPair,
+    ) -> Result<Vec<ItemStack>, AccessError> {
+        let (chunk, index) = self.chunk_mut(point)?;
+        Ok(chunk.ground_items.remove(&index).unwrap_or_default())
+    }
+
+    pub fn is_explored(&self, point: CoordPair) -> Result<bool, AccessError> {
+        let (chunk, index) = self.chunk(point)?;
+        Ok(chunk.explored_layer[index])
+    }
+
+    pub(crate) fn explore(
+        &mut self,
+        point: CoordPair,
+    ) -> Result<(), AccessError> {
+        let (chunk, index) = self.chunk_mut(point)?;
+        chunk.explored_layer[index] = true;
+        Ok(())
+    }
+
+    pub(crate) fn occupy(
+        &mut self,
+        point: CoordPair,
+        block: SpecialBlock,
+    ) -> Result<(), AccessError> {
+        let (chunk, index) = self.chunk_mut(point)?;
+        let covered = chunk.block_layer[index];
+        chunk.block_layer[index] = block.into();
+        if let Block::Placeable(covered) = covered
+            && covered != PlaceableBlock::Air
+        {
+            chunk.covered_blocks.insert(index, covered);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn vacate(
+        &mut self,
+        point: CoordPair,
+    ) -> Result<(), AccessError> {
+        let (chunk, index) = self.chunk_mut(point)?;
+        let covered = chunk.covered_blocks.remove(&index).unwrap_or_default();
+        chunk.block_layer[index] = covered.into();
+        Ok(())
+    }
+
+    /// Finds the chunk containing the point, and the point's index in the
+    /// chunk layers.
+    fn locate(
+        &self,
+        point: CoordPair,
+    ) -> Result<(ChunkIndex, usize), InvalidPoint> {
+        self.rect
             .map(usize::from)
             .checked_horz_area_down_to(point.map(usize::from))?;
-        Ok(index)
+        let offset = point - self.rect.top_left;
+        let index = offset / Chunk::SIZE;
+        Ok((index, Chunk::flat_index(offset % Chunk::SIZE)))
+    }
+
+    fn chunk(&self, point: CoordPair) -> Result<(&Chunk, usize), AccessError> {
+        let (index, flat_index) = self.locate(point)?;
+        let chunk =
+            self.chunks.get(&index).ok_or(AccessError::UnloadedChunk(index))?;
+        Ok((chunk, flat_index))
+    }
+
+    fn chunk_mut(
+        &mut self,
+        point: CoordPair,
+    ) -> Result<(&mut Chunk, usize), AccessError> {
+        let (index, flat_index) = self.locate(point)?;
+        let chunk = self
+            .chunks
+            .get_mut(&index)
+            .ok_or(AccessError::UnloadedChunk(index))?;
+        Ok((chunk, flat_index))
     }
 }
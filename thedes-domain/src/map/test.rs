@@ -0,0 +1,75 @@
+use crate::{
+    chunk::Chunk,
+    geometry::{CoordPair, Rect},
+    item::{ItemKind, ItemStack},
+};
+
+use super::Map;
+
+fn map() -> Map {
+    let rect = Rect {
+        top_left: CoordPair { y: 0, x: 0 },
+        size: CoordPair { y: 100, x: 100 },
+    };
+    Map::new(rect).unwrap()
+}
+
+#[test]
+fn ground_is_empty_by_default() {
+    let map = map();
+    assert!(map.ground_items(CoordPair { y: 3, x: 4 }).unwrap().is_empty());
+    assert!(map.ground_items(CoordPair { y: 300, x: 4 }).is_err());
+}
+
+#[test]
+fn put_merges_stacks_of_same_kind() {
+    let mut map = map();
+    let point = CoordPair { y: 3, x: 4 };
+    map.put_ground_items(point, ItemStack::new(ItemKind::Berry, 10).unwrap())
+        .unwrap();
+    map.put_ground_items(point, ItemStack::new(ItemKind::Stone, 2).unwrap())
+        .unwrap();
+    map.put_ground_items(point, ItemStack::new(ItemKind::Berry, 10).unwrap())
+        .unwrap();
+    assert_eq!(
+        map.ground_items(point).unwrap(),
+        &[
+            ItemStack::new(ItemKind::Berry, 16).unwrap(),
+            ItemStack::new(ItemKind::Stone, 2).unwrap(),
+            ItemStack::new(ItemKind::Berry, 4).unwrap(),
+        ],
+    );
+}
+
+#[test]
+fn take_clears_tile() {
+    let mut map = map();
+    let point = CoordPair { y: 3, x: 4 };
+    let stack = ItemStack::new(ItemKind::Wood, 5).unwrap();
+    map.put_ground_items(point, stack).unwrap();
+    assert_eq!(map.take_ground_items(point).unwrap(), vec![stack]);
+    assert!(map.ground_items(point).unwrap().is_empty());
+}
+
+#[test]
+fn largest_map_is_capped_by_coord() {
+    let rect = Rect {
+        top_left: CoordPair { y: 0, x: 0 },
+        size: CoordPair { y: u16::MAX, x: u16::MAX },
+    };
+    let mut map = Map::new_lazy(rect, 1).unwrap();
+    assert_eq!(map.chunk_grid_size(), CoordPair { y: 2048, x: 2048 });
+
+    let last = CoordPair { y: 2047, x: 2047 };
+    assert_eq!(map.chunk_rect(last).unwrap().size, CoordPair { y: 31, x: 31 });
+    map.load_chunk(last, Chunk::new()).unwrap();
+    let corner = CoordPair { y: u16::MAX - 1, x: u16::MAX - 1 };
+    assert!(map.is_loaded(corner));
+    assert_eq!(map.loaded_chunks().count(), 1);
+
+    let beyond = Rect {
+        top_left: CoordPair { y: 1, x: 0 },
+        size: CoordPair { y: u16::MAX, x: u16::MAX },
+    };
+    assert!(Map::new_lazy(beyond, 1).is_err());
+}
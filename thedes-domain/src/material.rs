@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::block::PlaceableBlock;
+
+#[cfg(test)]
+mod test;
+
+pub type MaterialCount = u32;
+
+#[derive(Debug, Error)]
+#[error("Not enough {block:?} to build, only {available} available")]
+pub struct NotEnoughMaterial {
+    pub block: PlaceableBlock,
+    pub available: MaterialCount,
+}
+
+/// Blocks dug by the player, kept to be built elsewhere.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+pub struct Materials {
+    counts: [MaterialCount; PlaceableBlock::COUNT],
+    selected: Option<PlaceableBlock>,
+}
+
+impl Materials {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn count(&self, block: PlaceableBlock) -> MaterialCount {
+        self.counts[block as usize]
+    }
+
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = (PlaceableBlock, MaterialCount)> {
+        PlaceableBlock::ALL
+            .into_iter()
+            .map(|block| (block, self.count(block)))
+            .filter(|(_, count)| *count > 0)
+    }
+
+    /// Material spent when building.
+    pub fn selected(&self) -> Option<PlaceableBlock> {
+        self.selected
+    }
+
+    /// Selects the next material in stock after the current selection,
+    /// wrapping around.
+    pub fn select_next(&mut self) -> Option<PlaceableBlock> {
+        let start = self.selected.map_or(0, |block| block as usize + 1);
+        self.selected = (0 .. PlaceableBlock::COUNT)
+            .map(|offset| {
+                PlaceableBlock::ALL[(start + offset) % PlaceableBlock::COUNT]
+            })
+            .find(|block| self.count(*block) > 0);
+        self.selected
+    }
+
+    /// The first material ever added becomes the selected one.
+    pub(crate) fn add(&mut self, block: PlaceableBlock) {
+        let count = &mut self.counts[block as usize];
+        *count = count.saturating_add(1);
+        if self.selected.is_none() {
+            self.selected = Some(block);
+        }
+    }
+
+    pub(crate) fn spend(
+        &mut self,
+        block: PlaceableBlock,
+    ) -> Result<(), NotEnoughMaterial> {
+        let count = &mut self.counts[block as usize];
+        if *count == 0 {
+            Err(NotEnoughMaterial { block, available: *count })?
+        }
+        *count -= 1;
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use crate::block::PlaceableBlock;
+
+use super::Materials;
+
+#[test]
+fn first_material_is_selected() {
+    let mut materials = Materials::new();
+    assert_eq!(materials.selected(), None);
+    materials.add(PlaceableBlock::Rock);
+    materials.add(PlaceableBlock::Wall);
+    assert_eq!(materials.selected(), Some(PlaceableBlock::Rock));
+    assert_eq!(materials.count(PlaceableBlock::Rock), 1);
+}
+
+#[test]
+fn spend_fails_without_stock() {
+    let mut materials = Materials::new();
+    materials.add(PlaceableBlock::Wall);
+    materials.spend(PlaceableBlock::Wall).unwrap();
+    let error = materials.spend(PlaceableBlock::Wall).unwrap_err();
+    assert_eq!(error.block, PlaceableBlock::Wall);
+    assert_eq!(error.available, 0);
+}
+
+#[test]
+fn select_next_skips_missing_and_wraps() {
+    let mut materials = Materials::new();
+    materials.add(PlaceableBlock::Wall);
+    materials.add(PlaceableBlock::ClosedDoor);
+    assert_eq!(materials.select_next(), Some(PlaceableBlock::ClosedDoor));
+    assert_eq!(materials.select_next(), Some(PlaceableBlock::Wall));
+    materials.spend(PlaceableBlock::Wall).unwrap();
+    assert_eq!(materials.select_next(), Some(PlaceableBlock::ClosedDoor));
+    materials.spend(PlaceableBlock::ClosedDoor).unwrap();
+    assert_eq!(materials.select_next(), None);
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(
@@ -27,6 +29,16 @@ impl Biome {
         [Self::Plains, Self::Desert, Self::Wasteland];
 }
 
+impl fmt::Display for Biome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Plains => "plains",
+            Self::Desert => "desert",
+            Self::Wasteland => "wasteland",
+        })
+    }
+}
+
 #[derive(
     Debug,
     Clone,
@@ -1,7 +1,13 @@
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 use thedes_geometry::orientation::Direction;
 
-use crate::geometry::CoordPair;
+use crate::{
+    effect::{EffectKind, StatusEffect, StatusEffects},
+    geometry::{Coord, CoordPair},
+    stat::{Stat, StatValue},
+};
 
 pub use thedes_entity::compact::InvalidId;
 
@@ -11,6 +17,109 @@ pub type Id = thedes_entity::compact::ShortId;
 
 pub type IdShortageError = thedes_entity::compact::NonShortId;
 
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+pub enum Species {
+    #[default]
+    Bat,
+    Wolf,
+    Scorpion,
+    Snake,
+    Ghoul,
+}
+
+impl Species {
+    pub const COUNT: usize = 5;
+
+    pub const ALL: [Self; Self::COUNT] =
+        [Self::Bat, Self::Wolf, Self::Scorpion, Self::Snake, Self::Ghoul];
+
+    pub const MAX_AGGRESSION: u8 = 100;
+
+    pub const NORMAL_SPEED: Coord = 100;
+
+    pub const fn hp(self) -> Stat {
+        let max = match self {
+            Self::Bat => 3,
+            Self::Wolf => 8,
+            Self::Scorpion => 6,
+            Self::Snake => 4,
+            Self::Ghoul => 12,
+        };
+        Stat::new(max, max)
+    }
+
+    pub const fn damage(self) -> StatValue {
+        match self {
+            Self::Bat => 1,
+            Self::Wolf => 2,
+            Self::Scorpion => 3,
+            Self::Snake => 2,
+            Self::Ghoul => 3,
+        }
+    }
+
+    /// Relative to [`Self::NORMAL_SPEED`].
+    pub const fn speed(self) -> Coord {
+        match self {
+            Self::Bat => 150,
+            Self::Wolf => 120,
+            Self::Scorpion => 80,
+            Self::Snake => 100,
+            Self::Ghoul => 60,
+        }
+    }
+
+    /// Effect inflicted on the player by this species' attacks.
+    pub const fn attack_effect(self) -> Option<StatusEffect> {
+        match self {
+            Self::Bat | Self::Wolf => None,
+            Self::Scorpion => {
+                Some(StatusEffect::new(EffectKind::Poison, 2, 10000))
+            },
+            Self::Snake => {
+                Some(StatusEffect::new(EffectKind::Poison, 1, 15000))
+            },
+            Self::Ghoul => Some(StatusEffect::new(EffectKind::Slow, 1, 12500)),
+        }
+    }
+
+    /// Chance out of [`Self::MAX_AGGRESSION`] of attacking or chasing the
+    /// player instead of wandering.
+    pub const fn aggression(self) -> u8 {
+        match self {
+            Self::Bat => 40,
+            Self::Wolf => 70,
+            Self::Scorpion => 60,
+            Self::Snake => 30,
+            Self::Ghoul => 90,
+        }
+    }
+}
+
+impl fmt::Display for Species {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Bat => "bat",
+            Self::Wolf => "wolf",
+            Self::Scorpion => "scorpion",
+            Self::Snake => "snake",
+            Self::Ghoul => "ghoul",
+        })
+    }
+}
+
 #[derive(
     Debug,
     Clone,
@@ -50,16 +159,46 @@ impl MonsterPosition {
     }
 }
 
+/// Record of a monster killed, as opposed to merely vanishing.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub struct Kill {
+    pub species: Species,
+    pub point: CoordPair,
+}
+
 #[derive(
     Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
 )]
 pub struct Monster {
     position: MonsterPosition,
+    species: Species,
+    hp: Stat,
+    effects: StatusEffects,
 }
 
 impl Monster {
-    pub fn new(position: MonsterPosition) -> Self {
-        Self { position }
+    pub fn new(position: MonsterPosition, species: Species) -> Self {
+        Self {
+            position,
+            species,
+            hp: species.hp(),
+            effects: StatusEffects::new(),
+        }
+    }
+
+    pub fn species(&self) -> Species {
+        self.species
     }
 
     pub fn position(&self) -> MonsterPosition {
@@ -69,4 +208,24 @@ impl Monster {
     pub(crate) fn position_mut(&mut self) -> &mut MonsterPosition {
         &mut self.position
     }
+
+    pub fn hp(&self) -> Stat {
+        self.hp
+    }
+
+    pub(crate) fn damage(&mut self, amount: StatValue) {
+        self.hp.decrease_value(amount);
+    }
+
+    pub(crate) fn heal(&mut self, amount: StatValue) {
+        self.hp.increase_value(amount);
+    }
+
+    pub fn effects(&self) -> &StatusEffects {
+        &self.effects
+    }
+
+    pub(crate) fn effects_mut(&mut self) -> &mut StatusEffects {
+        &mut self.effects
+    }
 }
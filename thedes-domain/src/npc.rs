@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use thedes_geometry::orientation::Direction;
+
+use crate::geometry::{Coord, CoordPair};
+
+pub use thedes_entity::compact::InvalidId;
+
+pub type Registry = thedes_entity::compact::Registry<Npc>;
+
+pub type Id = thedes_entity::compact::ShortId;
+
+pub type IdShortageError = thedes_entity::compact::NonShortId;
+
+/// A non-hostile character, which wanders around its home and can be
+/// talked to.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Npc {
+    name: String,
+    dialogue: String,
+    body: CoordPair,
+    facing: Direction,
+    home: CoordPair,
+    wander_radius: Coord,
+}
+
+impl Npc {
+    pub const DEFAULT_WANDER_RADIUS: Coord = 6;
+
+    /// The NPC's home is where it is created.
+    pub fn new(
+        name: impl Into<String>,
+        dialogue: impl Into<String>,
+        body: CoordPair,
+        facing: Direction,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            dialogue: dialogue.into(),
+            body,
+            facing,
+            home: body,
+            wander_radius: Self::DEFAULT_WANDER_RADIUS,
+        }
+    }
+
+    pub fn with_wander_radius(self, radius: Coord) -> Self {
+        Self { wander_radius: radius, ..self }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Name of the NPC's dialogue in the dialogue assets.
+    pub fn dialogue(&self) -> &str {
+        &self.dialogue
+    }
+
+    pub fn body(&self) -> CoordPair {
+        self.body
+    }
+
+    pub(crate) fn set_body(&mut self, body: CoordPair) {
+        self.body = body;
+    }
+
+    pub fn facing(&self) -> Direction {
+        self.facing
+    }
+
+    pub(crate) fn face(&mut self, facing: Direction) {
+        self.facing = facing;
+    }
+
+    pub fn home(&self) -> CoordPair {
+        self.home
+    }
+
+    pub fn wander_radius(&self) -> Coord {
+        self.wander_radius
+    }
+
+    /// Whether the NPC may wander to the given point, i.e. whether it is
+    /// close enough to its home.
+    pub fn can_wander_to(&self, point: CoordPair) -> bool {
+        let distance =
+            CoordPair::from_axes(|axis| point[axis].abs_diff(self.home[axis]));
+        distance.x <= self.wander_radius && distance.y <= self.wander_radius
+    }
+}
@@ -0,0 +1,98 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, hash_map},
+};
+
+use thedes_geometry::orientation::Direction;
+
+use crate::geometry::{Coord, CoordPair, Rect};
+
+#[cfg(test)]
+mod test;
+
+fn distance(a: CoordPair, b: CoordPair) -> u32 {
+    let diff = a.zip2_with(b, |a, b| u32::from(a.abs_diff(b)));
+    diff.y + diff.x
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Visit {
+    cost: u32,
+    first_step: Option<Direction>,
+}
+
+/// Searches for the shortest path from `start` to `goal` using A*, visiting
+/// only points within `radius` of `start` (on both axes) and inside `rect`.
+///
+/// Yields the first step of the path found. When `goal` cannot be reached
+/// inside the search area, the first step towards the visited point closest
+/// to `goal` is yielded instead. The goal itself is always considered
+/// passable.
+pub fn first_step<F, E>(
+    rect: Rect,
+    start: CoordPair,
+    goal: CoordPair,
+    radius: Coord,
+    mut is_passable: F,
+) -> Result<Option<Direction>, E>
+where
+    F: FnMut(CoordPair) -> Result<bool, E>,
+{
+    let in_range = |point: CoordPair| {
+        point
+            .zip2(start)
+            .all(|(coord, center)| coord.abs_diff(center) <= radius)
+    };
+
+    let mut visits =
+        HashMap::from([(start, Visit { cost: 0, first_step: None })]);
+    let mut frontier = BinaryHeap::from([Reverse((
+        distance(start, goal),
+        distance(start, goal),
+        start,
+    ))]);
+    let mut closest = (distance(start, goal), 0, None);
+
+    while let Some(Reverse((_, heuristic, point))) = frontier.pop() {
+        let visit = visits[&point];
+        if point == goal {
+            return Ok(visit.first_step);
+        }
+        if (heuristic, visit.cost) < (closest.0, closest.1) {
+            closest = (heuristic, visit.cost, visit.first_step);
+        }
+
+        for direction in Direction::ALL {
+            let Ok(neighbor) = rect.checked_move_point_unit(point, direction)
+            else {
+                continue;
+            };
+            if !in_range(neighbor) {
+                continue;
+            }
+            let cost = visit.cost + 1;
+            let new_visit = Visit {
+                cost,
+                first_step: visit.first_step.or(Some(direction)),
+            };
+            match visits.entry(neighbor) {
+                hash_map::Entry::Occupied(mut entry) => {
+                    if entry.get().cost <= cost {
+                        continue;
+                    }
+                    entry.insert(new_visit);
+                },
+                hash_map::Entry::Vacant(entry) => {
+                    if neighbor != goal && !is_passable(neighbor)? {
+                        continue;
+                    }
+                    entry.insert(new_visit);
+                },
+            }
+            let heuristic = distance(neighbor, goal);
+            frontier.push(Reverse((cost + heuristic, heuristic, neighbor)));
+        }
+    }
+
+    Ok(closest.2)
+}
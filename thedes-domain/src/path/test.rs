@@ -0,0 +1,101 @@
+use std::convert::Infallible;
+
+use thedes_geometry::orientation::Direction;
+
+use crate::geometry::{CoordPair, Rect};
+
+use super::first_step;
+
+fn rect() -> Rect {
+    Rect {
+        top_left: CoordPair { y: 0, x: 0 },
+        size: CoordPair { y: 10, x: 10 },
+    }
+}
+
+#[test]
+fn straight_line() {
+    let step = first_step(
+        rect(),
+        CoordPair { y: 5, x: 2 },
+        CoordPair { y: 5, x: 7 },
+        10,
+        |_| Ok::<_, Infallible>(true),
+    )
+    .unwrap();
+    assert_eq!(step, Some(Direction::Right));
+}
+
+#[test]
+fn already_at_goal() {
+    let step = first_step(
+        rect(),
+        CoordPair { y: 5, x: 2 },
+        CoordPair { y: 5, x: 2 },
+        10,
+        |_| Ok::<_, Infallible>(true),
+    )
+    .unwrap();
+    assert_eq!(step, None);
+}
+
+#[test]
+fn goes_around_wall() {
+    // Wall on column 4 from row 0 through row 8, open only at row 9.
+    let step = first_step(
+        rect(),
+        CoordPair { y: 2, x: 3 },
+        CoordPair { y: 2, x: 6 },
+        10,
+        |point| Ok::<_, Infallible>(point.x != 4 || point.y == 9),
+    )
+    .unwrap();
+    assert_eq!(step, Some(Direction::Down));
+}
+
+#[test]
+fn goal_itself_may_be_impassable() {
+    let goal = CoordPair { y: 3, x: 3 };
+    let step =
+        first_step(rect(), CoordPair { y: 3, x: 4 }, goal, 10, |point| {
+            Ok::<_, Infallible>(point != goal)
+        })
+        .unwrap();
+    assert_eq!(step, Some(Direction::Left));
+}
+
+#[test]
+fn out_of_radius_approaches_closest() {
+    let step = first_step(
+        rect(),
+        CoordPair { y: 0, x: 0 },
+        CoordPair { y: 0, x: 9 },
+        2,
+        |_| Ok::<_, Infallible>(true),
+    )
+    .unwrap();
+    assert_eq!(step, Some(Direction::Right));
+}
+
+#[test]
+fn enclosed_does_not_move() {
+    let start = CoordPair { y: 5, x: 5 };
+    let step =
+        first_step(rect(), start, CoordPair { y: 0, x: 0 }, 10, |point| {
+            Ok::<_, Infallible>(point == start)
+        })
+        .unwrap();
+    assert_eq!(step, None);
+}
+
+#[test]
+fn propagates_errors() {
+    let result = first_step(
+        rect(),
+        CoordPair { y: 5, x: 5 },
+        CoordPair { y: 0, x: 0 },
+        10,
+        |_| Err("failed"),
+    );
+    assert_eq!(result, Err("failed"));
+}
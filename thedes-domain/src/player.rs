@@ -1,9 +1,17 @@
+use std::collections::BTreeSet;
+
 use serde::{Deserialize, Serialize};
 use thedes_geometry::orientation::Direction;
 use thiserror::Error;
 
 use crate::{
-    geometry::CoordPair,
+    effect::StatusEffects,
+    experience::{Experience, Level, XpValue},
+    geometry::{Coord, CoordPair},
+    inventory::Inventory,
+    item::{Equipment, ItemStack},
+    material::Materials,
+    matter::Biome,
     stat::{Stat, StatValue},
 };
 
@@ -62,13 +70,44 @@ impl PlayerPosition {
 pub struct Player {
     position: PlayerPosition,
     hp: Stat,
+    hunger: Stat,
+    stamina: Stat,
+    inventory: Inventory,
+    equipment: Equipment,
+    experience: Experience,
+    discovered_biomes: BTreeSet<Biome>,
+    effects: StatusEffects,
+    materials: Materials,
 }
 
 impl Player {
     pub const DEFAULT_HP: Stat = Stat::new(80, 80);
 
+    pub const DEFAULT_HUNGER: Stat = Stat::new(100, 100);
+
+    pub const DEFAULT_STAMINA: Stat = Stat::new(50, 50);
+
+    pub const INVENTORY_CAPACITY: usize = 20;
+
+    pub const BASE_ATTACK: StatValue = 1;
+
+    pub const SIGHT_RADIUS: Coord = 20;
+
+    pub const HP_PER_LEVEL: StatValue = 10;
+
     pub fn new(position: PlayerPosition, hp: Stat) -> Self {
-        Self { position, hp }
+        Self {
+            position,
+            hp,
+            hunger: Self::DEFAULT_HUNGER,
+            stamina: Self::DEFAULT_STAMINA,
+            inventory: Inventory::new(Self::INVENTORY_CAPACITY),
+            equipment: Equipment::new(),
+            experience: Experience::new(),
+            discovered_biomes: BTreeSet::new(),
+            effects: StatusEffects::new(),
+            materials: Materials::new(),
+        }
     }
 
     pub fn position(&self) -> &PlayerPosition {
@@ -83,6 +122,38 @@ impl Player {
         self.hp
     }
 
+    pub fn hunger(&self) -> Stat {
+        self.hunger
+    }
+
+    pub(crate) fn starve(&mut self, amount: StatValue) {
+        self.hunger.decrease_value(amount);
+    }
+
+    pub(crate) fn feed(&mut self, amount: StatValue) {
+        self.hunger.increase_value(amount);
+    }
+
+    pub fn stamina(&self) -> Stat {
+        self.stamina
+    }
+
+    pub(crate) fn spend_stamina(&mut self, amount: StatValue) {
+        self.stamina.decrease_value(amount);
+    }
+
+    pub(crate) fn recover_stamina(&mut self, amount: StatValue) {
+        self.stamina.increase_value(amount);
+    }
+
+    pub fn sight_radius(&self) -> Coord {
+        Self::SIGHT_RADIUS
+    }
+
+    pub fn attack_damage(&self) -> StatValue {
+        Self::BASE_ATTACK + self.equipment.attack_bonus()
+    }
+
     pub fn damage(&mut self, amount: StatValue) {
         self.hp.decrease_value(amount);
     }
@@ -90,4 +161,73 @@ impl Player {
     pub fn heal(&mut self, amount: StatValue) {
         self.hp.increase_value(amount);
     }
+
+    pub fn inventory(&self) -> &Inventory {
+        &self.inventory
+    }
+
+    pub(crate) fn inventory_mut(&mut self) -> &mut Inventory {
+        &mut self.inventory
+    }
+
+    pub fn equipment(&self) -> &Equipment {
+        &self.equipment
+    }
+
+    pub(crate) fn equipment_mut(&mut self) -> &mut Equipment {
+        &mut self.equipment
+    }
+
+    pub fn experience(&self) -> Experience {
+        self.experience
+    }
+
+    pub(crate) fn gain_experience(&mut self, amount: XpValue) -> Level {
+        let levels = self.experience.gain(amount);
+        let hp_gain = Self::HP_PER_LEVEL.saturating_mul(levels);
+        self.hp.increase_max(hp_gain);
+        self.hp.increase_value(hp_gain);
+        levels
+    }
+
+    pub fn has_discovered(&self, biome: Biome) -> bool {
+        self.discovered_biomes.contains(&biome)
+    }
+
+    pub fn effects(&self) -> &StatusEffects {
+        &self.effects
+    }
+
+    pub(crate) fn effects_mut(&mut self) -> &mut StatusEffects {
+        &mut self.effects
+    }
+
+    pub fn materials(&self) -> &Materials {
+        &self.materials
+    }
+
+    pub(crate) fn materials_mut(&mut self) -> &mut Materials {
+        &mut self.materials
+    }
+
+    pub(crate) fn discover_biome(&mut self, biome: Biome) -> bool {
+        self.discovered_biomes.insert(biome)
+    }
+
+    pub fn discovered_biomes(&self) -> impl Iterator<Item = Biome> + '_ {
+        self.discovered_biomes.iter().copied()
+    }
+
+    /// Brings the player back at half health, rested and free of effects,
+    /// but stripped of everything they carried, which is returned.
+    pub(crate) fn revive(&mut self) -> Vec<ItemStack> {
+        self.hp.set_value((self.hp.curr_max() / 2).max(1));
+        let min_hunger = self.hunger.curr_max() / 2;
+        if self.hunger.value() < min_hunger {
+            self.hunger.set_value(min_hunger);
+        }
+        self.stamina.set_value(self.stamina.curr_max());
+        self.effects = StatusEffects::new();
+        self.inventory.take_all()
+    }
 }
@@ -0,0 +1,279 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    experience::XpValue,
+    geometry::{Coord, CoordPair},
+    item::ItemStack,
+    map::MapId,
+    matter::Biome,
+    monster::Species,
+    player::Player,
+    time::WorldTime,
+};
+
+#[cfg(test)]
+mod test;
+
+/// What must be done to complete a quest.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub enum Objective {
+    /// Stay alive for the given event ticks after accepting the quest.
+    Survive {
+        ticks: u64,
+    },
+    /// Get the player's head within the radius around the point.
+    Reach {
+        map: MapId,
+        point: CoordPair,
+        radius: Coord,
+    },
+    Explore(Biome),
+    Slay {
+        species: Species,
+        count: u32,
+    },
+}
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Survive { ticks } => write!(
+                f,
+                "survive for {} minutes",
+                ticks.div_ceil(WorldTime::EPOCHS_PER_MINUTE),
+            ),
+            Self::Reach { point, .. } => {
+                write!(f, "reach {}, {}", point.x, point.y)
+            },
+            Self::Explore(biome) => write!(f, "explore the {biome}"),
+            Self::Slay { species, count } => {
+                write!(f, "slay {count} {species}")
+            },
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub struct Reward {
+    pub xp: XpValue,
+    pub items: Vec<ItemStack>,
+}
+
+impl fmt::Display for Reward {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} XP", self.xp)?;
+        for stack in &self.items {
+            write!(f, ", {stack}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Quest {
+    name: String,
+    objective: Objective,
+    reward: Reward,
+}
+
+impl Quest {
+    pub fn new(
+        name: impl Into<String>,
+        objective: Objective,
+        reward: Reward,
+    ) -> Self {
+        Self { name: name.into(), objective, reward }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn objective(&self) -> Objective {
+        self.objective
+    }
+
+    pub fn reward(&self) -> &Reward {
+        &self.reward
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub enum QuestStatus {
+    Active,
+    /// Completed at the given event epoch.
+    Completed(u64),
+}
+
+/// Where the player stands when quests are checked.
+#[derive(Debug, Clone, Copy)]
+pub struct QuestContext<'a> {
+    pub now: u64,
+    pub map: MapId,
+    pub player: &'a Player,
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct QuestEntry {
+    quest: Quest,
+    accepted_at: u64,
+    slain: u32,
+    status: QuestStatus,
+}
+
+impl QuestEntry {
+    pub fn quest(&self) -> &Quest {
+        &self.quest
+    }
+
+    pub fn accepted_at(&self) -> u64 {
+        self.accepted_at
+    }
+
+    pub fn status(&self) -> QuestStatus {
+        self.status
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.status == QuestStatus::Active
+    }
+
+    /// How much of the objective is done, out of its goal.
+    pub fn progress(&self, context: QuestContext) -> (u64, u64) {
+        match self.quest.objective {
+            Objective::Survive { ticks } => {
+                let elapsed = context.now.saturating_sub(self.accepted_at);
+                (elapsed.min(ticks), ticks)
+            },
+            Objective::Slay { count, .. } => {
+                (u64::from(self.slain.min(count)), u64::from(count))
+            },
+            Objective::Reach { .. } | Objective::Explore(_) => {
+                (u64::from(self.is_fulfilled(context)), 1)
+            },
+        }
+    }
+
+    pub fn is_fulfilled(&self, context: QuestContext) -> bool {
+        match self.quest.objective {
+            Objective::Survive { ticks } => {
+                context.now.saturating_sub(self.accepted_at) >= ticks
+            },
+            Objective::Reach { map, point, radius } => {
+                let head = context.player.position().head();
+                map == context.map
+                    && head
+                        .zip2(point)
+                        .all(|(actual, goal)| actual.abs_diff(goal) <= radius)
+            },
+            Objective::Explore(biome) => context.player.has_discovered(biome),
+            Objective::Slay { count, .. } => self.slain >= count,
+        }
+    }
+}
+
+/// Every quest the player has accepted, active or completed.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub struct QuestLog {
+    entries: Vec<QuestEntry>,
+}
+
+impl QuestLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn entries(&self) -> &[QuestEntry] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub(crate) fn accept(&mut self, quest: Quest, now: u64) {
+        self.entries.push(QuestEntry {
+            quest,
+            accepted_at: now,
+            slain: 0,
+            status: QuestStatus::Active,
+        });
+    }
+
+    pub(crate) fn record_kill(&mut self, killed: Species) {
+        for entry in &mut self.entries {
+            if entry.is_active()
+                && let Objective::Slay { species, .. } = entry.quest.objective
+                && species == killed
+            {
+                entry.slain = entry.slain.saturating_add(1);
+            }
+        }
+    }
+
+    /// Marks fulfilled quests as completed, yielding the rewards to be
+    /// granted.
+    pub(crate) fn complete_fulfilled(
+        &mut self,
+        context: QuestContext,
+    ) -> Vec<Reward> {
+        let mut rewards = Vec::new();
+        for entry in &mut self.entries {
+            if entry.is_active() && entry.is_fulfilled(context) {
+                entry.status = QuestStatus::Completed(context.now);
+                rewards.push(entry.quest.reward.clone());
+            }
+        }
+        rewards
+    }
+}
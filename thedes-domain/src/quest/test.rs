@@ -0,0 +1,86 @@
+use thedes_geometry::orientation::Direction;
+
+use crate::{
+    geometry::CoordPair,
+    matter::Biome,
+    monster::Species,
+    player::{Player, PlayerPosition},
+};
+
+use super::{Objective, Quest, QuestContext, QuestLog, QuestStatus, Reward};
+
+fn player_at(head: CoordPair) -> Player {
+    let position = PlayerPosition::new(head, Direction::Right).unwrap();
+    Player::new(position, Player::DEFAULT_HP)
+}
+
+fn reward(xp: u32) -> Reward {
+    Reward { xp, items: Vec::new() }
+}
+
+#[test]
+fn survive_completes_after_ticks() {
+    let player = player_at(CoordPair { y: 5, x: 5 });
+    let mut log = QuestLog::new();
+    log.accept(
+        Quest::new("Endure", Objective::Survive { ticks: 100 }, reward(10)),
+        50,
+    );
+    let context = QuestContext { now: 120, map: 0, player: &player };
+    assert_eq!(log.entries()[0].progress(context), (70, 100));
+    assert!(log.complete_fulfilled(context).is_empty());
+    let context = QuestContext { now: 150, ..context };
+    assert_eq!(log.complete_fulfilled(context), vec![reward(10)]);
+    assert_eq!(log.entries()[0].status(), QuestStatus::Completed(150));
+    assert!(log.complete_fulfilled(context).is_empty());
+}
+
+#[test]
+fn reach_requires_map_and_radius() {
+    let objective = Objective::Reach {
+        map: 1,
+        point: CoordPair { y: 10, x: 10 },
+        radius: 2,
+    };
+    let mut log = QuestLog::new();
+    log.accept(Quest::new("Go", objective, reward(5)), 0);
+    let far = player_at(CoordPair { y: 10, x: 13 });
+    let near = player_at(CoordPair { y: 12, x: 8 });
+    let entry = &log.entries()[0];
+    assert!(!entry.is_fulfilled(QuestContext { now: 0, map: 1, player: &far }));
+    assert!(!entry.is_fulfilled(QuestContext {
+        now: 0,
+        map: 0,
+        player: &near
+    }));
+    assert!(entry.is_fulfilled(QuestContext { now: 0, map: 1, player: &near }));
+}
+
+#[test]
+fn explore_checks_discovered_biomes() {
+    let mut player = player_at(CoordPair { y: 5, x: 5 });
+    let mut log = QuestLog::new();
+    log.accept(
+        Quest::new("Sand", Objective::Explore(Biome::Desert), reward(5)),
+        0,
+    );
+    let context = QuestContext { now: 0, map: 0, player: &player };
+    assert!(!log.entries()[0].is_fulfilled(context));
+    player.discover_biome(Biome::Desert);
+    let context = QuestContext { now: 0, map: 0, player: &player };
+    assert!(log.entries()[0].is_fulfilled(context));
+}
+
+#[test]
+fn slay_counts_matching_kills() {
+    let player = player_at(CoordPair { y: 5, x: 5 });
+    let objective = Objective::Slay { species: Species::Wolf, count: 2 };
+    let mut log = QuestLog::new();
+    log.accept(Quest::new("Hunt", objective, reward(20)), 0);
+    let context = QuestContext { now: 0, map: 0, player: &player };
+    log.record_kill(Species::Wolf);
+    log.record_kill(Species::Bat);
+    assert_eq!(log.entries()[0].progress(context), (1, 2));
+    log.record_kill(Species::Wolf);
+    assert_eq!(log.complete_fulfilled(context), vec![reward(20)]);
+}
@@ -0,0 +1,127 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+mod test;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub enum DayPhase {
+    Night,
+    Dawn,
+    Day,
+    Dusk,
+}
+
+impl DayPhase {
+    pub const COUNT: usize = 4;
+
+    pub const ALL: [Self; Self::COUNT] =
+        [Self::Night, Self::Dawn, Self::Day, Self::Dusk];
+}
+
+impl fmt::Display for DayPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Night => "night",
+            Self::Dawn => "dawn",
+            Self::Day => "day",
+            Self::Dusk => "dusk",
+        })
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub struct WorldTime {
+    epoch: u64,
+}
+
+impl WorldTime {
+    pub const MINUTES_PER_DAY: u64 = 24 * 60;
+
+    pub const EPOCHS_PER_MINUTE: u64 = 2500;
+
+    pub const EPOCHS_PER_DAY: u64 =
+        Self::MINUTES_PER_DAY * Self::EPOCHS_PER_MINUTE;
+
+    pub const START_MINUTE: u64 = 8 * 60;
+
+    pub const DAWN_START: u64 = 5 * 60;
+
+    pub const DAY_START: u64 = 7 * 60;
+
+    pub const DUSK_START: u64 = 18 * 60;
+
+    pub const NIGHT_START: u64 = 20 * 60;
+
+    pub const fn from_epoch(epoch: u64) -> Self {
+        Self { epoch }
+    }
+
+    pub const fn epoch(self) -> u64 {
+        self.epoch
+    }
+
+    pub const fn day(self) -> u64 {
+        self.total_minutes() / Self::MINUTES_PER_DAY
+    }
+
+    pub const fn minute_of_day(self) -> u64 {
+        self.total_minutes() % Self::MINUTES_PER_DAY
+    }
+
+    pub const fn hour(self) -> u64 {
+        self.minute_of_day() / 60
+    }
+
+    pub const fn minute(self) -> u64 {
+        self.minute_of_day() % 60
+    }
+
+    pub const fn phase(self) -> DayPhase {
+        let minute = self.minute_of_day();
+        if minute < Self::DAWN_START {
+            DayPhase::Night
+        } else if minute < Self::DAY_START {
+            DayPhase::Dawn
+        } else if minute < Self::DUSK_START {
+            DayPhase::Day
+        } else if minute < Self::NIGHT_START {
+            DayPhase::Dusk
+        } else {
+            DayPhase::Night
+        }
+    }
+
+    const fn total_minutes(self) -> u64 {
+        self.epoch / Self::EPOCHS_PER_MINUTE + Self::START_MINUTE
+    }
+}
+
+impl fmt::Display for WorldTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour(), self.minute())
+    }
+}
@@ -0,0 +1,37 @@
+use super::{DayPhase, WorldTime};
+
+fn at(hour: u64, minute: u64) -> WorldTime {
+    let minutes = (hour * 60 + minute + WorldTime::MINUTES_PER_DAY
+        - WorldTime::START_MINUTE)
+        % WorldTime::MINUTES_PER_DAY;
+    WorldTime::from_epoch(minutes * WorldTime::EPOCHS_PER_MINUTE)
+}
+
+#[test]
+fn starts_in_the_morning() {
+    let time = WorldTime::from_epoch(0);
+    assert_eq!(time.hour(), 8);
+    assert_eq!(time.minute(), 0);
+    assert_eq!(time.day(), 0);
+    assert_eq!(time.phase(), DayPhase::Day);
+    assert_eq!(time.to_string(), "08:00");
+}
+
+#[test]
+fn phases() {
+    assert_eq!(at(0, 0).phase(), DayPhase::Night);
+    assert_eq!(at(4, 59).phase(), DayPhase::Night);
+    assert_eq!(at(5, 0).phase(), DayPhase::Dawn);
+    assert_eq!(at(7, 0).phase(), DayPhase::Day);
+    assert_eq!(at(17, 59).phase(), DayPhase::Day);
+    assert_eq!(at(18, 0).phase(), DayPhase::Dusk);
+    assert_eq!(at(20, 0).phase(), DayPhase::Night);
+    assert_eq!(at(23, 59).phase(), DayPhase::Night);
+}
+
+#[test]
+fn wraps_into_next_day() {
+    let time = WorldTime::from_epoch(WorldTime::EPOCHS_PER_DAY);
+    assert_eq!(time.day(), 1);
+    assert_eq!(time.hour(), 8);
+}
@@ -0,0 +1,184 @@
+use std::{collections::BTreeMap, fmt};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    geometry::{Coord, CoordPair},
+    map::MapId,
+    matter::Biome,
+};
+
+#[cfg(test)]
+mod test;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+pub enum Weather {
+    #[default]
+    Clear,
+    Rain,
+    Sandstorm,
+}
+
+impl Weather {
+    pub const COUNT: usize = 3;
+
+    pub const ALL: [Self; Self::COUNT] =
+        [Self::Clear, Self::Rain, Self::Sandstorm];
+
+    pub const fn is_possible_in(self, biome: Biome) -> bool {
+        match (self, biome) {
+            (Self::Clear, _) => true,
+            (Self::Rain, Biome::Plains | Biome::Wasteland) => true,
+            (Self::Sandstorm, Biome::Desert | Biome::Wasteland) => true,
+            (Self::Rain, Biome::Desert) => false,
+            (Self::Sandstorm, Biome::Plains) => false,
+        }
+    }
+
+    /// Weathers this one can change into in the given biome. Clear skies
+    /// turn into any weather possible there, and any other weather only ever
+    /// clears up.
+    pub fn transitions(self, biome: Biome) -> impl Iterator<Item = Self> {
+        Self::ALL.into_iter().filter(move |next| {
+            *next != self
+                && (self == Self::Clear || *next == Self::Clear)
+                && next.is_possible_in(biome)
+        })
+    }
+
+    pub const fn visibility_percent(self) -> u16 {
+        match self {
+            Self::Clear => 100,
+            Self::Rain => 60,
+            Self::Sandstorm => 30,
+        }
+    }
+
+    pub const fn movement_percent(self) -> u32 {
+        match self {
+            Self::Clear => 100,
+            Self::Rain => 80,
+            Self::Sandstorm => 50,
+        }
+    }
+}
+
+impl fmt::Display for Weather {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Clear => "clear",
+            Self::Rain => "rain",
+            Self::Sandstorm => "sandstorm",
+        })
+    }
+}
+
+/// Part of a biome inside of a square cell of a map, sharing the same
+/// weather. Biomes can be arbitrarily large on lazily generated maps, so
+/// cells keep every region, and the weather events touching it, local.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub struct WeatherRegion {
+    pub map: MapId,
+    /// Offset of the region's top left point from the map origin, divided by
+    /// [`WeatherRegion::SIZE`].
+    pub cell: CoordPair,
+    pub biome: Biome,
+}
+
+impl WeatherRegion {
+    pub const SIZE: Coord = 64;
+
+    pub fn containing(map: MapId, point: CoordPair, biome: Biome) -> Self {
+        Self { map, cell: point / Self::SIZE, biome }
+    }
+}
+
+fn serialize_regions<S>(
+    by_region: &BTreeMap<WeatherRegion, Weather>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_seq(by_region)
+}
+
+fn deserialize_regions<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<WeatherRegion, Weather>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let entries = Vec::<(WeatherRegion, Weather)>::deserialize(deserializer)?;
+    Ok(entries.into_iter().collect())
+}
+
+/// Weather of every region of every map. Regions are clear unless stated
+/// otherwise.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+pub struct WeatherState {
+    #[serde(
+        serialize_with = "serialize_regions",
+        deserialize_with = "deserialize_regions"
+    )]
+    by_region: BTreeMap<WeatherRegion, Weather>,
+}
+
+impl WeatherState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, region: WeatherRegion) -> Weather {
+        self.by_region.get(&region).copied().unwrap_or_default()
+    }
+
+    /// Weather at a point of the given biome, which is clear wherever the
+    /// weather of its region is not possible.
+    pub fn at(&self, map: MapId, point: CoordPair, biome: Biome) -> Weather {
+        let weather = self.get(WeatherRegion::containing(map, point, biome));
+        if weather.is_possible_in(biome) { weather } else { Weather::Clear }
+    }
+
+    pub(crate) fn set(&mut self, region: WeatherRegion, weather: Weather) {
+        if weather == Weather::Clear {
+            self.by_region.remove(&region);
+        } else {
+            self.by_region.insert(region, weather);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{geometry::CoordPair, matter::Biome};
+
+use super::{Weather, WeatherRegion, WeatherState};
+
+#[test]
+fn transitions_always_change_weather() {
+    for biome in Biome::ALL {
+        for weather in Weather::ALL {
+            let transitions: Vec<_> = weather.transitions(biome).collect();
+            assert!(!transitions.is_empty(), "{weather} in {biome:?}");
+            assert!(!transitions.contains(&weather));
+        }
+    }
+    assert_eq!(
+        Weather::Clear.transitions(Biome::Wasteland).collect::<Vec<_>>(),
+        [Weather::Rain, Weather::Sandstorm],
+    );
+    assert_eq!(
+        Weather::Rain.transitions(Biome::Desert).collect::<Vec<_>>(),
+        [Weather::Clear],
+    );
+}
+
+#[test]
+fn biome_regions_have_their_own_weather() {
+    let mut state = WeatherState::new();
+    let point = CoordPair { y: 10, x: 70 };
+    let region = WeatherRegion::containing(0, point, Biome::Plains);
+    assert_eq!(region.cell, CoordPair { y: 0, x: 1 });
+    state.set(region, Weather::Rain);
+    let wasteland = WeatherRegion::containing(0, point, Biome::Wasteland);
+    state.set(wasteland, Weather::Sandstorm);
+
+    assert_eq!(state.at(0, point, Biome::Plains), Weather::Rain);
+    assert_eq!(
+        state.at(0, CoordPair { y: 63, x: 127 }, Biome::Plains),
+        Weather::Rain
+    );
+    assert_eq!(state.at(0, point, Biome::Desert), Weather::Clear);
+    assert_eq!(state.at(0, point, Biome::Wasteland), Weather::Sandstorm);
+    assert_eq!(
+        state.at(0, CoordPair { y: 10, x: 10 }, Biome::Plains),
+        Weather::Clear
+    );
+    assert_eq!(state.at(1, point, Biome::Plains), Weather::Clear);
+
+    let json = serde_json::to_string(&state).unwrap();
+    assert_eq!(serde_json::from_str::<WeatherState>(&json).unwrap(), state);
+
+    state.set(region, Weather::Clear);
+    state.set(wasteland, Weather::Clear);
+    assert_eq!(state, WeatherState::new());
+}
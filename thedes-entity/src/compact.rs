@@ -196,8 +196,7 @@ impl<T> Registry<T> {
 
     pub fn remove(&mut self, id: impl Into<Id>) -> Result<T, InvalidId> {
         let id = id.into();
-        let index =
-            self.secondary_pos.get(&id).copied().ok_or(InvalidId(id))?;
+        let index = self.secondary_pos.remove(&id).ok_or(InvalidId(id))?;
         let last_index = self.primary.len() - 1;
         if index != last_index {
             self.primary.swap(index, last_index);
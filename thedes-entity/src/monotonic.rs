@@ -66,7 +66,7 @@ impl<T> Registry<T> {
     }
 
     pub fn remove(&mut self, id: Id) -> Option<T> {
-        let index = self.secondary_pos.get(&id).copied()?;
+        let index = self.secondary_pos.remove(&id)?;
         let new_index = self.primary.len() - 1;
         if index != new_index {
             self.primary.swap(index, new_index);
@@ -0,0 +1,258 @@
+use std::convert::Infallible;
+
+use thedes_domain::{
+    chunk::{Chunk, ChunkIndex},
+    geometry::{Coord, CoordPair, Rect},
+    map::{self, AccessError, LoadChunkError, Map},
+};
+use thiserror::Error;
+
+use crate::{
+    map::layer::{
+        Layer,
+        LayerDistribution,
+        block::{BlockLayer, BlockLayerDistr},
+        matter::{BiomeLayer, ClimateConfig, GroundLayer, GroundLayerDistr},
+        neighbourhood::{self, MajorityVote},
+        water,
+    },
+    random::create_positional_rng,
+    settlement::{self, Collector, NopCollector},
+    structure::{self, overlaps},
+};
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Error)]
+pub enum Error<Ce = Infallible> {
+    #[error("Map is not lazily generated, so it has no seed")]
+    NotLazy,
+    #[error("Chunk index {0} is outside of the map")]
+    InvalidIndex(ChunkIndex),
+    #[error("Failed to create scratch map")]
+    ScratchMap(
+        #[from]
+        #[source]
+        map::InitError,
+    ),
+    #[error("Failed to load chunk into scratch map")]
+    LoadChunk(
+        #[from]
+        #[source]
+        LoadChunkError,
+    ),
+    #[error("Failed to access chunk in scratch map")]
+    Access(
+        #[from]
+        #[source]
+        AccessError,
+    ),
+    #[error("Error collecting settlements for outside generation components")]
+    Collection(#[source] Ce),
+}
+
+fn points(rect: Rect) -> impl Iterator<Item = CoordPair> {
+    let bottom_right = rect.bottom_right();
+    (rect.top_left.y .. bottom_right.y).flat_map(move |y| {
+        (rect.top_left.x .. bottom_right.x).map(move |x| CoordPair { y, x })
+    })
+}
+
+/// The rectangle grown by the given width on every side, clipped to the map.
+fn halo(rect: Rect, width: usize, map_rect: Rect) -> Rect {
+    let width = Coord::try_from(width).unwrap_or(Coord::MAX);
+    let top_left = rect.top_left.zip2_with(map_rect.top_left, |start, min| {
+        start.saturating_sub(width).max(min)
+    });
+    let bottom_right =
+        rect.bottom_right().zip2_with(map_rect.bottom_right(), |end, max| {
+            end.saturating_add(width).min(max)
+        });
+    Rect { top_left, size: bottom_right - top_left }
+}
+
+/// Generates chunks of lazily generated maps on demand. Every chunk is
+/// derived only from the map's seed and from the chunk's position, so
+/// chunks can be generated in any order and still fit together.
+#[derive(Debug, Clone)]
+pub struct Config {
+    climate: ClimateConfig,
+    biome_smoothing: usize,
+    ground_layer_distr: GroundLayerDistr,
+    water: water::Config,
+    block_layer_distr: BlockLayerDistr,
+    structures: structure::Config,
+    settlements: settlement::Config,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self {
+            climate: ClimateConfig::new(),
+            biome_smoothing: 2,
+            ground_layer_distr: GroundLayerDistr::default(),
+            water: water::Config::new(),
+            block_layer_distr: BlockLayerDistr::default(),
+            structures: structure::Config::new(),
+            settlements: settlement::Config::new(),
+        }
+    }
+
+    pub fn with_climate(self, config: ClimateConfig) -> Self {
+        Self { climate: config, ..self }
+    }
+
+    /// Majority vote passes smoothing biome borders.
+    pub fn with_biome_smoothing(self, iterations: usize) -> Self {
+        Self { biome_smoothing: iterations, ..self }
+    }
+
+    pub fn with_ground_layer_distr(self, distr: GroundLayerDistr) -> Self {
+        Self { ground_layer_distr: distr, ..self }
+    }
+
+    /// Lakes and rivers, placed after the ground layer.
+    pub fn with_water(self, config: water::Config) -> Self {
+        Self { water: config, ..self }
+    }
+
+    pub fn with_block_layer_distr(self, distr: BlockLayerDistr) -> Self {
+        Self { block_layer_distr: distr, ..self }
+    }
+
+    /// Structures of rooms and corridors scattered through the map.
+    pub fn with_structures(self, config: structure::Config) -> Self {
+        Self { structures: config, ..self }
+    }
+
+    /// Settlements of buildings and roads, more common in some biomes.
+    pub fn with_settlements(self, config: settlement::Config) -> Self {
+        Self { settlements: config, ..self }
+    }
+
+    pub fn generate(
+        &self,
+        map: &Map,
+        index: ChunkIndex,
+    ) -> Result<Chunk, Error> {
+        self.generate_with(map, index, NopCollector)
+    }
+
+    /// Same as [`Config::generate`], but reports every settlement whose
+    /// center lies in the chunk to the collector, so each settlement is
+    /// reported by a single chunk.
+    pub fn generate_with<C>(
+        &self,
+        map: &Map,
+        index: ChunkIndex,
+        mut collector: C,
+    ) -> Result<Chunk, Error<C::Error>>
+    where
+        C: Collector,
+    {
+        let seed = map.seed().ok_or(Error::NotLazy)?;
+        let rect = map.chunk_rect(index).ok_or(Error::InvalidIndex(index))?;
+        let mut scratch = Map::new_lazy(map.rect(), seed)?;
+        scratch.load_chunk(index, Chunk::new())?;
+
+        // Climate noise depends only on the seed, never on the chunk, so
+        // biomes flow across chunk borders. Smoothing moves information one
+        // tile per pass, so sampling a halo as wide as the pass count around
+        // the chunk gives the same biomes as smoothing the whole map.
+        let biome_layer_distr = self.climate.finish(seed);
+        let mut rng = create_positional_rng(!seed, index.map(u32::from));
+        let halo_rect = halo(rect, self.biome_smoothing, map.rect());
+        let mut biomes: Vec<_> = points(halo_rect)
+            .map(|point| biome_layer_distr.biome_at(point))
+            .collect();
+        let Ok(()) = neighbourhood::Generator::new()
+            .with_iterations(self.biome_smoothing)
+            .execute_on_grid(
+                halo_rect,
+                &mut biomes,
+                &MajorityVote::new(),
+                &mut rng,
+            );
+        for (point, biome) in points(halo_rect).zip(biomes) {
+            if rect.contains_point(point) {
+                BiomeLayer.set(&mut scratch, point, biome)?;
+            }
+        }
+        for point in points(rect) {
+            let ground = self.ground_layer_distr.sample(
+                &mut scratch,
+                point,
+                &mut rng,
+            )?;
+            GroundLayer.set(&mut scratch, point, ground)?;
+        }
+        // Rivers may flow across chunks, so every chunk traces again the
+        // rivers springing near it, but paints only its own part of them.
+        let water_layer_distr = self
+            .water
+            .finish(seed, map.rect(), *biome_layer_distr.elevation())
+            .layer_distr(rect);
+        for point in points(rect) {
+            let block =
+                water_layer_distr.sample(&mut scratch, point, &mut rng)?;
+            BlockLayer.set(&mut scratch, point, block)?;
+        }
+        for point in points(rect) {
+            let block =
+                self.block_layer_distr.sample(&mut scratch, point, &mut rng)?;
+            BlockLayer.set(&mut scratch, point, block)?;
+        }
+
+        // Structures may span several chunks, so each chunk carves the whole
+        // structure again, but keeps only its own part.
+        let settlement_sites = self.settlements.finish(
+            seed,
+            map.rect(),
+            biome_layer_distr.clone(),
+        );
+        let structure_sites = self.structures.finish(seed, map.rect());
+        for structure in structure_sites.structures_in(rect) {
+            // Settlements win over structures, and every chunk the structure
+            // spans agrees on that, since only the structure's rectangle is
+            // checked.
+            if settlement_sites
+                .rects_in(structure.rect())
+                .any(|settlement| overlaps(settlement, structure.rect()))
+            {
+                continue;
+            }
+            for (point, block) in structure.blocks() {
+                if rect.contains_point(point) {
+                    BlockLayer.set(&mut scratch, point, block)?;
+                }
+            }
+        }
+
+        for settlement in settlement_sites.settlements_in(rect) {
+            for (point, ground) in settlement.grounds() {
+                if rect.contains_point(point) {
+                    GroundLayer.set(&mut scratch, point, ground)?;
+                }
+            }
+            for (point, block) in settlement.blocks() {
+                if rect.contains_point(point) {
+                    BlockLayer.set(&mut scratch, point, block)?;
+                }
+            }
+            if rect.contains_point(settlement.center()) {
+                collector
+                    .add_settlement(&settlement)
+                    .map_err(Error::Collection)?;
+            }
+        }
+
+        Ok(scratch.unload_chunk(index).expect("chunk was just loaded"))
+    }
+}
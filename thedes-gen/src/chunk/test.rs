@@ -0,0 +1,322 @@
+use std::convert::Infallible;
+
+use rand::{SeedableRng, rngs::StdRng};
+use thedes_domain::{
+    block::PlaceableBlock,
+    chunk::ChunkIndex,
+    geometry::{CoordPair, Rect},
+    map::Map,
+    matter::Biome,
+};
+
+use crate::{
+    map::layer::{
+        matter::ClimateConfig,
+        neighbourhood::{self, MajorityVote},
+        water,
+    },
+    settlement::{self, Collector, Settlement},
+    structure::{self, overlaps},
+};
+
+use super::{Config, points};
+
+const SEED: u64 = 0x5eed;
+
+fn lazy_map() -> Map {
+    let rect = Rect {
+        top_left: CoordPair { y: 0, x: 0 },
+        size: CoordPair { y: 256, x: 256 },
+    };
+    Map::new_lazy(rect, SEED).unwrap()
+}
+
+const CENTER: ChunkIndex = CoordPair { y: 3, x: 3 };
+
+const NEIGHBOURS: [ChunkIndex; 8] = [
+    CoordPair { y: 2, x: 2 },
+    CoordPair { y: 2, x: 3 },
+    CoordPair { y: 2, x: 4 },
+    CoordPair { y: 3, x: 2 },
+    CoordPair { y: 3, x: 4 },
+    CoordPair { y: 4, x: 2 },
+    CoordPair { y: 4, x: 3 },
+    CoordPair { y: 4, x: 4 },
+];
+
+#[test]
+fn chunk_does_not_depend_on_generation_order() {
+    let config = Config::new();
+    let alone = config.generate(&lazy_map(), CENTER).unwrap();
+
+    let orders = [
+        NEIGHBOURS.to_vec(),
+        NEIGHBOURS.iter().rev().copied().collect(),
+        NEIGHBOURS
+            .iter()
+            .step_by(3)
+            .chain(&NEIGHBOURS[1 ..])
+            .copied()
+            .collect(),
+    ];
+    for order in orders {
+        let mut map = lazy_map();
+        for index in order {
+            if map.is_chunk_loaded(index) {
+                continue;
+            }
+            let chunk = config.generate(&map, index).unwrap();
+            map.load_chunk(index, chunk).unwrap();
+        }
+        assert_eq!(config.generate(&map, CENTER).unwrap(), alone);
+    }
+}
+
+#[test]
+fn chunks_are_deterministic_per_seed() {
+    let config = Config::new();
+    let map = lazy_map();
+    for index in NEIGHBOURS {
+        assert_eq!(
+            config.generate(&map, index).unwrap(),
+            config.generate(&map, index).unwrap(),
+        );
+    }
+
+    let other = Map::new_lazy(map.rect(), !SEED).unwrap();
+    assert!(NEIGHBOURS.iter().any(|&index| {
+        config.generate(&map, index).unwrap()
+            != config.generate(&other, index).unwrap()
+    }));
+}
+
+#[test]
+fn eager_maps_are_rejected() {
+    let map = Map::new(lazy_map().rect()).unwrap();
+    assert!(Config::new().generate(&map, CENTER).is_err());
+}
+
+/// Every chunk of the map, generated and loaded.
+fn generate_all(config: &Config) -> Map {
+    let mut map = lazy_map();
+    let grid_size = map.chunk_grid_size();
+    for y in 0 .. grid_size.y {
+        for x in 0 .. grid_size.x {
+            let index = CoordPair { y, x };
+            let chunk = config.generate(&map, index).unwrap();
+            map.load_chunk(index, chunk).unwrap();
+        }
+    }
+    map
+}
+
+#[test]
+fn chunks_paint_their_part_of_the_water() {
+    let mut settlements = settlement::Config::new();
+    for biome in Biome::ALL {
+        settlements = settlements.with_density(biome, 0.0).unwrap();
+    }
+    let config = Config::new()
+        .with_structures(structure::Config::new().with_density(0.0).unwrap())
+        .with_settlements(settlements);
+    let map = generate_all(&config);
+
+    let elevation = *ClimateConfig::new().finish(SEED).elevation();
+    let water = water::Config::new()
+        .finish(SEED, map.rect(), elevation)
+        .layer_distr(map.rect());
+    let mut water_tiles = 0;
+    for point in points(map.rect()) {
+        let block = map.get_covered_block(point).unwrap();
+        assert_eq!(block == PlaceableBlock::Water, water.is_water(point));
+        water_tiles += usize::from(water.is_water(point));
+    }
+    assert!(water_tiles > 0);
+}
+
+#[test]
+fn structures_across_chunks_are_painted_whole() {
+    let mut settlements = settlement::Config::new();
+    for biome in Biome::ALL {
+        settlements = settlements.with_density(biome, 0.0).unwrap();
+    }
+    let structures = structure::Config::new().with_density(1.0).unwrap();
+    let config = Config::new()
+        .with_structures(structures.clone())
+        .with_settlements(settlements);
+    let map = generate_all(&config);
+
+    let mut spanning = 0;
+    for structure in
+        structures.finish(SEED, map.rect()).structures_in(map.rect())
+    {
+        let rect = structure.rect();
+        let first = map.chunk_index(rect.top_left).unwrap();
+        let last = map
+            .chunk_index(rect.bottom_right().map(|coord| coord - 1))
+            .unwrap();
+        if first != last {
+            spanning += 1;
+        }
+        for (point, block) in structure.blocks() {
+            assert_eq!(
+                map.get_covered_block(point).unwrap(),
+                block,
+                "{point:?}"
+            );
+        }
+    }
+    assert!(spanning > 0);
+}
+
+#[derive(Debug, Default)]
+struct CountingCollector {
+    centers: Vec<CoordPair>,
+}
+
+impl Collector for CountingCollector {
+    type Error = Infallible;
+
+    fn add_settlement(
+        &mut self,
+        settlement: &Settlement,
+    ) -> Result<(), Self::Error> {
+        self.centers.push(settlement.center());
+        Ok(())
+    }
+}
+
+#[test]
+fn settlements_win_over_structures_in_every_chunk() {
+    let mut settlements = settlement::Config::new();
+    for biome in Biome::ALL {
+        settlements = settlements.with_density(biome, 1.0).unwrap();
+    }
+    let structures = structure::Config::new()
+        .with_size(20, 48)
+        .unwrap()
+        .with_cell_size(64)
+        .unwrap()
+        .with_density(1.0)
+        .unwrap();
+    let with_structures = Config::new()
+        .with_structures(structures.clone())
+        .with_settlements(settlements.clone());
+    let without_structures = Config::new()
+        .with_structures(structure::Config::new().with_density(0.0).unwrap())
+        .with_settlements(settlements.clone());
+
+    let mut collector = CountingCollector::default();
+    let mut map = lazy_map();
+    let grid_size = map.chunk_grid_size();
+    for y in 0 .. grid_size.y {
+        for x in 0 .. grid_size.x {
+            let index = CoordPair { y, x };
+            let chunk = with_structures
+                .generate_with(&map, index, &mut collector)
+                .unwrap();
+            map.load_chunk(index, chunk).unwrap();
+        }
+    }
+    let bare = generate_all(&without_structures);
+
+    let map_rect = map.rect();
+    let settlement_sites =
+        settlements.finish(SEED, map_rect, ClimateConfig::new().finish(SEED));
+    let settlement_list: Vec<_> =
+        settlement_sites.settlements_in(map_rect).collect();
+    let mut expected_centers: Vec<_> =
+        settlement_list.iter().map(Settlement::center).collect();
+    expected_centers.sort();
+    collector.centers.sort();
+    assert_eq!(collector.centers, expected_centers);
+
+    let (kept, skipped): (Vec<_>, Vec<_>) = structures
+        .finish(SEED, map_rect)
+        .structures_in(map_rect)
+        .partition(|structure| {
+            settlement_list.iter().all(|settlement| {
+                !overlaps(settlement.rect(), structure.rect())
+            })
+        });
+    assert!(!kept.is_empty());
+    assert!(!skipped.is_empty());
+
+    for point in points(map_rect) {
+        let block = map.get_covered_block(point).unwrap();
+        let structure_block =
+            kept.iter().find_map(|structure| structure.block(point));
+        match structure_block {
+            Some(structure_block) => assert_eq!(block, structure_block),
+            None => {
+                assert_eq!(block, bare.get_covered_block(point).unwrap())
+            },
+        }
+    }
+}
+
+#[test]
+fn smoothed_biomes_match_across_chunks() {
+    let map = generate_all(&Config::new());
+    let map_rect = map.rect();
+    let climate = ClimateConfig::new().finish(SEED);
+    let mut biomes: Vec<_> =
+        points(map_rect).map(|point| climate.biome_at(point)).collect();
+    let raw = biomes.clone();
+    neighbourhood::Generator::new()
+        .with_iterations(2)
+        .execute_on_grid(
+            map_rect,
+            &mut biomes,
+            &MajorityVote::new(),
+            StdRng::seed_from_u64(0),
+        )
+        .unwrap();
+    assert_ne!(biomes, raw);
+    for (point, biome) in points(map_rect).zip(biomes) {
+        assert_eq!(map.get_biome(point).unwrap(), biome, "{point:?}");
+    }
+}
+
+#[test]
+fn default_overworld_has_water_near_spawn() {
+    let rect = Rect {
+        top_left: CoordPair { y: 0, x: 0 },
+        size: CoordPair { y: u16::MAX, x: u16::MAX },
+    };
+    // Chunks around the center, where the player spawns, a little beyond
+    // those generated along with a new game.
+    let radius = 3;
+    let config = Config::new();
+    for seed in [SEED, 1, 22, 333] {
+        let mut map = Map::new_lazy(rect, seed).unwrap();
+        let center = map.chunk_grid_size().map(|coord| coord / 2);
+        for y in center.y - radius ..= center.y + radius {
+            for x in center.x - radius ..= center.x + radius {
+                let index = CoordPair { y, x };
+                let chunk = config.generate(&map, index).unwrap();
+                map.load_chunk(index, chunk).unwrap();
+            }
+        }
+        let spawn = map.loaded_rect().unwrap();
+        let water_tiles = points(spawn)
+            .filter(|point| {
+                map.get_covered_block(*point).unwrap() == PlaceableBlock::Water
+            })
+            .count();
+        assert!(water_tiles > 0, "seed {seed}");
+
+        let elevation = *ClimateConfig::new().finish(seed).elevation();
+        let water = water::Config::new().finish(seed, rect, elevation);
+        let nearby = Rect {
+            top_left: spawn.top_left.map(|coord| coord - 256),
+            size: spawn.size.map(|coord| coord + 512),
+        };
+        assert!(
+            water
+                .rivers_in(nearby)
+                .any(|river| river.end() != water::RiverEnd::Sink),
+            "seed {seed}"
+        );
+    }
+}
@@ -10,8 +10,13 @@ use rand_distr::{
 use thedes_domain::{
     event::Event,
     game::Game,
-    geometry::Coord,
-    monster::{self, MonsterPosition},
+    geometry::{Coord, CoordPair},
+    map::{Map, MapId},
+    matter::Biome,
+    monster::{self, Monster, MonsterPosition, Species},
+    npc::{self, Npc},
+    time::DayPhase,
+    weather::{WeatherRegion, WeatherState},
 };
 use thedes_geometry::{
     orientation::Direction,
@@ -21,6 +26,9 @@ use thiserror::Error;
 
 use crate::random::ProabilityWeight;
 
+#[cfg(test)]
+mod test;
+
 #[derive(Debug, Error)]
 pub enum DistrError {
     #[error("Invalid map rectangle")]
@@ -77,6 +85,17 @@ pub enum InvalidMonsterFollowPeriod {
     BoundOrder { min: Coord, max: Coord },
 }
 
+#[derive(Debug, Error)]
+pub enum InvalidMonsterFollowRadius {
+    #[error(
+        "Monster-follow radius must be in the interval [{}, {}], given {}",
+        DistrConfig::MIN_FOLLOW_RADIUS,
+        DistrConfig::MAX_FOLLOW_RADIUS,
+        _0
+    )]
+    Range(Coord),
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum EventType {
     TrySpawnMonster,
@@ -84,10 +103,12 @@ pub enum EventType {
     TryMoveMonster,
     MonsterAttack,
     FollowPlayer,
+    ChangeWeather,
+    TryMoveNpc,
 }
 
 impl EventType {
-    pub const COUNT: usize = 5;
+    pub const COUNT: usize = 7;
 
     pub const ALL: [Self; Self::COUNT] = [
         Self::TrySpawnMonster,
@@ -95,6 +116,8 @@ impl EventType {
         Self::TryMoveMonster,
         Self::MonsterAttack,
         Self::FollowPlayer,
+        Self::ChangeWeather,
+        Self::TryMoveNpc,
     ];
 }
 
@@ -116,19 +139,22 @@ impl EventTypeDistr {
         Self { cumulative_weights }
     }
 
-    pub fn from_monster_count(x: Coord) -> Self {
+    /// NPCs only ever wander around, as often as a monster would move.
+    /// Weather changes have a small fixed weight, so they get rarer the more
+    /// crowded the map is, but never stop.
+    pub fn from_population(x: Coord, npcs: Coord, phase: DayPhase) -> Self {
         let cut = 10000;
         let x = x as ProabilityWeight;
+        let spawn_factor = match phase {
+            DayPhase::Day => 1,
+            DayPhase::Dawn | DayPhase::Dusk => 2,
+            DayPhase::Night => 4,
+        };
         Self::new(|ty| {
             let weight = match ty {
                 EventType::TrySpawnMonster => {
-                    if x == 0 {
-                        1
-                    } else if x < cut {
-                        cut * 2 - x
-                    } else {
-                        x / cut
-                    }
+                    let weight = if x < cut { cut * 2 - x } else { x / cut };
+                    weight * spawn_factor
                 },
                 EventType::VanishMonster => {
                     if x == 0 {
@@ -142,6 +168,8 @@ impl EventTypeDistr {
                 EventType::TryMoveMonster => x * cut / 100,
                 EventType::MonsterAttack => x * cut / 5,
                 EventType::FollowPlayer => x,
+                EventType::ChangeWeather => 20,
+                EventType::TryMoveNpc => npcs as ProabilityWeight * cut / 100,
             };
             weight
         })
@@ -167,14 +195,74 @@ impl Distribution<EventType> for EventTypeDistr {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct SpeciesDistr {
+    cumulative_weights: [[ProabilityWeight; Species::COUNT]; Biome::COUNT],
+}
+
+impl Default for SpeciesDistr {
+    fn default() -> Self {
+        Self::new(|biome, species| match (biome, species) {
+            (Biome::Plains, Species::Bat) => 3,
+            (Biome::Plains, Species::Wolf) => 5,
+            (Biome::Plains, Species::Snake) => 1,
+            (Biome::Desert, Species::Scorpion) => 5,
+            (Biome::Desert, Species::Snake) => 4,
+            (Biome::Wasteland, Species::Bat) => 4,
+            (Biome::Wasteland, Species::Scorpion) => 1,
+            (Biome::Wasteland, Species::Ghoul) => 3,
+            _ => 0,
+        })
+    }
+}
+
+impl SpeciesDistr {
+    pub fn new<F>(mut density_function: F) -> Self
+    where
+        F: FnMut(Biome, Species) -> ProabilityWeight,
+    {
+        let cumulative_weights = array::from_fn(|i| {
+            let mut accumuled_weight = 0;
+            array::from_fn(|j| {
+                accumuled_weight +=
+                    density_function(Biome::ALL[i], Species::ALL[j]);
+                accumuled_weight
+            })
+        });
+        Self { cumulative_weights }
+    }
+
+    pub fn sample<R>(&self, biome: Biome, rng: &mut R) -> Species
+    where
+        R: Rng + ?Sized,
+    {
+        let cumulative_weights = self.cumulative_weights[biome as usize];
+        let last_cumulative_weight =
+            cumulative_weights[cumulative_weights.len() - 1];
+        if last_cumulative_weight == 0 {
+            return Species::default();
+        }
+        let sampled_weight = rng.random_range(0 .. last_cumulative_weight);
+        for (i, cumulative_weight) in cumulative_weights.into_iter().enumerate()
+        {
+            if sampled_weight < cumulative_weight {
+                return Species::ALL[i];
+            }
+        }
+        panic!("sampled weight {sampled_weight} is out of requested bounds")
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DistrConfig {
+    species_distr: SpeciesDistr,
     monster_follow_limit_min: u32,
     monster_follow_limit_peak: u32,
     monster_follow_limit_max: u32,
     monster_follow_period_min: Coord,
     monster_follow_period_peak: Coord,
     monster_follow_period_max: Coord,
+    monster_follow_radius: Coord,
 }
 
 impl DistrConfig {
@@ -184,17 +272,30 @@ impl DistrConfig {
     pub const MIN_FOLLOW_LIMIT: u32 = 1;
     pub const MAX_FOLLOW_LIMIT: u32 = u32::MAX;
 
+    pub const MIN_FOLLOW_RADIUS: Coord = 1;
+    pub const MAX_FOLLOW_RADIUS: Coord = Coord::MAX;
+
     pub fn new() -> Self {
         Self {
+            species_distr: SpeciesDistr::default(),
             monster_follow_period_min: 1500,
             monster_follow_period_peak: 2000,
             monster_follow_period_max: 2500,
             monster_follow_limit_min: 100,
             monster_follow_limit_peak: 1000,
             monster_follow_limit_max: 5000,
+            monster_follow_radius: 32,
         }
     }
 
+    pub fn species_distr(&self) -> &SpeciesDistr {
+        &self.species_distr
+    }
+
+    pub fn with_species_distr(self, species_distr: SpeciesDistr) -> Self {
+        Self { species_distr, ..self }
+    }
+
     pub fn monster_follow_limit_min(&self) -> u32 {
         self.monster_follow_limit_min
     }
@@ -405,15 +506,48 @@ impl DistrConfig {
         Ok(self)
     }
 
+    pub fn monster_follow_radius(&self) -> Coord {
+        self.monster_follow_radius
+    }
+
+    pub fn set_monster_follow_radius(
+        &mut self,
+        value: Coord,
+    ) -> Result<(), InvalidMonsterFollowRadius> {
+        if !(Self::MIN_FOLLOW_RADIUS ..= Self::MAX_FOLLOW_RADIUS)
+            .contains(&value)
+        {
+            Err(InvalidMonsterFollowRadius::Range(value))?;
+        }
+        self.monster_follow_radius = value;
+        Ok(())
+    }
+
+    pub fn with_monster_follow_radius(
+        mut self,
+        value: Coord,
+    ) -> Result<Self, InvalidMonsterFollowRadius> {
+        self.set_monster_follow_radius(value)?;
+        Ok(self)
+    }
+
     pub fn finish<'a>(
-        &self,
+        &'a self,
         game: &'a Game,
     ) -> Result<EventDistr<'a>, DistrError> {
         let monsters = game.monster_registry();
         let monster_count = monsters.len() as Coord;
-        let event_type_distr =
-            EventTypeDistr::from_monster_count(monster_count);
-        let map_rect_uniform_distr = UniformRectDistr::new(game.map().rect())?;
+        let npcs = game.npc_registry();
+        let event_type_distr = EventTypeDistr::from_population(
+            monster_count,
+            npcs.len() as Coord,
+            game.time().phase(),
+        );
+        // Lazily generated maps can be huge, so spawn only where chunks are
+        // loaded, i.e. around the player.
+        let map = game.map();
+        let spawn_rect = map.loaded_rect().unwrap_or(map.rect());
+        let map_rect_uniform_distr = UniformRectDistr::new(spawn_rect)?;
 
         let monster_follow_limit_distr = Triangular::new(
             self.monster_follow_limit_min as f64,
@@ -430,22 +564,105 @@ impl DistrConfig {
         .map_err(DistrError::InvalidMonsterFollowPeriodDistr)?;
 
         Ok(EventDistr {
+            map: game.map(),
+            map_id: game.current_map_id(),
+            weather: game.weather(),
+            species_distr: &self.species_distr,
             monsters,
+            npcs,
             event_type_distr,
             map_rect_uniform_distr,
             monster_follow_limit_distr,
             monster_follow_period_distr,
+            monster_follow_radius: self.monster_follow_radius,
         })
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct EventDistr<'a> {
+    map: &'a Map,
+    map_id: MapId,
+    weather: &'a WeatherState,
+    species_distr: &'a SpeciesDistr,
     monsters: &'a monster::Registry,
+    npcs: &'a npc::Registry,
     event_type_distr: EventTypeDistr,
     map_rect_uniform_distr: UniformRectDistr<Coord>,
     monster_follow_limit_distr: Triangular<f64>,
     monster_follow_period_distr: Triangular<f64>,
+    monster_follow_radius: Coord,
+}
+
+impl<'a> EventDistr<'a> {
+    fn sample_monster<R>(&self, rng: &mut R) -> (monster::Id, &'a Monster)
+    where
+        R: Rng + ?Sized,
+    {
+        let index = rng.random_range(.. self.monsters.len());
+        self.monsters.get_by_index_as(index).expect("inconsistent indexing")
+    }
+
+    fn sample_move<R>(
+        &self,
+        id: monster::Id,
+        monster: &Monster,
+        rng: &mut R,
+    ) -> Event
+    where
+        R: Rng + ?Sized,
+    {
+        let direction =
+            Self::sample_direction(monster.position().facing(), rng);
+        Event::TryMoveMonster(id, direction)
+    }
+
+    /// Samples a direction to move, favoring the current one.
+    fn sample_direction<R>(curr_direction: Direction, rng: &mut R) -> Direction
+    where
+        R: Rng + ?Sized,
+    {
+        let directions = Direction::ALL;
+        let weights = directions
+            .map(|direction| if direction == curr_direction { 5 } else { 1 });
+        let weighted = WeightedIndex::new(&weights)
+            .expect("no weight should be zero, no overflow");
+        directions[weighted.sample(rng)]
+    }
+
+    fn sample_npc_move<R>(&self, rng: &mut R) -> Event
+    where
+        R: Rng + ?Sized,
+    {
+        let index = rng.random_range(.. self.npcs.len());
+        let (id, npc): (npc::Id, &Npc) =
+            self.npcs.get_by_index_as(index).expect("inconsistent indexing");
+        let direction = Self::sample_direction(npc.facing(), rng);
+        Event::TryMoveNpc(id, direction)
+    }
+
+    /// Changes the weather of the biome region around a random loaded point,
+    /// into any weather it can turn into in that biome.
+    fn sample_weather<R>(&self, rng: &mut R) -> Event
+    where
+        R: Rng + ?Sized,
+    {
+        let point: CoordPair = self.map_rect_uniform_distr.sample(rng);
+        let biome = self.map.get_biome(point).unwrap_or_default();
+        let region = WeatherRegion::containing(self.map_id, point, biome);
+        let transitions: Vec<_> =
+            self.weather.get(region).transitions(biome).collect();
+        let weather = transitions[rng.random_range(.. transitions.len())];
+        Event::ChangeWeather { region, weather }
+    }
+
+    fn is_aggressive<R>(&self, monster: &Monster, rng: &mut R) -> bool
+    where
+        R: Rng + ?Sized,
+    {
+        rng.random_range(0 .. Species::MAX_AGGRESSION)
+            < monster.species().aggression()
+    }
 }
 
 impl<'a> Distribution<Event> for EventDistr<'a> {
@@ -455,53 +672,51 @@ impl<'a> Distribution<Event> for EventDistr<'a> {
     {
         match self.event_type_distr.sample(rng) {
             EventType::TrySpawnMonster => {
-                let body = self.map_rect_uniform_distr.sample(rng);
+                let body: CoordPair = self.map_rect_uniform_distr.sample(rng);
                 let facing = rng.random();
-                Event::TrySpawnMonster(MonsterPosition::new(body, facing))
+                let biome = self.map.get_biome(body).unwrap_or_default();
+                let species = self.species_distr.sample(biome, rng);
+                Event::TrySpawnMonster(
+                    MonsterPosition::new(body, facing),
+                    species,
+                )
             },
             EventType::VanishMonster => {
-                let index = rng.random_range(.. self.monsters.len());
-                let (id, _) = self
-                    .monsters
-                    .get_by_index_as(index)
-                    .expect("inconsistent indexing");
+                let (id, _) = self.sample_monster(rng);
                 Event::VanishMonster(id)
             },
             EventType::TryMoveMonster => {
-                let index = rng.random_range(.. self.monsters.len());
-                let (id, monster) = self
-                    .monsters
-                    .get_by_index_as(index)
-                    .expect("inconsistent indexing");
-                let curr_direction = monster.position().facing();
-                let directions = Direction::ALL;
-                let weights = directions.map(|direction| {
-                    if direction == curr_direction { 5 } else { 1 }
-                });
-                let weighted = WeightedIndex::new(&weights)
-                    .expect("no weight should be zero, no overflow");
-                let direction = directions[weighted.sample(rng)];
-                Event::TryMoveMonster(id, direction)
+                let (id, monster) = self.sample_monster(rng);
+                self.sample_move(id, monster, rng)
             },
             EventType::MonsterAttack => {
-                let index = rng.random_range(.. self.monsters.len());
-                let (id, _) = self
-                    .monsters
-                    .get_by_index_as(index)
-                    .expect("inconsistent indexing");
+                let (id, monster) = self.sample_monster(rng);
+                if !self.is_aggressive(monster, rng) {
+                    return self.sample_move(id, monster, rng);
+                }
                 Event::MonsterAttack(id)
             },
             EventType::FollowPlayer => {
-                let index = rng.random_range(.. self.monsters.len());
-                let (id, _) = self
-                    .monsters
-                    .get_by_index_as(index)
-                    .expect("inconsistent indexing");
+                let (id, monster) = self.sample_monster(rng);
+                if !self.is_aggressive(monster, rng) {
+                    return self.sample_move(id, monster, rng);
+                }
                 let limit = self.monster_follow_limit_distr.sample(rng) as u32;
                 let period =
-                    self.monster_follow_period_distr.sample(rng) as Coord;
-                Event::FollowPlayer { id, period, limit }
+                    self.monster_follow_period_distr.sample(rng) as u32;
+                let period = (period * u32::from(Species::NORMAL_SPEED)
+                    / u32::from(monster.species().speed()))
+                .clamp(1, u32::from(Coord::MAX))
+                    as Coord;
+                Event::FollowPlayer {
+                    id,
+                    period,
+                    limit,
+                    radius: self.monster_follow_radius,
+                }
             },
+            EventType::ChangeWeather => self.sample_weather(rng),
+            EventType::TryMoveNpc => self.sample_npc_move(rng),
         }
     }
 }
@@ -0,0 +1,66 @@
+use rand::{SeedableRng, distr::Distribution, rngs::StdRng};
+use thedes_domain::{
+    event::Event,
+    game::Game,
+    geometry::{Coord, CoordPair, Rect},
+    map::Map,
+    player::{Player, PlayerPosition},
+    time::DayPhase,
+};
+use thedes_geometry::orientation::Direction;
+
+use super::{DistrConfig, EventType, EventTypeDistr};
+
+fn game() -> Game {
+    let rect = Rect {
+        top_left: CoordPair { y: 0, x: 0 },
+        size: CoordPair { y: 200, x: 200 },
+    };
+    let position =
+        PlayerPosition::new(CoordPair { y: 10, x: 10 }, Direction::Right)
+            .unwrap();
+    let player = Player::new(position, Player::DEFAULT_HP);
+    Game::new(Map::new(rect).unwrap(), player).unwrap()
+}
+
+#[test]
+fn weather_events_always_change_weather() {
+    let mut game = game();
+    let config = DistrConfig::new();
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut changes = 0;
+    while changes < 200 {
+        let event = config.finish(&game).unwrap().sample(&mut rng);
+        if let Event::ChangeWeather { region, weather } = event {
+            assert_ne!(game.weather().get(region), weather);
+            game.change_weather(region, weather);
+            changes += 1;
+        }
+    }
+}
+
+#[test]
+fn weather_changes_stay_rare() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let distr = EventTypeDistr::from_population(0, 0, DayPhase::Day);
+    let samples = 100_000;
+    let changes = (0 .. samples)
+        .filter(|_| distr.sample(&mut rng) == EventType::ChangeWeather)
+        .count();
+    assert!(changes > 0);
+    assert!(changes < samples / 100, "{changes} weather changes");
+}
+
+#[test]
+fn weights_fit_a_crowded_map() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let distr = EventTypeDistr::from_population(
+        Coord::MAX,
+        Coord::MAX,
+        DayPhase::Night,
+    );
+    let changes = (0 .. 10_000)
+        .filter(|_| distr.sample(&mut rng) == EventType::ChangeWeather)
+        .count();
+    assert!(changes < 10, "{changes} weather changes");
+}
@@ -2,14 +2,36 @@ use rand::Rng;
 use rand_distr::{Triangular, TriangularError};
 use thedes_async_util::progress;
 use thedes_domain::{
-    game::{self, Game},
-    geometry::Coord,
+    block::PlaceableBlock,
+    game::{
+        self,
+        AddMapError,
+        DeathMode,
+        Game,
+        LinkPortalsError,
+        SimulationMode,
+    },
+    geometry::{Coord, CoordPair, Rect},
+    item::{ItemKind, ItemStack},
+    map::{
+        self as domain_map,
+        AccessError,
+        LoadChunkError,
+        Map,
+        MapId,
+        PortalEnd,
+    },
+    matter::Biome,
+    monster::Species,
     player::{self, Player, PlayerPosition},
+    quest::{Objective, Quest, Reward},
+    time::WorldTime,
 };
+
 use thedes_geometry::orientation::{Axis, Direction};
 use thiserror::Error;
 
-use crate::{map, random::PickedReproducibleRng};
+use crate::{chunk, map, npc, random::PickedReproducibleRng, settlement};
 
 #[derive(Debug, Error)]
 pub enum InitError {
@@ -29,6 +51,24 @@ pub enum Error {
         #[source]
         map::Error,
     ),
+    #[error("Failed to create overworld map")]
+    CreateOverworld(
+        #[from]
+        #[source]
+        domain_map::InitError,
+    ),
+    #[error("Failed to generate overworld chunk")]
+    Chunk(
+        #[from]
+        #[source]
+        chunk::Error,
+    ),
+    #[error("Failed to load overworld chunk")]
+    LoadChunk(
+        #[from]
+        #[source]
+        LoadChunkError,
+    ),
     #[error("Error creating random distribution for player's head in axis {1}")]
     PlayerHeadDistr(#[source] TriangularError, Axis),
     #[error("Failed to create a game")]
@@ -43,11 +83,43 @@ pub enum Error {
         #[from]
         player::InitError,
     ),
+    #[error("Failed to clear player's spawn location")]
+    ClearPlayerSpawn(#[source] AccessError),
+    #[error("Failed to place stairs")]
+    PlaceStairs(#[source] AccessError),
+    #[error("Failed to add dungeon level to the game")]
+    AddMap(
+        #[from]
+        #[source]
+        AddMapError,
+    ),
+    #[error("Failed to link stairs between levels")]
+    LinkStairs(
+        #[from]
+        #[source]
+        LinkPortalsError,
+    ),
+    #[error("Failed to spawn NPCs")]
+    Npc(
+        #[from]
+        #[source]
+        npc::Error,
+    ),
 }
 
 #[derive(Debug, Clone)]
 pub struct Config {
-    map_config: map::Config,
+    overworld_size: CoordPair,
+    chunk_config: chunk::Config,
+    spawn_chunk_radius: Coord,
+    dungeon_map_config: map::Config,
+    dungeon_levels: MapId,
+    simulation_mode: SimulationMode,
+    death_mode: DeathMode,
+    npc_config: npc::Config,
+    villager_config: npc::Config,
+    starting_quests: Vec<Quest>,
+    stairs_quest_reward: Option<Reward>,
 }
 
 impl Default for Config {
@@ -58,29 +130,146 @@ impl Default for Config {
 
 impl Config {
     pub fn new() -> Self {
-        Self { map_config: map::Config::new() }
+        Self {
+            overworld_size: CoordPair::from_axes(|_| Coord::MAX),
+            chunk_config: chunk::Config::new(),
+            spawn_chunk_radius: 2,
+            dungeon_map_config: map::Config::new_dungeon(),
+            dungeon_levels: 2,
+            simulation_mode: SimulationMode::default(),
+            death_mode: DeathMode::default(),
+            npc_config: npc::Config::new(),
+            villager_config: npc::Config::new_villagers(),
+            starting_quests: vec![
+                Quest::new(
+                    "First Night",
+                    Objective::Survive { ticks: WorldTime::EPOCHS_PER_DAY / 2 },
+                    Reward {
+                        xp: 100,
+                        items: vec![ItemStack::single(ItemKind::HealingPotion)],
+                    },
+                ),
+                Quest::new(
+                    "Shifting Sands",
+                    Objective::Explore(Biome::Desert),
+                    Reward { xp: 50, items: Vec::new() },
+                ),
+                Quest::new(
+                    "Wolf Hunt",
+                    Objective::Slay { species: Species::Wolf, count: 3 },
+                    Reward {
+                        xp: 75,
+                        items: vec![
+                            ItemStack::new(ItemKind::Berry, 5)
+                                .expect("berries should stack"),
+                        ],
+                    },
+                ),
+            ],
+            stairs_quest_reward: Some(Reward { xp: 150, items: Vec::new() }),
+        }
+    }
+
+    /// Defaults to the largest size a [`Coord`] allows, `u16::MAX` tiles
+    /// along each axis. Chunks are generated on demand, so a large overworld
+    /// costs nothing until explored.
+    pub fn with_overworld_size(self, size: CoordPair) -> Self {
+        Self { overworld_size: size, ..self }
+    }
+
+    pub fn with_chunk(self, chunk_config: chunk::Config) -> Self {
+        Self { chunk_config, ..self }
+    }
+
+    /// Radius, in chunks, of the overworld area generated along with the game
+    /// around the player's spawn. Everything else is generated on demand.
+    pub fn with_spawn_chunk_radius(self, radius: Coord) -> Self {
+        Self { spawn_chunk_radius: radius, ..self }
     }
 
-    pub fn with_map(self, map_config: map::Config) -> Self {
-        Self { map_config, ..self }
+    pub fn with_dungeon_map(self, config: map::Config) -> Self {
+        Self { dungeon_map_config: config, ..self }
+    }
+
+    pub fn with_dungeon_levels(self, levels: MapId) -> Self {
+        Self { dungeon_levels: levels, ..self }
+    }
+
+    pub fn with_simulation_mode(self, mode: SimulationMode) -> Self {
+        Self { simulation_mode: mode, ..self }
+    }
+
+    pub fn with_death_mode(self, mode: DeathMode) -> Self {
+        Self { death_mode: mode, ..self }
+    }
+
+    /// NPCs spawned around the player at the start of the game.
+    pub fn with_npc(self, config: npc::Config) -> Self {
+        Self { npc_config: config, ..self }
+    }
+
+    /// NPCs spawned in every settlement generated along with the game.
+    pub fn with_villagers(self, config: npc::Config) -> Self {
+        Self { villager_config: config, ..self }
+    }
+
+    /// Quests the player starts the game with.
+    pub fn with_starting_quests(self, quests: Vec<Quest>) -> Self {
+        Self { starting_quests: quests, ..self }
+    }
+
+    /// Reward of the starting quest to find the stairs down from the
+    /// overworld. Without a reward, there is no such quest.
+    pub fn with_stairs_quest_reward(self, reward: Option<Reward>) -> Self {
+        Self { stairs_quest_reward: reward, ..self }
     }
 
     pub fn finish(
         self,
         rng: &mut PickedReproducibleRng,
     ) -> Result<Generator, InitError> {
-        Ok(Generator { map_gen: self.map_config.finish(rng)? })
+        let dungeon_gens = (0 .. self.dungeon_levels)
+            .map(|_| self.dungeon_map_config.clone().finish(rng))
+            .collect::<Result<_, _>>()?;
+        Ok(Generator {
+            overworld_size: self.overworld_size,
+            chunk_config: self.chunk_config,
+            spawn_chunk_radius: self.spawn_chunk_radius,
+            dungeon_gens,
+            simulation_mode: self.simulation_mode,
+            death_mode: self.death_mode,
+            npc_config: self.npc_config,
+            villager_config: self.villager_config,
+            starting_quests: self.starting_quests,
+            stairs_quest_reward: self.stairs_quest_reward,
+        })
     }
 }
 
 #[derive(Debug)]
 pub struct Generator {
-    map_gen: map::Generator,
+    overworld_size: CoordPair,
+    chunk_config: chunk::Config,
+    spawn_chunk_radius: Coord,
+    dungeon_gens: Vec<map::Generator>,
+    simulation_mode: SimulationMode,
+    death_mode: DeathMode,
+    npc_config: npc::Config,
+    villager_config: npc::Config,
+    starting_quests: Vec<Quest>,
+    stairs_quest_reward: Option<Reward>,
 }
 
 impl Generator {
     pub fn progress_goal(&self) -> usize {
-        self.map_gen.progress_goal() + 1
+        let spawn_chunk_side = usize::from(self.spawn_chunk_radius) * 2 + 1;
+        spawn_chunk_side * spawn_chunk_side
+            + self
+                .dungeon_gens
+                .iter()
+                .map(map::Generator::progress_goal)
+                .sum::<usize>()
+            + 2
     }
 
     pub async fn execute(
@@ -88,12 +277,21 @@ impl Generator {
         rng: &mut PickedReproducibleRng,
         progress_logger: progress::Logger,
     ) -> Result<Game, Error> {
-        progress_logger.set_status("generating map");
-        let map = self.map_gen.execute(rng, progress_logger.nest()).await?;
+        progress_logger.set_status("generating overworld");
+        let mut settlements = settlement::Centers::new();
+        let mut map =
+            self.generate_overworld(rng, &mut settlements, &progress_logger)?;
+
+        let mut dungeon_maps = Vec::with_capacity(self.dungeon_gens.len());
+        for dungeon_gen in self.dungeon_gens {
+            progress_logger.set_status("generating dungeon level");
+            dungeon_maps
+                .push(dungeon_gen.execute(rng, progress_logger.nest()).await?);
+        }
 
         progress_logger.set_status("generating player");
-        let player_head_distr = map
-            .rect()
+        let spawn_rect = map.loaded_rect().unwrap_or(map.rect());
+        let player_head_distr = spawn_rect
             .size
             .map_with_axes(|coord, axis| {
                 let min = 2.0;
@@ -105,16 +303,131 @@ impl Generator {
             .transpose()?;
         let player_head_offset =
             player_head_distr.as_ref().map(|distr| rng.sample(distr) as Coord);
-        let player_head = map.rect().top_left + player_head_offset;
+        let player_head = spawn_rect.top_left + player_head_offset;
         let player_facing_index = rng.random_range(0 .. Direction::ALL.len());
         let player_facing = Direction::ALL[player_facing_index];
         let player_pos = PlayerPosition::new(player_head, player_facing)?;
+        for point in [player_pos.head(), player_pos.pointer()] {
+            map.set_placeable_block(point, PlaceableBlock::Air)
+                .map_err(Error::ClearPlayerSpawn)?;
+        }
         let player_hp = Player::DEFAULT_HP;
         let player = Player::new(player_pos, player_hp);
-        let game = Game::new(map, player)?;
+        let spawn = player.position().head();
+
+        progress_logger.set_status("placing stairs");
+        let mut stairs = Vec::with_capacity(dungeon_maps.len());
+        let mut upper_map = &mut map;
+        for lower_map in &mut dungeon_maps {
+            let down = loop {
+                let point = Self::place_stairs(
+                    rng,
+                    upper_map,
+                    PlaceableBlock::StairsDown,
+                )?;
+                if point.zip2(spawn).any(|(a, b)| a.abs_diff(b) > 2) {
+                    break point;
+                }
+            };
+            let up =
+                Self::place_stairs(rng, lower_map, PlaceableBlock::StairsUp)?;
+            stairs.push((down, up));
+            upper_map = lower_map;
+        }
+
+        let mut game = Game::new(map, player)?;
+        game.set_simulation_mode(self.simulation_mode);
+        game.set_death_mode(self.death_mode);
+        progress_logger.set_status("spawning NPCs");
+        self.npc_config.spawn_around(&mut game, spawn, rng)?;
+        for center in settlements {
+            self.villager_config.spawn_around(&mut game, center, rng)?;
+        }
+        progress_logger.increment();
+
+        for quest in self.starting_quests {
+            game.accept_quest(quest);
+        }
+        if let (Some(reward), Some((down, _))) =
+            (self.stairs_quest_reward, stairs.first())
+        {
+            game.accept_quest(Quest::new(
+                "Into the Depths",
+                Objective::Reach {
+                    map: game.current_map_id(),
+                    point: *down,
+                    radius: 1,
+                },
+                reward,
+            ));
+        }
+
+        let mut upper_id = game.current_map_id();
+        for (lower_map, (down, up)) in dungeon_maps.into_iter().zip(stairs) {
+            let lower_id = game.add_map(lower_map)?;
+            game.link_portals(
+                PortalEnd { map: upper_id, point: down },
+                PortalEnd { map: lower_id, point: up },
+            )?;
+            upper_id = lower_id;
+        }
         progress_logger.increment();
 
         progress_logger.set_status("done");
         Ok(game)
     }
+
+    /// Creates a lazily generated overworld, generating only the chunks
+    /// around its center, where the player spawns.
+    fn generate_overworld(
+        &self,
+        rng: &mut PickedReproducibleRng,
+        settlements: &mut settlement::Centers,
+        progress_logger: &progress::Logger,
+    ) -> Result<Map, Error> {
+        let rect = Rect {
+            top_left: CoordPair::from_axes(|_| 0),
+            size: self.overworld_size,
+        };
+        let mut map = Map::new_lazy(rect, rng.random())?;
+        let grid_size = map.chunk_grid_size();
+        let center = grid_size.map(|coord| coord / 2);
+        let radius = self.spawn_chunk_radius;
+        for y in center.y.saturating_sub(radius) ..= center.y + radius {
+            for x in center.x.saturating_sub(radius) ..= center.x + radius {
+                let index = CoordPair { y, x };
+                if index.zip2(grid_size).all(|(coord, size)| coord < size) {
+                    let chunk = self.chunk_config.generate_with(
+                        &map,
+                        index,
+                        &mut *settlements,
+                    )?;
+                    map.load_chunk(index, chunk)?;
+                }
+                progress_logger.increment();
+            }
+        }
+        Ok(map)
+    }
+
+    /// Places stairs at a random point of the map, clearing its surroundings
+    /// so that the player can always arrive there.
+    fn place_stairs(
+        rng: &mut PickedReproducibleRng,
+        map: &mut Map,
+        block: PlaceableBlock,
+    ) -> Result<CoordPair, Error> {
+        let rect = map.loaded_rect().unwrap_or(map.rect());
+        let point = rect.size.map(|coord| rng.random_range(2 .. coord - 2))
+            + rect.top_left;
+        for direction in Direction::ALL {
+            map.set_placeable_block(
+                point.move_unit(direction),
+                PlaceableBlock::Air,
+            )
+            .map_err(Error::PlaceStairs)?;
+        }
+        map.set_placeable_block(point, block).map_err(Error::PlaceStairs)?;
+        Ok(point)
+    }
 }
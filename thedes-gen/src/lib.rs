@@ -6,8 +6,13 @@ use thiserror::Error;
 pub mod random;
 pub mod matter;
 pub mod map;
+pub mod chunk;
+pub mod structure;
+pub mod settlement;
 pub mod game;
 pub mod event;
+pub mod loot;
+pub mod npc;
 
 #[derive(Debug, Error)]
 pub enum InitError {
@@ -0,0 +1,150 @@
+use std::array;
+
+use rand::Rng;
+use rand_distr::Distribution;
+use thedes_domain::{
+    item::{ItemCount, ItemKind, ItemStack},
+    monster::Species,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum InvalidLootEntry {
+    #[error("Loot chance {0}% is above 100%")]
+    Chance(u8),
+    #[error("Loot count range {min}..={max} is not valid for {kind}")]
+    CountRange { kind: ItemKind, min: ItemCount, max: ItemCount },
+}
+
+/// A possible drop of a loot table: with the given chance, a stack with
+/// between `min` and `max` items is dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LootEntry {
+    kind: ItemKind,
+    chance_percent: u8,
+    min: ItemCount,
+    max: ItemCount,
+}
+
+impl LootEntry {
+    pub fn new(
+        kind: ItemKind,
+        chance_percent: u8,
+        min: ItemCount,
+        max: ItemCount,
+    ) -> Result<Self, InvalidLootEntry> {
+        if chance_percent > 100 {
+            Err(InvalidLootEntry::Chance(chance_percent))?
+        }
+        if min < 1 || min > max || max > kind.max_stack() {
+            Err(InvalidLootEntry::CountRange { kind, min, max })?
+        }
+        Ok(Self { kind, chance_percent, min, max })
+    }
+
+    pub fn kind(&self) -> ItemKind {
+        self.kind
+    }
+
+    pub fn chance_percent(&self) -> u8 {
+        self.chance_percent
+    }
+
+    pub fn min(&self) -> ItemCount {
+        self.min
+    }
+
+    pub fn max(&self) -> ItemCount {
+        self.max
+    }
+}
+
+/// Every entry is rolled independently, so a table can drop none, some or
+/// all of its entries at once.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LootTable {
+    entries: Vec<LootEntry>,
+}
+
+impl LootTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_entry(mut self, entry: LootEntry) -> Self {
+        self.entries.push(entry);
+        self
+    }
+
+    pub fn entries(&self) -> &[LootEntry] {
+        &self.entries
+    }
+}
+
+impl Distribution<Vec<ItemStack>> for LootTable {
+    fn sample<R>(&self, rng: &mut R) -> Vec<ItemStack>
+    where
+        R: Rng + ?Sized,
+    {
+        let mut stacks = Vec::new();
+        for entry in &self.entries {
+            if rng.random_range(0 .. 100) < entry.chance_percent {
+                let count = rng.random_range(entry.min ..= entry.max);
+                let stack = ItemStack::new(entry.kind, count)
+                    .expect("entry count range should have been validated");
+                stacks.push(stack);
+            }
+        }
+        stacks
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    tables: [LootTable; Species::COUNT],
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Config {
+    pub fn new() -> Self {
+        let entry = |kind, chance_percent, min, max| LootEntry {
+            kind,
+            chance_percent,
+            min,
+            max,
+        };
+        let tables = array::from_fn(|i| match Species::ALL[i] {
+            Species::Bat => {
+                LootTable::new().with_entry(entry(ItemKind::Berry, 30, 1, 2))
+            },
+            Species::Wolf => LootTable::new()
+                .with_entry(entry(ItemKind::Berry, 40, 1, 3))
+                .with_entry(entry(ItemKind::Wood, 20, 1, 2)),
+            Species::Scorpion => LootTable::new()
+                .with_entry(entry(ItemKind::Stone, 40, 1, 3))
+                .with_entry(entry(ItemKind::HealingPotion, 10, 1, 1)),
+            Species::Snake => LootTable::new()
+                .with_entry(entry(ItemKind::Berry, 40, 1, 2))
+                .with_entry(entry(ItemKind::HealingPotion, 15, 1, 1)),
+            Species::Ghoul => LootTable::new()
+                .with_entry(entry(ItemKind::Stone, 50, 2, 5))
+                .with_entry(entry(ItemKind::HealingPotion, 35, 1, 2))
+                .with_entry(entry(ItemKind::StoneSword, 5, 1, 1)),
+        });
+        Self { tables }
+    }
+
+    pub fn with_table(mut self, species: Species, table: LootTable) -> Self {
+        self.tables[species as usize] = table;
+        self
+    }
+
+    pub fn table(&self, species: Species) -> &LootTable {
+        &self.tables[species as usize]
+    }
+}
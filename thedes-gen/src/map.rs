@@ -1,12 +1,20 @@
 use std::convert::Infallible;
 
-use layer::matter::{
-    BiomeLayer,
-    BiomeLayerError,
-    GroundDistrError,
-    GroundLayer,
-    GroundLayerDistr,
-    GroundLayerError,
+use layer::{
+    block::{BlockDistrError, BlockLayer, BlockLayerDistr, BlockLayerError},
+    matter::{
+        BiomeDistrError,
+        BiomeLayer,
+        BiomeLayerDistr,
+        BiomeLayerError,
+        ClimateConfig,
+        GroundDistrError,
+        GroundLayer,
+        GroundLayerDistr,
+        GroundLayerError,
+    },
+    neighbourhood::MajorityVote,
+    water::{WaterDistrError, WaterLayerDistr},
 };
 use rand::Rng;
 use rand_distr::{Triangular, TriangularError};
@@ -18,7 +26,7 @@ use thedes_domain::{
 use thedes_geometry::orientation::Axis;
 use thiserror::Error;
 
-use crate::{matter::BiomeDistr, random::PickedReproducibleRng};
+use crate::random::PickedReproducibleRng;
 
 pub mod layer;
 
@@ -34,8 +42,6 @@ pub enum InvalidConfig {
 
 #[derive(Debug, Error)]
 pub enum InitError {
-    #[error("Failed to initialize biome layer generator")]
-    Biome(#[source] layer::region::InitError),
     #[error("Error creating random distribution for map top left's axis {1}")]
     TopLeftDistr(#[source] TriangularError, Axis),
     #[error("Error creating random distribution for map size's axis {1}")]
@@ -54,7 +60,13 @@ pub enum Error {
     BiomeLayer(
         #[source]
         #[from]
-        layer::region::Error<BiomeLayerError, Infallible, Infallible>,
+        layer::pointwise::Error<BiomeLayerError, BiomeDistrError>,
+    ),
+    #[error("Error smoothing map biome layer")]
+    BiomeSmoothing(
+        #[source]
+        #[from]
+        layer::neighbourhood::Error<BiomeLayerError, Infallible>,
     ),
     #[error("Error generating map ground layer")]
     GroundLayer(
@@ -62,6 +74,12 @@ pub enum Error {
         #[from]
         layer::pointwise::Error<GroundLayerError, GroundDistrError>,
     ),
+    #[error("Error generating map block layer")]
+    BlockLayer(
+        #[source] layer::pointwise::Error<BlockLayerError, BlockDistrError>,
+    ),
+    #[error("Error generating map lakes and rivers")]
+    Water(#[source] layer::pointwise::Error<BlockLayerError, WaterDistrError>),
 }
 
 #[derive(Debug, Clone)]
@@ -70,9 +88,11 @@ pub struct Config {
     max_top_left: CoordPair,
     min_size: CoordPair,
     max_size: CoordPair,
-    biome_distr: BiomeDistr,
-    biome_layer_config: layer::region::Config,
+    climate: ClimateConfig,
+    biome_smoothing: usize,
     ground_layer_distr: GroundLayerDistr,
+    water: layer::water::Config,
+    block_layer_distr: BlockLayerDistr,
 }
 
 impl Default for Config {
@@ -88,9 +108,24 @@ impl Config {
             max_top_left: CoordPair { y: 10_000, x: 10_000 },
             min_size: CoordPair { y: 950, x: 950 },
             max_size: CoordPair { y: 1050, x: 1050 },
-            biome_distr: BiomeDistr::default(),
-            biome_layer_config: layer::region::Config::new(),
+            climate: ClimateConfig::new(),
+            biome_smoothing: 2,
             ground_layer_distr: GroundLayerDistr::default(),
+            water: layer::water::Config::new(),
+            block_layer_distr: BlockLayerDistr::default(),
+        }
+    }
+
+    /// Same as [`Config::new`], but with the smaller sizes of dungeon
+    /// levels.
+    pub fn new_dungeon() -> Self {
+        Self {
+            min_size: CoordPair { y: 180, x: 180 },
+            max_size: CoordPair { y: 220, x: 220 },
+            water: layer::water::Config::new()
+                .with_lake_level(0.22)
+                .expect("lake level is within bounds"),
+            ..Self::new()
         }
     }
 
@@ -162,18 +197,29 @@ impl Config {
         Ok(Self { max_size, ..self })
     }
 
-    pub fn with_biome_layer(self, config: layer::region::Config) -> Self {
-        Self { biome_layer_config: config, ..self }
+    pub fn with_climate(self, config: ClimateConfig) -> Self {
+        Self { climate: config, ..self }
     }
 
-    pub fn with_biome_distr(self, distr: BiomeDistr) -> Self {
-        Self { biome_distr: distr, ..self }
+    /// Iterations of majority-vote smoothing over biome borders, `0`
+    /// disables it.
+    pub fn with_biome_smoothing(self, iterations: usize) -> Self {
+        Self { biome_smoothing: iterations, ..self }
     }
 
     pub fn with_ground_layer_distr(self, distr: GroundLayerDistr) -> Self {
         Self { ground_layer_distr: distr, ..self }
     }
 
+    pub fn with_block_layer_distr(self, distr: BlockLayerDistr) -> Self {
+        Self { block_layer_distr: distr, ..self }
+    }
+
+    /// Lakes and rivers, placed after the ground layer.
+    pub fn with_water(self, config: layer::water::Config) -> Self {
+        Self { water: config, ..self }
+    }
+
     pub fn finish(
         self,
         rng: &mut PickedReproducibleRng,
@@ -206,14 +252,29 @@ impl Config {
 
         let map = Map::new(rect)?;
 
-        let biome_layer_gen = self
-            .biome_layer_config
-            .clone()
-            .finish(&map, rng)
-            .map_err(InitError::Biome)?;
+        let biome_layer_distr = self.climate.finish(rng.random());
+        let water_layer_distr = self
+            .water
+            .finish(rng.random(), rect, *biome_layer_distr.elevation())
+            .layer_distr(rect);
+        let biome_layer_gen = layer::pointwise::Generator::new();
+        let biome_smoothing_gen = layer::neighbourhood::Generator::new()
+            .with_iterations(self.biome_smoothing);
         let ground_layer_gen = layer::pointwise::Generator::new();
+        let water_gen = layer::pointwise::Generator::new();
+        let block_layer_gen = layer::pointwise::Generator::new();
 
-        Ok(Generator { config: self, map, biome_layer_gen, ground_layer_gen })
+        Ok(Generator {
+            config: self,
+            map,
+            biome_layer_distr,
+            biome_layer_gen,
+            biome_smoothing_gen,
+            ground_layer_gen,
+            water_layer_distr,
+            water_gen,
+            block_layer_gen,
+        })
     }
 }
 
@@ -221,14 +282,22 @@ impl Config {
 pub struct Generator {
     config: Config,
     map: Map,
-    biome_layer_gen: layer::region::Generator,
+    biome_layer_distr: BiomeLayerDistr,
+    biome_layer_gen: layer::pointwise::Generator,
+    biome_smoothing_gen: layer::neighbourhood::Generator,
     ground_layer_gen: layer::pointwise::Generator,
+    water_layer_distr: WaterLayerDistr,
+    water_gen: layer::pointwise::Generator,
+    block_layer_gen: layer::pointwise::Generator,
 }
 
 impl Generator {
     pub fn progress_goal(&self) -> usize {
         self.biome_layer_gen.progress_goal(&self.map)
+            + self.biome_smoothing_gen.progress_goal(&self.map)
             + self.ground_layer_gen.progress_goal(&self.map)
+            + self.water_gen.progress_goal(&self.map)
+            + self.block_layer_gen.progress_goal(&self.map)
     }
 
     pub async fn execute(
@@ -240,10 +309,20 @@ impl Generator {
         self.biome_layer_gen
             .execute(
                 &BiomeLayer,
-                &mut self.config.biome_distr,
+                &self.biome_layer_distr,
+                &mut self.map,
+                rng,
+                progress_logger.nest(),
+            )
+            .await?;
+
+        progress_logger.set_status("smoothing biome borders");
+        self.biome_smoothing_gen
+            .execute(
+                &BiomeLayer,
+                &MajorityVote::new(),
                 &mut self.map,
                 rng,
-                &mut layer::region::NopCollector,
                 progress_logger.nest(),
             )
             .await?;
@@ -259,6 +338,30 @@ impl Generator {
             )
             .await?;
 
+        progress_logger.set_status("generating lakes and rivers");
+        self.water_gen
+            .execute(
+                &BlockLayer,
+                &self.water_layer_distr,
+                &mut self.map,
+                rng,
+                progress_logger.nest(),
+            )
+            .await
+            .map_err(Error::Water)?;
+
+        progress_logger.set_status("generating block layer");
+        self.block_layer_gen
+            .execute(
+                &BlockLayer,
+                &self.config.block_layer_distr,
+                &mut self.map,
+                rng,
+                progress_logger.nest(),
+            )
+            .await
+            .map_err(Error::BlockLayer)?;
+
         progress_logger.set_status("done");
 
         Ok(self.map)
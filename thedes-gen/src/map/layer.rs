@@ -1,10 +1,13 @@
 use rand::Rng;
 use thedes_domain::{geometry::CoordPair, map::Map};
 
-pub mod region;
 pub mod pointwise;
+pub mod neighbourhood;
+pub mod noise;
 
 pub mod matter;
+pub mod water;
+pub mod block;
 
 pub trait Layer {
     type Data;
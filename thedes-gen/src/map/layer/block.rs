@@ -0,0 +1,116 @@
+use std::array;
+
+use rand::Rng;
+use thedes_domain::{
+    block::PlaceableBlock,
+    geometry::CoordPair,
+    map::{AccessError, Map},
+    matter::Biome,
+};
+
+use crate::random::ProabilityWeight;
+
+use super::{Layer, LayerDistribution};
+
+pub type BlockLayerError = AccessError;
+pub type BlockDistrError = AccessError;
+
+#[derive(Debug, Clone)]
+pub struct BlockLayer;
+
+impl Layer for BlockLayer {
+    type Data = PlaceableBlock;
+    type Error = BlockLayerError;
+
+    fn get(
+        &self,
+        map: &mut Map,
+        point: CoordPair,
+    ) -> Result<Self::Data, Self::Error> {
+        map.get_covered_block(point)
+    }
+
+    fn set(
+        &self,
+        map: &mut Map,
+        point: CoordPair,
+        value: Self::Data,
+    ) -> Result<(), Self::Error> {
+        map.set_placeable_block(point, value)
+    }
+}
+
+/// Points already holding a block, such as water, keep it.
+#[derive(Debug, Clone)]
+pub struct BlockLayerDistr {
+    cumulative_weights:
+        [[ProabilityWeight; PlaceableBlock::COUNT]; Biome::COUNT],
+}
+
+impl Default for BlockLayerDistr {
+    fn default() -> Self {
+        Self::new(|biome, block| match (biome, block) {
+            (Biome::Plains, PlaceableBlock::Air) => 200,
+            (Biome::Plains, PlaceableBlock::Tree) => 12,
+            (Biome::Plains, PlaceableBlock::Rock) => 1,
+            (Biome::Desert, PlaceableBlock::Air) => 400,
+            (Biome::Desert, PlaceableBlock::Tree) => 1,
+            (Biome::Desert, PlaceableBlock::Rock) => 3,
+            (Biome::Wasteland, PlaceableBlock::Air) => 150,
+            (Biome::Wasteland, PlaceableBlock::Rock) => 10,
+            _ => 0,
+        })
+    }
+}
+
+impl BlockLayerDistr {
+    pub fn new<F>(mut density_function: F) -> Self
+    where
+        F: FnMut(Biome, PlaceableBlock) -> ProabilityWeight,
+    {
+        let cumulative_weights = array::from_fn(|i| {
+            let mut accumuled_weight = 0;
+            array::from_fn(|j| {
+                accumuled_weight +=
+                    density_function(Biome::ALL[i], PlaceableBlock::ALL[j]);
+                accumuled_weight
+            })
+        });
+        Self { cumulative_weights }
+    }
+}
+
+impl LayerDistribution for BlockLayerDistr {
+    type Data = PlaceableBlock;
+    type Error = BlockDistrError;
+
+    fn sample<R>(
+        &self,
+        map: &mut Map,
+        point: CoordPair,
+        mut rng: R,
+    ) -> Result<Self::Data, Self::Error>
+    where
+        R: Rng,
+    {
+        let current = map.get_covered_block(point)?;
+        if current != PlaceableBlock::Air {
+            return Ok(current);
+        }
+        let biome = map.get_biome(point)?;
+        let cumulative_weights = self.cumulative_weights[biome as usize];
+        let last_cumulative_weight =
+            cumulative_weights[cumulative_weights.len() - 1];
+        if last_cumulative_weight == 0 {
+            return Ok(PlaceableBlock::default());
+        }
+        let sampled_weight = rng.random_range(0 .. last_cumulative_weight);
+        for (i, cumulative_weight) in cumulative_weights.into_iter().enumerate()
+        {
+            if sampled_weight < cumulative_weight {
+                return Ok(PlaceableBlock::ALL[i]);
+            }
+        }
+        panic!("sampled weight {sampled_weight} is out of requested bounds")
+    }
+}
@@ -1,14 +1,26 @@
+use std::convert::Infallible;
+
 use thedes_domain::{
     geometry::CoordPair,
     map::{AccessError, Map},
     matter::{Biome, Ground},
 };
 
-use super::{Layer, LayerDistribution};
+use crate::{matter::BiomeTable, random::derive_seed};
+
+use super::{
+    Layer,
+    LayerDistribution,
+    noise::{self, ValueNoise},
+};
+
+#[cfg(test)]
+mod test;
 
 pub type GroundLayerError = AccessError;
 pub type BiomeLayerError = AccessError;
 pub type GroundDistrError = AccessError;
+pub type BiomeDistrError = Infallible;
 
 #[derive(Debug, Clone)]
 pub struct GroundLayer;
@@ -92,3 +104,104 @@ impl LayerDistribution for GroundLayerDistr {
         Ok(ground)
     }
 }
+
+/// Configures the elevation and moisture noises behind biome placement.
+#[derive(Debug, Clone)]
+pub struct ClimateConfig {
+    elevation: noise::Config,
+    moisture: noise::Config,
+    table: BiomeTable,
+}
+
+impl Default for ClimateConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClimateConfig {
+    const ELEVATION_STREAM: u64 = 0;
+
+    const MOISTURE_STREAM: u64 = 1;
+
+    pub fn new() -> Self {
+        Self {
+            elevation: noise::Config::new(),
+            moisture: noise::Config::new()
+                .with_cell_size(96)
+                .expect("cell size is positive"),
+            table: BiomeTable::default(),
+        }
+    }
+
+    pub fn with_elevation(self, config: noise::Config) -> Self {
+        Self { elevation: config, ..self }
+    }
+
+    pub fn with_moisture(self, config: noise::Config) -> Self {
+        Self { moisture: config, ..self }
+    }
+
+    pub fn with_biome_table(self, table: BiomeTable) -> Self {
+        Self { table, ..self }
+    }
+
+    pub fn finish(&self, seed: u64) -> BiomeLayerDistr {
+        BiomeLayerDistr {
+            elevation: self
+                .elevation
+                .finish(derive_seed(seed, Self::ELEVATION_STREAM)),
+            moisture: self
+                .moisture
+                .finish(derive_seed(seed, Self::MOISTURE_STREAM)),
+            table: self.table.clone(),
+        }
+    }
+}
+
+/// Places biomes by looking elevation and moisture up in a biome table, so
+/// that neighbouring biomes follow smooth changes in climate.
+#[derive(Debug, Clone)]
+pub struct BiomeLayerDistr {
+    elevation: ValueNoise,
+    moisture: ValueNoise,
+    table: BiomeTable,
+}
+
+impl BiomeLayerDistr {
+    pub fn elevation(&self) -> &ValueNoise {
+        &self.elevation
+    }
+
+    pub fn moisture(&self) -> &ValueNoise {
+        &self.moisture
+    }
+
+    pub fn table(&self) -> &BiomeTable {
+        &self.table
+    }
+
+    pub fn biome_at(&self, point: CoordPair) -> Biome {
+        self.table.biome(
+            self.elevation.value_at(point),
+            self.moisture.value_at(point),
+        )
+    }
+}
+
+impl LayerDistribution for BiomeLayerDistr {
+    type Data = Biome;
+    type Error = BiomeDistrError;
+
+    fn sample<R>(
+        &self,
+        _map: &mut Map,
+        point: CoordPair,
+        _rng: R,
+    ) -> Result<Self::Data, Self::Error>
+    where
+        R: rand::Rng,
+    {
+        Ok(self.biome_at(point))
+    }
+}
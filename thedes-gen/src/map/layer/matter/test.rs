@@ -0,0 +1,58 @@
+use std::collections::BTreeSet;
+
+use thedes_domain::{geometry::CoordPair, matter::Biome};
+
+use crate::matter::{Elevation, Moisture};
+
+use super::ClimateConfig;
+
+fn points() -> impl Iterator<Item = CoordPair> {
+    (0 .. 1024)
+        .step_by(8)
+        .flat_map(|y| (0 .. 1024).step_by(8).map(move |x| CoordPair { y, x }))
+}
+
+#[test]
+fn climate_is_deterministic_per_seed() {
+    let config = ClimateConfig::new();
+    let climate = config.finish(1234);
+    let again = config.finish(1234);
+    let other = config.finish(4321);
+    for point in points() {
+        assert_eq!(climate.biome_at(point), again.biome_at(point));
+    }
+    assert!(
+        points().any(|point| climate.biome_at(point) != other.biome_at(point))
+    );
+}
+
+#[test]
+fn elevation_and_moisture_are_independent() {
+    let climate = ClimateConfig::new().finish(1234);
+    assert!(points().any(|point| {
+        climate.elevation().value_at(point)
+            != climate.moisture().value_at(point)
+    }));
+}
+
+#[test]
+fn biomes_follow_climate_bands() {
+    let climate = ClimateConfig::new().finish(99);
+    let mut elevations = BTreeSet::new();
+    let mut moistures = BTreeSet::new();
+    let mut biomes = BTreeSet::new();
+    for point in points() {
+        let elevation =
+            climate.table().elevation(climate.elevation().value_at(point));
+        let moisture =
+            climate.table().moisture(climate.moisture().value_at(point));
+        let biome = climate.biome_at(point);
+        assert_eq!(biome, climate.table().get(elevation, moisture));
+        elevations.insert(elevation);
+        moistures.insert(moisture);
+        biomes.insert(biome);
+    }
+    assert_eq!(elevations.len(), Elevation::COUNT);
+    assert_eq!(moistures.len(), Moisture::COUNT);
+    assert_eq!(biomes.len(), Biome::COUNT);
+}
@@ -0,0 +1,342 @@
+use std::{convert::Infallible, marker::PhantomData};
+
+use rand::Rng;
+use thedes_async_util::progress;
+use thedes_domain::{
+    geometry::{Coord, CoordPair, Rect},
+    map::Map,
+};
+use thiserror::Error;
+use tokio::task;
+
+use crate::random::PickedReproducibleRng;
+
+use super::Layer;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Error)]
+pub enum Error<L, Nd>
+where
+    L: std::error::Error,
+    Nd: std::error::Error,
+{
+    #[error("Failed to manipulate layer")]
+    Layer(#[source] L),
+    #[error("Failed to manipulate neighbourhood distribution")]
+    NeighbourhoodDistribution(#[source] Nd),
+}
+
+/// Moore neighbourhood of a point: the point itself and its 8 surrounding
+/// points, as they were before the current iteration. Points outside of the
+/// map are absent.
+#[derive(Debug, Clone)]
+pub struct Neighbourhood<T> {
+    center: T,
+    neighbours: [Option<T>; 8],
+}
+
+impl<T> Neighbourhood<T> {
+    pub fn center(&self) -> &T {
+        &self.center
+    }
+
+    /// Surrounding points inside of the map, center excluded.
+    pub fn neighbours(&self) -> impl Iterator<Item = &T> + '_ {
+        self.neighbours.iter().flatten()
+    }
+
+    /// How many surrounding points lie outside of the map.
+    pub fn off_map(&self) -> usize {
+        self.neighbours.iter().filter(|neighbour| neighbour.is_none()).count()
+    }
+
+    /// Counts the surrounding points (center excluded) satisfying the given
+    /// predicate.
+    pub fn count<F>(&self, mut predicate: F) -> usize
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.neighbours().filter(|neighbour| predicate(neighbour)).count()
+    }
+}
+
+pub trait NeighbourhoodDistribution {
+    type Data;
+    type Error;
+
+    fn sample<R>(
+        &self,
+        neighbourhood: &Neighbourhood<Self::Data>,
+        rng: R,
+    ) -> Result<Self::Data, Self::Error>
+    where
+        R: Rng;
+}
+
+/// Replaces a point by the most common value of its neighbourhood (center
+/// included), if that value covers at least `threshold` of the 9 points.
+/// Removes single-point noise along region borders.
+#[derive(Debug, Clone)]
+pub struct MajorityVote<T> {
+    threshold: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for MajorityVote<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> MajorityVote<T> {
+    pub fn new() -> Self {
+        Self { threshold: 5, _marker: PhantomData }
+    }
+
+    pub fn with_threshold(self, threshold: usize) -> Self {
+        Self { threshold, ..self }
+    }
+}
+
+impl<T> NeighbourhoodDistribution for MajorityVote<T>
+where
+    T: Clone + Eq,
+{
+    type Data = T;
+    type Error = Infallible;
+
+    fn sample<R>(
+        &self,
+        neighbourhood: &Neighbourhood<Self::Data>,
+        _rng: R,
+    ) -> Result<Self::Data, Self::Error>
+    where
+        R: Rng,
+    {
+        let center = neighbourhood.center();
+        let mut winner = center;
+        let mut winner_votes = 1 + neighbourhood.count(|data| data == center);
+        for candidate in neighbourhood.neighbours() {
+            if candidate == winner || candidate == center {
+                continue;
+            }
+            let votes = neighbourhood.count(|data| data == candidate);
+            if votes > winner_votes {
+                winner = candidate;
+                winner_votes = votes;
+            }
+        }
+        if winner_votes >= self.threshold {
+            Ok(winner.clone())
+        } else {
+            Ok(center.clone())
+        }
+    }
+}
+
+/// Cave-style automaton between a `wall` and a `floor` value. A wall with
+/// fewer than `survival_limit` walls around becomes floor, and a floor with
+/// at least `birth_limit` walls around becomes wall. Points outside of the
+/// map count as walls; any other value is left untouched.
+#[derive(Debug, Clone)]
+pub struct CaveAutomaton<T> {
+    wall: T,
+    floor: T,
+    birth_limit: usize,
+    survival_limit: usize,
+}
+
+impl<T> CaveAutomaton<T> {
+    pub fn new(wall: T, floor: T) -> Self {
+        Self { wall, floor, birth_limit: 5, survival_limit: 4 }
+    }
+
+    pub fn with_birth_limit(self, birth_limit: usize) -> Self {
+        Self { birth_limit, ..self }
+    }
+
+    pub fn with_survival_limit(self, survival_limit: usize) -> Self {
+        Self { survival_limit, ..self }
+    }
+}
+
+impl<T> NeighbourhoodDistribution for CaveAutomaton<T>
+where
+    T: Clone + Eq,
+{
+    type Data = T;
+    type Error = Infallible;
+
+    fn sample<R>(
+        &self,
+        neighbourhood: &Neighbourhood<Self::Data>,
+        _rng: R,
+    ) -> Result<Self::Data, Self::Error>
+    where
+        R: Rng,
+    {
+        let center = neighbourhood.center();
+        let walls = neighbourhood.count(|data| *data == self.wall)
+            + neighbourhood.off_map();
+        let data = if *center == self.wall && walls < self.survival_limit {
+            self.floor.clone()
+        } else if *center == self.floor && walls >= self.birth_limit {
+            self.wall.clone()
+        } else {
+            center.clone()
+        };
+        Ok(data)
+    }
+}
+
+const NEIGHBOUR_OFFSETS: [(i32, i32); 8] =
+    [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+fn offset(point: CoordPair, dy: i32, dx: i32) -> Option<CoordPair> {
+    let y = Coord::try_from(i32::from(point.y) + dy).ok()?;
+    let x = Coord::try_from(i32::from(point.x) + dx).ok()?;
+    Some(CoordPair { y, x })
+}
+
+/// Index of a point of the rectangle in a row-major grid covering it.
+fn grid_index(rect: Rect, point: CoordPair) -> usize {
+    let offset = point - rect.top_left;
+    usize::from(offset.y) * usize::from(rect.size.x) + usize::from(offset.x)
+}
+
+/// Neighbourhood of a point in a snapshot of a grid covering the rectangle,
+/// where points outside of the rectangle are absent.
+fn neighbourhood_in<T>(
+    snapshot: &[T],
+    rect: Rect,
+    point: CoordPair,
+) -> Neighbourhood<T>
+where
+    T: Clone,
+{
+    let at = |point: Option<CoordPair>| {
+        point
+            .filter(|point| rect.contains_point(*point))
+            .map(|point| snapshot[grid_index(rect, point)].clone())
+    };
+    Neighbourhood {
+        center: snapshot[grid_index(rect, point)].clone(),
+        neighbours: NEIGHBOUR_OFFSETS.map(|(dy, dx)| at(offset(point, dy, dx))),
+    }
+}
+
+/// Counterpart of [`super::pointwise::Generator`] whose distribution sees the
+/// Moore neighbourhood of each point. Every iteration updates all points at
+/// once, from a snapshot of the layer taken before it.
+#[derive(Debug)]
+pub struct Generator {
+    iterations: usize,
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Generator {
+    pub fn new() -> Self {
+        Self { iterations: 1 }
+    }
+
+    pub fn with_iterations(self, iterations: usize) -> Self {
+        Self { iterations }
+    }
+
+    pub fn progress_goal(&self, map: &Map) -> usize {
+        map.rect().map(usize::from).total_area() * self.iterations
+    }
+
+    pub async fn execute<L, Nd>(
+        self,
+        layer: &L,
+        distr: &Nd,
+        map: &mut Map,
+        rng: &mut PickedReproducibleRng,
+        progress_logger: progress::Logger,
+    ) -> Result<(), Error<L::Error, Nd::Error>>
+    where
+        L: Layer,
+        L::Data: Clone,
+        L::Error: std::error::Error,
+        Nd: NeighbourhoodDistribution<Data = L::Data>,
+        Nd::Error: std::error::Error,
+    {
+        let map_rect = map.rect();
+
+        for iteration in 0 .. self.iterations {
+            progress_logger.set_status(&format!(
+                "running neighbourhood pass {}/{}",
+                iteration + 1,
+                self.iterations,
+            ));
+
+            let mut snapshot =
+                Vec::with_capacity(map_rect.map(usize::from).total_area());
+            for y in map_rect.top_left.y .. map_rect.bottom_right().y {
+                for x in map_rect.top_left.x .. map_rect.bottom_right().x {
+                    let point = CoordPair { y, x };
+                    snapshot.push(layer.get(map, point).map_err(Error::Layer)?);
+                }
+            }
+
+            for y in map_rect.top_left.y .. map_rect.bottom_right().y {
+                for x in map_rect.top_left.x .. map_rect.bottom_right().x {
+                    let point = CoordPair { y, x };
+                    let neighbourhood =
+                        neighbourhood_in(&snapshot, map_rect, point);
+                    let data = distr
+                        .sample(&neighbourhood, &mut *rng)
+                        .map_err(Error::NeighbourhoodDistribution)?;
+                    layer.set(map, point, data).map_err(Error::Layer)?;
+                    progress_logger.increment();
+                    task::yield_now().await;
+                }
+            }
+        }
+
+        progress_logger.set_status("done");
+        Ok(())
+    }
+
+    /// Same as [`Generator::execute`], but over a row-major grid of data
+    /// covering the given rectangle instead of a map layer, with points
+    /// outside of the rectangle absent. A point `n` tiles away from the
+    /// border of the rectangle sees the same data as it would in a larger
+    /// grid, as long as `n` is at least the iteration count and the
+    /// distribution does not use the RNG.
+    pub fn execute_on_grid<Nd, R>(
+        &self,
+        rect: Rect,
+        grid: &mut [Nd::Data],
+        distr: &Nd,
+        mut rng: R,
+    ) -> Result<(), Nd::Error>
+    where
+        Nd: NeighbourhoodDistribution,
+        Nd::Data: Clone,
+        R: Rng,
+    {
+        assert_eq!(grid.len(), rect.map(usize::from).total_area::<usize>());
+        for _ in 0 .. self.iterations {
+            let snapshot = grid.to_vec();
+            for y in rect.top_left.y .. rect.bottom_right().y {
+                for x in rect.top_left.x .. rect.bottom_right().x {
+                    let point = CoordPair { y, x };
+                    let neighbourhood =
+                        neighbourhood_in(&snapshot, rect, point);
+                    grid[grid_index(rect, point)] =
+                        distr.sample(&neighbourhood, &mut rng)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
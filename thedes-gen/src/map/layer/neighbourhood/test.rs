@@ -0,0 +1,85 @@
+use std::convert::Infallible;
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use thedes_domain::{
+    geometry::{CoordPair, Rect},
+    matter::Biome,
+};
+
+use super::{
+    Generator,
+    MajorityVote,
+    Neighbourhood,
+    NeighbourhoodDistribution,
+};
+
+fn rect() -> Rect {
+    Rect {
+        top_left: CoordPair { y: 10, x: 20 },
+        size: CoordPair { y: 5, x: 7 },
+    }
+}
+
+/// Counts how many times it ran on every point.
+#[derive(Debug)]
+struct Increment;
+
+impl NeighbourhoodDistribution for Increment {
+    type Data = usize;
+    type Error = Infallible;
+
+    fn sample<R>(
+        &self,
+        neighbourhood: &Neighbourhood<Self::Data>,
+        _rng: R,
+    ) -> Result<Self::Data, Self::Error>
+    where
+        R: Rng,
+    {
+        Ok(neighbourhood.center() + 1)
+    }
+}
+
+fn rng() -> StdRng {
+    StdRng::seed_from_u64(5)
+}
+
+#[test]
+fn lone_odd_tile_is_voted_away() {
+    let mut grid = vec![Biome::Plains; 35];
+    let odd = 2 * 7 + 3;
+    grid[odd] = Biome::Desert;
+    Generator::new()
+        .execute_on_grid(rect(), &mut grid, &MajorityVote::new(), rng())
+        .unwrap();
+    assert!(grid.iter().all(|biome| *biome == Biome::Plains));
+}
+
+#[test]
+fn votes_below_threshold_keep_center() {
+    let mut grid = vec![Biome::Plains; 35];
+    let odd = 2 * 7 + 3;
+    grid[odd] = Biome::Desert;
+    let before = grid.clone();
+    Generator::new()
+        .execute_on_grid(
+            rect(),
+            &mut grid,
+            &MajorityVote::new().with_threshold(9),
+            rng(),
+        )
+        .unwrap();
+    assert_eq!(grid, before);
+}
+
+#[test]
+fn iteration_count_is_respected() {
+    for iterations in [0, 1, 3] {
+        let mut grid = vec![0; 35];
+        Generator::new()
+            .with_iterations(iterations)
+            .execute_on_grid(rect(), &mut grid, &Increment, rng())
+            .unwrap();
+        assert!(grid.iter().all(|count| *count == iterations));
+    }
+}
@@ -0,0 +1,142 @@
+use std::convert::Infallible;
+
+use rand::Rng;
+use thedes_domain::{
+    geometry::{Coord, CoordPair},
+    map::Map,
+};
+use thiserror::Error;
+
+use crate::random::{derive_seed, positional_unit};
+
+use super::LayerDistribution;
+
+#[cfg(test)]
+mod test;
+
+/// Noise values are in the range `0.0 ..= 1.0`.
+pub type NoiseValue = f64;
+
+#[derive(Debug, Error)]
+pub enum InvalidConfig {
+    #[error("Noise cell size must be positive")]
+    ZeroCellSize,
+    #[error("Noise must have at least one octave")]
+    ZeroOctaves,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Config {
+    cell_size: Coord,
+    octaves: u8,
+    persistence: f64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self { cell_size: 128, octaves: 4, persistence: 0.5 }
+    }
+
+    /// Distance between lattice points of the coarsest octave, that is,
+    /// roughly how wide the features of the noise are.
+    pub fn with_cell_size(self, size: Coord) -> Result<Self, InvalidConfig> {
+        if size == 0 {
+            Err(InvalidConfig::ZeroCellSize)?
+        }
+        Ok(Self { cell_size: size, ..self })
+    }
+
+    /// Every octave halves the cell size of the previous one, adding finer
+    /// detail.
+    pub fn with_octaves(self, octaves: u8) -> Result<Self, InvalidConfig> {
+        if octaves == 0 {
+            Err(InvalidConfig::ZeroOctaves)?
+        }
+        Ok(Self { octaves, ..self })
+    }
+
+    /// How much every octave weighs compared to the previous one.
+    pub fn with_persistence(self, persistence: f64) -> Self {
+        Self { persistence, ..self }
+    }
+
+    pub fn finish(self, seed: u64) -> ValueNoise {
+        ValueNoise { config: self, seed }
+    }
+}
+
+/// Seeded value noise: random values on a lattice, smoothly interpolated
+/// between lattice points, summed over several octaves. Values depend only
+/// on the seed and on the point, so any region can be sampled in any order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValueNoise {
+    config: Config,
+    seed: u64,
+}
+
+impl ValueNoise {
+    pub fn value_at(&self, point: CoordPair) -> NoiseValue {
+        let point = point.map(u32::from);
+        let mut cell_size = u32::from(self.config.cell_size);
+        let mut amplitude = 1.0;
+        let mut total = 0.0;
+        let mut total_amplitude = 0.0;
+        for octave in 0 .. self.config.octaves {
+            let seed = derive_seed(self.seed, u64::from(octave));
+            total += amplitude * Self::octave_at(seed, cell_size, point);
+            total_amplitude += amplitude;
+            amplitude *= self.config.persistence;
+            cell_size = (cell_size / 2).max(1);
+        }
+        if total_amplitude > 0.0 { total / total_amplitude } else { 0.0 }
+    }
+
+    fn octave_at(
+        seed: u64,
+        cell_size: u32,
+        point: thedes_geometry::CoordPair<u32>,
+    ) -> NoiseValue {
+        let cell = point.map(|coord| coord / cell_size);
+        let weight = point.map(|coord| {
+            let t = f64::from(coord % cell_size) / f64::from(cell_size);
+            t * t * (3.0 - 2.0 * t)
+        });
+        let corner = |dy, dx| {
+            let corner = thedes_geometry::CoordPair {
+                y: cell.y.wrapping_add(dy),
+                x: cell.x.wrapping_add(dx),
+            };
+            positional_unit(seed, corner)
+        };
+        let top = lerp(corner(0, 0), corner(0, 1), weight.x);
+        let bottom = lerp(corner(1, 0), corner(1, 1), weight.x);
+        lerp(top, bottom, weight.y)
+    }
+}
+
+fn lerp(start: f64, end: f64, t: f64) -> f64 {
+    start + (end - start) * t
+}
+
+impl LayerDistribution for ValueNoise {
+    type Data = NoiseValue;
+    type Error = Infallible;
+
+    fn sample<R>(
+        &self,
+        _map: &mut Map,
+        point: CoordPair,
+        _rng: R,
+    ) -> Result<Self::Data, Self::Error>
+    where
+        R: Rng,
+    {
+        Ok(self.value_at(point))
+    }
+}
@@ -0,0 +1,54 @@
+use thedes_domain::geometry::CoordPair;
+
+use super::Config;
+
+fn points() -> impl Iterator<Item = CoordPair> {
+    (0 .. 300)
+        .step_by(7)
+        .flat_map(|y| (0 .. 300).step_by(11).map(move |x| CoordPair { y, x }))
+}
+
+#[test]
+fn same_seed_gives_same_noise() {
+    let config = Config::new().with_cell_size(32).unwrap();
+    let noise = config.finish(42);
+    let again = config.finish(42);
+    for point in points() {
+        assert_eq!(noise.value_at(point), again.value_at(point));
+    }
+
+    let other = config.finish(43);
+    assert!(
+        points().any(|point| noise.value_at(point) != other.value_at(point))
+    );
+}
+
+#[test]
+fn noise_stays_in_unit_range() {
+    let noise = Config::new().with_cell_size(16).unwrap().finish(7);
+    for point in points() {
+        let value = noise.value_at(point);
+        assert!((0.0 ..= 1.0).contains(&value), "{value} at {point:?}");
+    }
+}
+
+#[test]
+fn noise_is_smooth_within_a_cell() {
+    let noise = Config::new()
+        .with_cell_size(64)
+        .unwrap()
+        .with_octaves(1)
+        .unwrap()
+        .finish(3);
+    for point in points() {
+        let next = CoordPair { y: point.y, x: point.x + 1 };
+        let step = (noise.value_at(point) - noise.value_at(next)).abs();
+        assert!(step < 0.1, "step of {step} at {point:?}");
+    }
+}
+
+#[test]
+fn invalid_config_is_rejected() {
+    assert!(Config::new().with_cell_size(0).is_err());
+    assert!(Config::new().with_octaves(0).is_err());
+}
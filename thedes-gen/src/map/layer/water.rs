@@ -0,0 +1,600 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, hash_map::Entry},
+};
+
+use rand::Rng;
+use thedes_domain::{
+    block::PlaceableBlock,
+    geometry::{Coord, CoordPair, Rect},
+    map::{AccessError, Map},
+};
+use thedes_geometry::orientation::Direction;
+use thiserror::Error;
+
+use crate::{
+    random::{
+        PickedReproducibleRng,
+        create_positional_rng,
+        derive_seed,
+        positional_unit,
+    },
+    structure::cells_in,
+};
+
+use super::{
+    LayerDistribution,
+    noise::{NoiseValue, ValueNoise},
+};
+
+#[cfg(test)]
+mod test;
+
+pub type WaterDistrError = AccessError;
+
+#[derive(Debug, Error)]
+pub enum InvalidConfig {
+    #[error("River width must be positive")]
+    ZeroRiverWidth,
+    #[error("Lake level {0} must be within 0 and 1")]
+    LakeLevel(NoiseValue),
+    #[error("Ford interval {interval} leaves no room for rivers {width} wide")]
+    FordInterval { interval: usize, width: Coord },
+    #[error("River cell size must be positive")]
+    ZeroCellSize,
+    #[error("River density {0} must be within 0 and 1")]
+    Density(f64),
+    #[error("Rivers must be at least one tile long")]
+    ZeroLength,
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    cell_size: Coord,
+    density: f64,
+    max_river_length: Coord,
+    river_width: Coord,
+    ford_interval: usize,
+    lake_level: NoiseValue,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Config {
+    /// Random points tried when looking for the highest spot of a cell to
+    /// start a river from.
+    const SOURCE_TRIES: usize = 16;
+
+    /// Points flooded at most when looking for a way out of a pit.
+    const PIT_FLOOD_LIMIT: usize = 512;
+
+    /// Distance between the points flooded when looking for a way out of a
+    /// pit.
+    const PIT_FLOOD_STEP: Coord = 4;
+
+    /// Distinct from the streams used by climate noise, structures and
+    /// settlements.
+    const SEED_STREAM: u64 = 4;
+
+    pub fn new() -> Self {
+        Self {
+            cell_size: 128,
+            density: 0.6,
+            max_river_length: 256,
+            river_width: 2,
+            ford_interval: 40,
+            lake_level: 0.28,
+        }
+    }
+
+    /// The map is split into square cells of this size, each holding at most
+    /// one river source, so that rivers are as common on a large map as on
+    /// a small one.
+    pub fn with_cell_size(self, size: Coord) -> Result<Self, InvalidConfig> {
+        if size == 0 {
+            Err(InvalidConfig::ZeroCellSize)?
+        }
+        Ok(Self { cell_size: size, ..self })
+    }
+
+    /// Probability of a cell holding a river source.
+    pub fn with_density(self, density: f64) -> Result<Self, InvalidConfig> {
+        if !(0.0 ..= 1.0).contains(&density) {
+            Err(InvalidConfig::Density(density))?
+        }
+        Ok(Self { density, ..self })
+    }
+
+    /// Steps a river may take from its source. This bounds how far from its
+    /// cell a river reaches, and so how many cells a part of the map has to
+    /// look at.
+    pub fn with_max_river_length(
+        self,
+        length: Coord,
+    ) -> Result<Self, InvalidConfig> {
+        if length == 0 {
+            Err(InvalidConfig::ZeroLength)?
+        }
+        Ok(Self { max_river_length: length, ..self })
+    }
+
+    pub fn with_river_width(self, width: Coord) -> Result<Self, InvalidConfig> {
+        if width == 0 {
+            Err(InvalidConfig::ZeroRiverWidth)?
+        }
+        Self::check_ford(self.ford_interval, width)?;
+        Ok(Self { river_width: width, ..self })
+    }
+
+    /// Rivers leave a dry ford every so many steps, so that they never cut
+    /// the map apart.
+    pub fn with_ford_interval(
+        self,
+        interval: usize,
+    ) -> Result<Self, InvalidConfig> {
+        Self::check_ford(interval, self.river_width)?;
+        Ok(Self { ford_interval: interval, ..self })
+    }
+
+    fn check_ford(interval: usize, width: Coord) -> Result<(), InvalidConfig> {
+        if interval <= Self::ford_length(width) {
+            Err(InvalidConfig::FordInterval { interval, width })?
+        }
+        Ok(())
+    }
+
+    /// Steps left dry so that a ford is passable even once the banks around
+    /// it are painted.
+    fn ford_length(width: Coord) -> usize {
+        usize::from(width) + 2
+    }
+
+    /// Points whose elevation is below this level are flooded into lakes.
+    pub fn with_lake_level(
+        self,
+        level: NoiseValue,
+    ) -> Result<Self, InvalidConfig> {
+        if !(0.0 ..= 1.0).contains(&level) {
+            Err(InvalidConfig::LakeLevel(level))?
+        }
+        Ok(Self { lake_level: level, ..self })
+    }
+
+    /// Lakes and rivers depend only on the seed, on the map rectangle and on
+    /// the elevation noise, so any part of the map can be painted in any
+    /// order.
+    pub fn finish(
+        &self,
+        seed: u64,
+        map_rect: Rect,
+        elevation: ValueNoise,
+    ) -> Sites {
+        Sites {
+            config: self.clone(),
+            seed: derive_seed(seed, Self::SEED_STREAM),
+            map_rect,
+            elevation,
+        }
+    }
+}
+
+/// Where a river stops flowing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiverEnd {
+    /// The river leaves the map.
+    MapEdge,
+    /// The river flows into a lake below the lake level.
+    Lake,
+    /// The river finds neither a lake nor the map edge within its length,
+    /// and seeps into the ground.
+    Sink,
+}
+
+/// A river flowing downhill from its source, carving its way out of any pit
+/// on its way.
+#[derive(Debug, Clone)]
+pub struct River {
+    path: Vec<CoordPair>,
+    end: RiverEnd,
+    tiles: HashSet<CoordPair>,
+}
+
+impl River {
+    /// Points the river flows through, from its source to its end.
+    pub fn path(&self) -> &[CoordPair] {
+        &self.path
+    }
+
+    pub fn end(&self) -> RiverEnd {
+        self.end
+    }
+
+    /// Whether the river floods the given point, fords excluded.
+    pub fn contains(&self, point: CoordPair) -> bool {
+        self.tiles.contains(&point)
+    }
+}
+
+/// Lakes of a map and the river sources of its cells.
+#[derive(Debug, Clone)]
+pub struct Sites {
+    config: Config,
+    seed: u64,
+    map_rect: Rect,
+    elevation: ValueNoise,
+}
+
+impl Sites {
+    pub fn is_lake(&self, point: CoordPair) -> bool {
+        self.elevation.value_at(point) < self.config.lake_level
+    }
+
+    /// The river springing from the given cell, if any.
+    pub fn river_at(&self, cell: CoordPair) -> Option<River> {
+        let (source, mut rng) = self.source_at(cell)?;
+        Some(self.trace_river(source, &mut rng))
+    }
+
+    /// Rivers which may flood some point of the given rectangle.
+    pub fn rivers_in(&self, rect: Rect) -> impl Iterator<Item = River> + '_ {
+        let reach = self
+            .config
+            .max_river_length
+            .saturating_add(self.config.river_width);
+        let top_left =
+            rect.top_left.zip2_with(self.map_rect.top_left, |start, min| {
+                start.saturating_sub(reach).max(min)
+            });
+        let bottom_right = rect
+            .bottom_right()
+            .zip2_with(self.map_rect.bottom_right(), |end, max| {
+                end.saturating_add(reach).min(max)
+            });
+        let search = Rect { top_left, size: bottom_right - top_left };
+        cells_in(search, self.config.cell_size)
+            .filter_map(move |cell| {
+                let (source, mut rng) = self.source_at(cell)?;
+                // A river never gets farther from its source than its length,
+                // so rivers springing too far need no tracing.
+                let gap = source.zip3(rect.top_left, rect.bottom_right()).map(
+                    |(coord, start, end)| {
+                        start.saturating_sub(coord)
+                            + coord.saturating_sub(end.saturating_sub(1))
+                    },
+                );
+                if gap.y.saturating_add(gap.x) > reach {
+                    return None;
+                }
+                Some(self.trace_river(source, &mut rng))
+            })
+            .filter(move |river| {
+                river.tiles.iter().any(|point| rect.contains_point(*point))
+            })
+    }
+
+    /// Distribution painting the lakes and the rivers of the given rectangle.
+    pub fn layer_distr(&self, rect: Rect) -> WaterLayerDistr {
+        WaterLayerDistr {
+            lake_level: self.config.lake_level,
+            elevation: self.elevation,
+            rivers: self.rivers_in(rect).collect(),
+        }
+    }
+
+    fn trace_river(
+        &self,
+        source: CoordPair,
+        rng: &mut PickedReproducibleRng,
+    ) -> River {
+        let mut tracing = Tracing {
+            sites: self,
+            river: River {
+                path: Vec::new(),
+                end: RiverEnd::Sink,
+                tiles: HashSet::new(),
+            },
+        };
+        let mut current = source;
+        loop {
+            if !tracing.push(current) {
+                break;
+            }
+            if self.is_on_edge(current) {
+                tracing.river.end = RiverEnd::MapEdge;
+                break;
+            }
+            let next = match self.flow_downhill(current, rng) {
+                Some(next) => next,
+                None => {
+                    let Some((channel, next)) = self.carve_out_of_pit(current)
+                    else {
+                        break;
+                    };
+                    if !channel.into_iter().all(|point| tracing.push(point)) {
+                        break;
+                    }
+                    next
+                },
+            };
+            if self.is_lake(next) {
+                tracing.river.end = RiverEnd::Lake;
+                break;
+            }
+            current = next;
+        }
+        tracing.river
+    }
+
+    /// The lowest of the strictly lower neighbours, with a little jitter so
+    /// that rivers meander.
+    fn flow_downhill(
+        &self,
+        point: CoordPair,
+        rng: &mut PickedReproducibleRng,
+    ) -> Option<CoordPair> {
+        let elevation = self.elevation.value_at(point);
+        self.neighbours(point)
+            .map(|neighbour| (neighbour, self.elevation.value_at(neighbour)))
+            .filter(|(_, neighbour)| *neighbour < elevation)
+            .map(|(neighbour, value)| {
+                (neighbour, value + rng.random_range(0.0 .. 0.01))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(neighbour, _)| neighbour)
+    }
+
+    /// Floods the pit from its bottom up, lowest points first, until the
+    /// water spills over its rim into a point lower than the bottom, a lake
+    /// or the map edge. Returns the channel carved through the rim, and
+    /// where the river goes on from.
+    fn carve_out_of_pit(
+        &self,
+        bottom: CoordPair,
+    ) -> Option<(Vec<CoordPair>, CoordPair)> {
+        let bottom_elevation = self.elevation.value_at(bottom);
+        let mut came_from = HashMap::from([(bottom, bottom)]);
+        let mut frontier =
+            BinaryHeap::from([(Reverse(Level(bottom_elevation)), bottom)]);
+        for _ in 0 .. Config::PIT_FLOOD_LIMIT {
+            let (Reverse(Level(elevation)), point) = frontier.pop()?;
+            if elevation < bottom_elevation
+                || self.is_lake(point)
+                || self.is_on_edge(point)
+            {
+                let mut hops = vec![point];
+                let mut current = point;
+                while current != bottom {
+                    current = came_from[&current];
+                    hops.push(current);
+                }
+                hops.reverse();
+                let mut channel: Vec<_> = hops
+                    .windows(2)
+                    .flat_map(|hop| straight_line(hop[0], hop[1]))
+                    .collect();
+                channel.pop();
+                return Some((channel, point));
+            }
+            for neighbour in self.flood_neighbours(point) {
+                if let Entry::Vacant(entry) = came_from.entry(neighbour) {
+                    entry.insert(point);
+                    let elevation = self.elevation.value_at(neighbour);
+                    frontier.push((Reverse(Level(elevation)), neighbour));
+                }
+            }
+        }
+        None
+    }
+
+    /// Points a few steps away along every direction, or as far as the map
+    /// goes, so that a flood covers wide pits quickly.
+    fn flood_neighbours(
+        &self,
+        point: CoordPair,
+    ) -> impl Iterator<Item = CoordPair> + '_ {
+        Direction::ALL.into_iter().filter_map(move |direction| {
+            let mut neighbour = point;
+            for _ in 0 .. Config::PIT_FLOOD_STEP {
+                match self
+                    .map_rect
+                    .checked_move_point_unit(neighbour, direction)
+                {
+                    Ok(next) => neighbour = next,
+                    Err(_) => break,
+                }
+            }
+            Some(neighbour).filter(|neighbour| *neighbour != point)
+        })
+    }
+
+    fn source_at(
+        &self,
+        cell: CoordPair,
+    ) -> Option<(CoordPair, PickedReproducibleRng)> {
+        let position = cell.map(u32::from);
+        if positional_unit(self.seed, position) >= self.config.density {
+            return None;
+        }
+        let mut rng = create_positional_rng(self.seed, position);
+        let source = self.pick_source(cell, &mut rng)?;
+        Some((source, rng))
+    }
+
+    /// The highest of a few random points of the cell.
+    fn pick_source(
+        &self,
+        cell: CoordPair,
+        rng: &mut PickedReproducibleRng,
+    ) -> Option<CoordPair> {
+        let cell_size = self.config.cell_size;
+        let top_left = cell
+            .checked_mul_scalar(&cell_size)?
+            .zip2_with(self.map_rect.top_left, |start, min| start.max(min));
+        let bottom_right = cell
+            .map(|coord| coord.saturating_add(1).saturating_mul(cell_size))
+            .zip2_with(self.map_rect.bottom_right(), |end, max| end.min(max));
+        if top_left.zip2(bottom_right).any(|(start, end)| start >= end) {
+            return None;
+        }
+        let source = (0 .. Config::SOURCE_TRIES)
+            .map(|_| CoordPair {
+                y: rng.random_range(top_left.y .. bottom_right.y),
+                x: rng.random_range(top_left.x .. bottom_right.x),
+            })
+            .max_by(|a, b| {
+                self.elevation
+                    .value_at(*a)
+                    .total_cmp(&self.elevation.value_at(*b))
+            })?;
+        if self.is_lake(source) { None } else { Some(source) }
+    }
+
+    fn neighbours(
+        &self,
+        point: CoordPair,
+    ) -> impl Iterator<Item = CoordPair> + '_ {
+        Direction::ALL.into_iter().filter_map(move |direction| {
+            self.map_rect.checked_move_point_unit(point, direction).ok()
+        })
+    }
+
+    fn is_on_edge(&self, point: CoordPair) -> bool {
+        self.neighbours(point).count() < Direction::ALL.len()
+    }
+}
+
+/// A river being traced, growing one point at a time.
+#[derive(Debug)]
+struct Tracing<'a> {
+    sites: &'a Sites,
+    river: River,
+}
+
+impl Tracing<'_> {
+    /// Adds a point to the river, unless the river is already as long as it
+    /// may be.
+    fn push(&mut self, point: CoordPair) -> bool {
+        let config = &self.sites.config;
+        if self.river.path.len() >= usize::from(config.max_river_length) {
+            return false;
+        }
+        let ford_length = Config::ford_length(config.river_width);
+        if self.river.path.len() % config.ford_interval >= ford_length {
+            paint(
+                &mut self.river.tiles,
+                self.sites.map_rect,
+                point,
+                config.river_width,
+            );
+        }
+        self.river.path.push(point);
+        true
+    }
+}
+
+/// Elevation ordered totally, so that points can be queued by it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Level(NoiseValue);
+
+impl Eq for Level {}
+
+impl PartialOrd for Level {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Level {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Floods low areas into lakes and covers the rivers flowing through some
+/// part of the map.
+#[derive(Debug, Clone)]
+pub struct WaterLayerDistr {
+    lake_level: NoiseValue,
+    elevation: ValueNoise,
+    rivers: Vec<River>,
+}
+
+impl WaterLayerDistr {
+    pub fn rivers(&self) -> &[River] {
+        &self.rivers
+    }
+
+    pub fn is_lake(&self, point: CoordPair) -> bool {
+        self.elevation.value_at(point) < self.lake_level
+    }
+
+    pub fn is_water(&self, point: CoordPair) -> bool {
+        self.is_lake(point)
+            || self.rivers.iter().any(|river| river.contains(point))
+    }
+}
+
+impl LayerDistribution for WaterLayerDistr {
+    type Data = PlaceableBlock;
+    type Error = WaterDistrError;
+
+    fn sample<R>(
+        &self,
+        map: &mut Map,
+        point: CoordPair,
+        _rng: R,
+    ) -> Result<Self::Data, Self::Error>
+    where
+        R: Rng,
+    {
+        if self.is_water(point) {
+            Ok(PlaceableBlock::Water)
+        } else {
+            map.get_covered_block(point)
+        }
+    }
+}
+
+/// Points from right after the start up to the end, which must share a row
+/// or a column.
+fn straight_line(start: CoordPair, end: CoordPair) -> Vec<CoordPair> {
+    let mut points = Vec::new();
+    let mut current = start;
+    while current != end {
+        current = current.zip2_with(end, |coord, target| {
+            if coord < target {
+                coord + 1
+            } else if coord > target {
+                coord - 1
+            } else {
+                coord
+            }
+        });
+        points.push(current);
+    }
+    points
+}
+
+/// Floods a square of the given side around the point, clipped to the map.
+fn paint(
+    tiles: &mut HashSet<CoordPair>,
+    map_rect: Rect,
+    center: CoordPair,
+    side: Coord,
+) {
+    let top_left = center.map(|coord| coord.saturating_sub(side / 2));
+    let brush = Rect { top_left, size: CoordPair::from_axes(|_| side) };
+    let bottom_right = brush.checked_bottom_right().unwrap_or(top_left);
+    for y in top_left.y .. bottom_right.y {
+        for x in top_left.x .. bottom_right.x {
+            let point = CoordPair { y, x };
+            if map_rect.contains_point(point) {
+                tiles.insert(point);
+            }
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::HashSet;
+
+use thedes_domain::geometry::{CoordPair, Rect};
+use thedes_geometry::orientation::Direction;
+
+use crate::map::layer::noise::{self, ValueNoise};
+
+use super::{Config, RiverEnd, Sites};
+
+const SEEDS: [u64; 4] = [1, 22, 333, 4444];
+
+fn map_rect() -> Rect {
+    Rect {
+        top_left: CoordPair { y: 0, x: 0 },
+        size: CoordPair { y: 384, x: 384 },
+    }
+}
+
+fn elevation(seed: u64) -> ValueNoise {
+    noise::Config::new().finish(seed)
+}
+
+fn water(seed: u64) -> Sites {
+    Config::new().finish(seed, map_rect(), elevation(seed))
+}
+
+fn neighbours(point: CoordPair) -> impl Iterator<Item = CoordPair> {
+    Direction::ALL.into_iter().filter_map(move |direction| {
+        map_rect().checked_move_point_unit(point, direction).ok()
+    })
+}
+
+fn is_local_minimum(elevation: &ValueNoise, point: CoordPair) -> bool {
+    let value = elevation.value_at(point);
+    neighbours(point).all(|neighbour| elevation.value_at(neighbour) >= value)
+}
+
+fn is_on_edge(point: CoordPair) -> bool {
+    neighbours(point).count() < Direction::ALL.len()
+}
+
+#[test]
+fn lakes_land_on_local_minima() {
+    let mut inner_lakes = 0;
+    for seed in SEEDS {
+        let water = water(seed);
+        let elevation = elevation(seed);
+        let mut visited = HashSet::new();
+        for y in 0 .. map_rect().size.y {
+            for x in 0 .. map_rect().size.x {
+                let start = CoordPair { y, x };
+                if !water.is_lake(start) || !visited.insert(start) {
+                    continue;
+                }
+                let mut lake = vec![start];
+                let mut frontier = vec![start];
+                while let Some(point) = frontier.pop() {
+                    for neighbour in neighbours(point) {
+                        if water.is_lake(neighbour) && visited.insert(neighbour)
+                        {
+                            lake.push(neighbour);
+                            frontier.push(neighbour);
+                        }
+                    }
+                }
+                if lake.iter().any(|point| is_on_edge(*point)) {
+                    continue;
+                }
+                let lowest = lake
+                    .iter()
+                    .copied()
+                    .min_by(|a, b| {
+                        elevation
+                            .value_at(*a)
+                            .total_cmp(&elevation.value_at(*b))
+                    })
+                    .unwrap();
+                assert!(is_local_minimum(&elevation, lowest), "{lowest:?}");
+                inner_lakes += 1;
+            }
+        }
+    }
+    assert!(inner_lakes > 0);
+}
+
+#[test]
+fn rivers_carve_out_of_pits_until_a_lake_or_the_map_edge() {
+    let mut ends = Vec::new();
+    let mut carved = 0;
+    for seed in SEEDS {
+        let water = water(seed);
+        let elevation = elevation(seed);
+        for river in water.rivers_in(map_rect()) {
+            assert!(river.path().len() <= 256);
+            for pair in river.path().windows(2) {
+                assert!(neighbours(pair[0]).any(|point| point == pair[1]));
+            }
+            let (last, flowing) = river.path().split_last().unwrap();
+            carved += flowing
+                .iter()
+                .filter(|point| is_local_minimum(&elevation, **point))
+                .count();
+            match river.end() {
+                RiverEnd::MapEdge => assert!(is_on_edge(*last)),
+                RiverEnd::Lake => {
+                    assert!(neighbours(*last).any(|point| water.is_lake(point)))
+                },
+                RiverEnd::Sink => {},
+            }
+            if !ends.contains(&river.end()) {
+                ends.push(river.end());
+            }
+        }
+    }
+    assert!(carved > 0);
+    assert!(ends.contains(&RiverEnd::MapEdge));
+    assert!(ends.contains(&RiverEnd::Lake));
+}
+
+#[test]
+fn rivers_spring_all_over_large_maps() {
+    let map_rect = Rect {
+        top_left: CoordPair { y: 0, x: 0 },
+        size: CoordPair { y: u16::MAX, x: u16::MAX },
+    };
+    let water = Config::new().finish(SEEDS[0], map_rect, elevation(SEEDS[0]));
+    for top_left in [
+        CoordPair { y: 1024, x: 1024 },
+        CoordPair { y: 1024, x: 60000 },
+        CoordPair { y: 32000, x: 32000 },
+        CoordPair { y: 60000, x: 1024 },
+    ] {
+        let rect = Rect { top_left, size: CoordPair { y: 512, x: 512 } };
+        assert!(water.rivers_in(rect).count() > 0, "{rect:?}");
+    }
+}
+
+#[test]
+fn water_depends_only_on_seed() {
+    for seed in SEEDS {
+        let paths: Vec<_> = water(seed)
+            .rivers_in(map_rect())
+            .map(|river| river.path().to_vec())
+            .collect();
+        let again: Vec<_> = water(seed)
+            .rivers_in(map_rect())
+            .map(|river| river.path().to_vec())
+            .collect();
+        assert_eq!(paths, again);
+    }
+}
+
+#[test]
+fn any_part_of_the_map_sees_its_rivers() {
+    let part = Rect {
+        top_left: CoordPair { y: 160, x: 96 },
+        size: CoordPair { y: 32, x: 32 },
+    };
+    for seed in SEEDS {
+        let water = water(seed);
+        let whole = water.layer_distr(map_rect());
+        let distr = water.layer_distr(part);
+        for y in part.top_left.y .. part.bottom_right().y {
+            for x in part.top_left.x .. part.bottom_right().x {
+                let point = CoordPair { y, x };
+                assert_eq!(distr.is_water(point), whole.is_water(point));
+            }
+        }
+    }
+}
+
+#[test]
+fn invalid_config_is_rejected() {
+    assert!(Config::new().with_river_width(0).is_err());
+    assert!(Config::new().with_lake_level(1.5).is_err());
+    assert!(
+        Config::new()
+            .with_river_width(4)
+            .unwrap()
+            .with_ford_interval(6)
+            .is_err()
+    );
+    assert!(Config::new().with_cell_size(0).is_err());
+    assert!(Config::new().with_density(1.5).is_err());
+    assert!(Config::new().with_max_river_length(0).is_err());
+}
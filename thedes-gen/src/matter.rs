@@ -1,55 +1,126 @@
 use std::array;
 
-use rand::Rng;
-use rand_distr::Distribution;
 use thedes_domain::matter::Biome;
+use thiserror::Error;
 
-use super::random::ProabilityWeight;
+use super::map::layer::noise::NoiseValue;
 
-#[derive(Debug, Clone)]
-pub struct BiomeDistr {
-    cumulative_weights: [ProabilityWeight; Biome::COUNT],
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Error)]
+#[error("Band thresholds {low} and {high} must be increasing, within 0 and 1")]
+pub struct InvalidThresholds {
+    pub low: NoiseValue,
+    pub high: NoiseValue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Elevation {
+    Low,
+    Mid,
+    High,
+}
+
+impl Elevation {
+    pub const COUNT: usize = 3;
+
+    pub const ALL: [Self; Self::COUNT] = [Self::Low, Self::Mid, Self::High];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Moisture {
+    Dry,
+    Moderate,
+    Wet,
 }
 
-impl Default for BiomeDistr {
+impl Moisture {
+    pub const COUNT: usize = 3;
+
+    pub const ALL: [Self; Self::COUNT] = [Self::Dry, Self::Moderate, Self::Wet];
+}
+
+/// Picks biomes by how high and how wet a place is. Noise values are split
+/// into bands by a low and a high threshold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BiomeTable {
+    biomes: [[Biome; Moisture::COUNT]; Elevation::COUNT],
+    elevation_thresholds: [NoiseValue; 2],
+    moisture_thresholds: [NoiseValue; 2],
+}
+
+impl Default for BiomeTable {
     fn default() -> Self {
-        Self::new(|ground| match ground {
-            Biome::Plains => 11,
-            Biome::Desert => 5,
-            Biome::Wasteland => 4,
+        Self::new(|elevation, moisture| match (elevation, moisture) {
+            (Elevation::High, Moisture::Wet) => Biome::Plains,
+            (Elevation::High, _) => Biome::Wasteland,
+            (_, Moisture::Dry) => Biome::Desert,
+            _ => Biome::Plains,
         })
     }
 }
 
-impl BiomeDistr {
-    pub fn new<F>(mut density_function: F) -> Self
+impl BiomeTable {
+    pub fn new<F>(mut biome_function: F) -> Self
     where
-        F: FnMut(Biome) -> ProabilityWeight,
+        F: FnMut(Elevation, Moisture) -> Biome,
     {
-        let mut accumuled_weight = 0;
-        let cumulative_weights = array::from_fn(|i| {
-            accumuled_weight += density_function(Biome::ALL[i]);
-            accumuled_weight
+        let biomes = array::from_fn(|i| {
+            array::from_fn(|j| {
+                biome_function(Elevation::ALL[i], Moisture::ALL[j])
+            })
         });
-        Self { cumulative_weights }
+        Self {
+            biomes,
+            elevation_thresholds: [0.4, 0.6],
+            moisture_thresholds: [0.4, 0.6],
+        }
     }
-}
 
-impl Distribution<Biome> for BiomeDistr {
-    fn sample<R>(&self, rng: &mut R) -> Biome
-    where
-        R: Rng + ?Sized,
-    {
-        let last_cumulative_weight =
-            self.cumulative_weights[self.cumulative_weights.len() - 1];
-        let sampled_weight = rng.random_range(0 .. last_cumulative_weight);
-        for (i, cumulative_weight) in
-            self.cumulative_weights.into_iter().enumerate()
-        {
-            if sampled_weight < cumulative_weight {
-                return Biome::ALL[i];
-            }
+    pub fn with_elevation_thresholds(
+        self,
+        low: NoiseValue,
+        high: NoiseValue,
+    ) -> Result<Self, InvalidThresholds> {
+        Ok(Self { elevation_thresholds: Self::thresholds(low, high)?, ..self })
+    }
+
+    pub fn with_moisture_thresholds(
+        self,
+        low: NoiseValue,
+        high: NoiseValue,
+    ) -> Result<Self, InvalidThresholds> {
+        Ok(Self { moisture_thresholds: Self::thresholds(low, high)?, ..self })
+    }
+
+    fn thresholds(
+        low: NoiseValue,
+        high: NoiseValue,
+    ) -> Result<[NoiseValue; 2], InvalidThresholds> {
+        if !(0.0 <= low && low <= high && high <= 1.0) {
+            Err(InvalidThresholds { low, high })?
         }
-        panic!("sampled weight {sampled_weight} is out of requested bounds")
+        Ok([low, high])
+    }
+
+    pub fn elevation(&self, value: NoiseValue) -> Elevation {
+        Elevation::ALL[Self::band(self.elevation_thresholds, value)]
+    }
+
+    pub fn moisture(&self, value: NoiseValue) -> Moisture {
+        Moisture::ALL[Self::band(self.moisture_thresholds, value)]
+    }
+
+    fn band(thresholds: [NoiseValue; 2], value: NoiseValue) -> usize {
+        thresholds.iter().filter(|threshold| value >= **threshold).count()
+    }
+
+    pub fn get(&self, elevation: Elevation, moisture: Moisture) -> Biome {
+        self.biomes[elevation as usize][moisture as usize]
+    }
+
+    pub fn biome(&self, elevation: NoiseValue, moisture: NoiseValue) -> Biome {
+        self.get(self.elevation(elevation), self.moisture(moisture))
     }
 }
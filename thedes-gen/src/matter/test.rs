@@ -0,0 +1,47 @@
+use thedes_domain::matter::Biome;
+
+use super::{BiomeTable, Elevation, Moisture};
+
+#[test]
+fn values_fall_into_bands() {
+    let table = BiomeTable::default();
+    assert_eq!(table.elevation(0.0), Elevation::Low);
+    assert_eq!(table.elevation(0.39), Elevation::Low);
+    assert_eq!(table.elevation(0.4), Elevation::Mid);
+    assert_eq!(table.elevation(0.59), Elevation::Mid);
+    assert_eq!(table.elevation(0.6), Elevation::High);
+    assert_eq!(table.elevation(1.0), Elevation::High);
+    assert_eq!(table.moisture(0.1), Moisture::Dry);
+    assert_eq!(table.moisture(0.5), Moisture::Moderate);
+    assert_eq!(table.moisture(0.9), Moisture::Wet);
+
+    let table = table.with_elevation_thresholds(0.2, 0.3).unwrap();
+    assert_eq!(table.elevation(0.25), Elevation::Mid);
+    assert_eq!(table.elevation(0.35), Elevation::High);
+    assert!(BiomeTable::default().with_moisture_thresholds(0.7, 0.2).is_err());
+    assert!(BiomeTable::default().with_moisture_thresholds(0.2, 1.5).is_err());
+}
+
+#[test]
+fn biomes_change_across_bands() {
+    let table = BiomeTable::default();
+    assert_eq!(table.biome(0.1, 0.1), Biome::Desert);
+    assert_eq!(table.biome(0.5, 0.1), Biome::Desert);
+    assert_eq!(table.biome(0.1, 0.5), Biome::Plains);
+    assert_eq!(table.biome(0.5, 0.9), Biome::Plains);
+    assert_eq!(table.biome(0.9, 0.1), Biome::Wasteland);
+    assert_eq!(table.biome(0.9, 0.5), Biome::Wasteland);
+    assert_eq!(table.biome(0.9, 0.9), Biome::Plains);
+
+    let table =
+        BiomeTable::new(|elevation, moisture| match (elevation, moisture) {
+            (Elevation::Low, _) => Biome::Plains,
+            (_, Moisture::Wet) => Biome::Wasteland,
+            _ => Biome::Desert,
+        });
+    for moisture in Moisture::ALL {
+        assert_eq!(table.get(Elevation::Low, moisture), Biome::Plains);
+    }
+    assert_eq!(table.get(Elevation::Mid, Moisture::Wet), Biome::Wasteland);
+    assert_eq!(table.get(Elevation::High, Moisture::Dry), Biome::Desert);
+}
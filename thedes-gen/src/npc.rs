@@ -0,0 +1,149 @@
+use rand::Rng;
+use thedes_domain::{
+    game::{Game, SpawnNpcError},
+    geometry::{Coord, CoordPair},
+    npc::Npc,
+};
+use thedes_geometry::orientation::Direction;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Failed to spawn NPC")]
+    Spawn(
+        #[from]
+        #[source]
+        SpawnNpcError,
+    ),
+}
+
+/// Who an NPC is: its name and the dialogue it starts when talked to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    name: String,
+    dialogue: String,
+}
+
+impl Template {
+    pub fn new(name: impl Into<String>, dialogue: impl Into<String>) -> Self {
+        Self { name: name.into(), dialogue: dialogue.into() }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn dialogue(&self) -> &str {
+        &self.dialogue
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    templates: Vec<Template>,
+    count: usize,
+    spawn_radius: Coord,
+    wander_radius: Coord,
+    attempts_per_npc: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self {
+            templates: vec![
+                Template::new("Hermit", "hermit"),
+                Template::new("Merchant", "merchant"),
+                Template::new("Farmer", "villager"),
+            ],
+            count: 3,
+            spawn_radius: 12,
+            wander_radius: Npc::DEFAULT_WANDER_RADIUS,
+            attempts_per_npc: 20,
+        }
+    }
+
+    /// Same as [`Config::new`], but with the villagers living in
+    /// settlements.
+    pub fn new_villagers() -> Self {
+        Self {
+            templates: vec![
+                Template::new("Farmer", "villager"),
+                Template::new("Merchant", "merchant"),
+            ],
+            spawn_radius: 10,
+            ..Self::new()
+        }
+    }
+
+    /// Templates are picked at random for every spawned NPC.
+    pub fn with_templates(self, templates: Vec<Template>) -> Self {
+        Self { templates, ..self }
+    }
+
+    pub fn with_count(self, count: usize) -> Self {
+        Self { count, ..self }
+    }
+
+    pub fn with_spawn_radius(self, radius: Coord) -> Self {
+        Self { spawn_radius: radius, ..self }
+    }
+
+    pub fn with_wander_radius(self, radius: Coord) -> Self {
+        Self { wander_radius: radius, ..self }
+    }
+
+    pub fn templates(&self) -> &[Template] {
+        &self.templates
+    }
+
+    /// Spawns NPCs on free tiles around the center, giving up on an NPC after
+    /// a few blocked tiles. Returns how many were spawned.
+    pub fn spawn_around<R>(
+        &self,
+        game: &mut Game,
+        center: CoordPair,
+        rng: &mut R,
+    ) -> Result<usize, Error>
+    where
+        R: Rng + ?Sized,
+    {
+        if self.templates.is_empty() {
+            return Ok(0);
+        }
+        let rect = game.map().rect();
+        let mut spawned = 0;
+        for _ in 0 .. self.count {
+            let template =
+                &self.templates[rng.random_range(.. self.templates.len())];
+            for _ in 0 .. self.attempts_per_npc {
+                let body = CoordPair::from_axes(|axis| {
+                    let min = center[axis].saturating_sub(self.spawn_radius);
+                    let max = center[axis].saturating_add(self.spawn_radius);
+                    rng.random_range(min ..= max)
+                });
+                if !rect.contains_point(body) {
+                    continue;
+                }
+                let facing: Direction = rng.random();
+                let npc = Npc::new(
+                    template.name(),
+                    template.dialogue(),
+                    body,
+                    facing,
+                )
+                .with_wander_radius(self.wander_radius);
+                if game.try_spawn_npc(npc)?.is_some() {
+                    spawned += 1;
+                    break;
+                }
+            }
+        }
+        Ok(spawned)
+    }
+}
@@ -2,6 +2,7 @@ use std::convert::Infallible;
 
 use rand::{Rng, SeedableRng};
 use rand_distr::Distribution;
+use thedes_geometry::CoordPair;
 
 pub type PickedReproducibleRng = rand_chacha::ChaCha8Rng;
 
@@ -19,6 +20,44 @@ pub fn create_reproducible_rng(seed: Seed) -> PickedReproducibleRng {
     PickedReproducibleRng::from_seed(full_seed)
 }
 
+/// Creates a generator reproducible from the seed and the position, no
+/// matter in which order positions are visited.
+pub fn create_positional_rng(
+    seed: u64,
+    position: CoordPair<u32>,
+) -> PickedReproducibleRng {
+    PickedReproducibleRng::seed_from_u64(positional_hash(seed, position))
+}
+
+/// Cheaper than [`create_positional_rng`] when a single random number per
+/// position is enough.
+pub fn positional_hash(seed: u64, position: CoordPair<u32>) -> u64 {
+    let mut state = seed;
+    for coord in [position.y, position.x] {
+        state = split_mix(state ^ u64::from(coord));
+    }
+    state
+}
+
+/// Same as [`positional_hash`], but uniformly distributed in `0.0 .. 1.0`.
+pub fn positional_unit(seed: u64, position: CoordPair<u32>) -> f64 {
+    let bits = positional_hash(seed, position) >> 11;
+    bits as f64 / (1u64 << 53) as f64
+}
+
+/// Derives a seed for an independent stream of randomness, so that a single
+/// seed can drive several generators without them being correlated.
+pub fn derive_seed(seed: u64, stream: u64) -> u64 {
+    split_mix(seed ^ split_mix(stream))
+}
+
+fn split_mix(mut state: u64) -> u64 {
+    state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    state = (state ^ (state >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    state = (state ^ (state >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    state ^ (state >> 31)
+}
+
 pub trait MutableDistribution<T> {
     type Error: std::error::Error;
 
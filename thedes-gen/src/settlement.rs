@@ -0,0 +1,675 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    convert::Infallible,
+};
+
+use rand::Rng;
+use thedes_domain::{
+    block::PlaceableBlock,
+    geometry::{Coord, CoordPair, Rect},
+    matter::{Biome, Ground},
+};
+use thedes_geometry::{collections::graph::CoordGraph, orientation::Direction};
+use thiserror::Error;
+
+use crate::{
+    map::layer::matter::BiomeLayerDistr,
+    random::{
+        PickedReproducibleRng,
+        create_positional_rng,
+        derive_seed,
+        positional_unit,
+    },
+    structure::{
+        cells_in,
+        center,
+        connect_path,
+        distance,
+        is_interior,
+        overlaps,
+        place_in_cell,
+        segments,
+    },
+};
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Error)]
+pub enum InvalidConfig {
+    #[error("Buildings must be at least {min} wide, given {given}")]
+    BuildingTooSmall { given: Coord, min: Coord },
+    #[error("Minimum building size {min} cannot be greater than maximum {max}")]
+    BuildingSizeBoundOrder { min: Coord, max: Coord },
+    #[error(
+        "Minimum building count {min} cannot be greater than maximum {max}"
+    )]
+    BuildingCountBoundOrder { min: usize, max: usize },
+    #[error(
+        "Settlements of size {given} cannot fit a building of size {building} \
+         and its margin"
+    )]
+    SizeTooSmall { given: Coord, building: Coord },
+    #[error(
+        "Minimum settlement size {min} cannot be greater than maximum {max}"
+    )]
+    SizeBoundOrder { min: Coord, max: Coord },
+    #[error(
+        "Settlement cell size {cell_size} cannot fit settlements {size} wide"
+    )]
+    CellTooSmall { cell_size: Coord, size: Coord },
+    #[error("Settlement density {density} in {biome} must be within 0 and 1")]
+    Density { biome: Biome, density: f64 },
+}
+
+/// Hook for later generation stages, such as populating settlements with
+/// NPCs or loot.
+pub trait Collector {
+    type Error: std::error::Error;
+
+    fn add_settlement(
+        &mut self,
+        settlement: &Settlement,
+    ) -> Result<(), Self::Error>;
+}
+
+impl<C> Collector for &mut C
+where
+    C: Collector + ?Sized,
+{
+    type Error = C::Error;
+
+    fn add_settlement(
+        &mut self,
+        settlement: &Settlement,
+    ) -> Result<(), Self::Error> {
+        (**self).add_settlement(settlement)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct NopCollector;
+
+impl Collector for NopCollector {
+    type Error = Infallible;
+
+    fn add_settlement(
+        &mut self,
+        _settlement: &Settlement,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Centers of the settlements generated so far, to be populated once their
+/// chunks are loaded.
+#[derive(Debug, Clone, Default)]
+pub struct Centers {
+    centers: Vec<CoordPair>,
+}
+
+impl Centers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl IntoIterator for Centers {
+    type Item = CoordPair;
+    type IntoIter = std::vec::IntoIter<CoordPair>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.centers.into_iter()
+    }
+}
+
+impl Collector for Centers {
+    type Error = Infallible;
+
+    fn add_settlement(
+        &mut self,
+        settlement: &Settlement,
+    ) -> Result<(), Self::Error> {
+        self.centers.push(settlement.center());
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    min_buildings: usize,
+    max_buildings: usize,
+    min_building_size: Coord,
+    max_building_size: Coord,
+    min_size: Coord,
+    max_size: Coord,
+    cell_size: Coord,
+    densities: [f64; Biome::COUNT],
+    road_ground: Ground,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Config {
+    /// A building needs its walls plus at least one tile of floor.
+    const MIN_BUILDING_SIZE: Coord = 3;
+
+    /// Free tiles between buildings and the border of the settlement, so
+    /// that entrances and roads stay inside of it.
+    const MARGIN: Coord = 2;
+
+    /// Free tiles between buildings, so that roads can pass between them.
+    const GAP: Coord = 2;
+
+    const TRIES_PER_BUILDING: usize = 20;
+
+    /// Extra cost of a turn when routing roads, in steps.
+    const TURN_COST: u32 = 6;
+
+    /// Distinct from the streams used by climate noise and structures.
+    const SEED_STREAM: u64 = 3;
+
+    pub fn new() -> Self {
+        Self {
+            min_buildings: 4,
+            max_buildings: 8,
+            min_building_size: 5,
+            max_building_size: 8,
+            min_size: 36,
+            max_size: 52,
+            cell_size: 224,
+            densities: Biome::ALL.map(|biome| match biome {
+                Biome::Plains => 0.4,
+                Biome::Desert => 0.05,
+                Biome::Wasteland => 0.02,
+            }),
+            road_ground: Ground::Stone,
+        }
+    }
+
+    /// How many buildings are tried to be placed; crowded settlements may
+    /// end up with fewer.
+    pub fn with_building_count(
+        self,
+        min: usize,
+        max: usize,
+    ) -> Result<Self, InvalidConfig> {
+        Self { min_buildings: min, max_buildings: max, ..self }.validate()
+    }
+
+    /// Sizes of buildings, including their walls.
+    pub fn with_building_size(
+        self,
+        min: Coord,
+        max: Coord,
+    ) -> Result<Self, InvalidConfig> {
+        Self { min_building_size: min, max_building_size: max, ..self }
+            .validate()
+    }
+
+    /// Sizes of whole settlements, along both axes.
+    pub fn with_size(
+        self,
+        min: Coord,
+        max: Coord,
+    ) -> Result<Self, InvalidConfig> {
+        Self { min_size: min, max_size: max, ..self }.validate()
+    }
+
+    /// The overworld is split into square cells of this size, each holding
+    /// at most one settlement.
+    pub fn with_cell_size(self, size: Coord) -> Result<Self, InvalidConfig> {
+        Self { cell_size: size, ..self }.validate()
+    }
+
+    /// Probability of a cell holding a settlement, given the biome at the
+    /// center of the cell.
+    pub fn with_density(
+        mut self,
+        biome: Biome,
+        density: f64,
+    ) -> Result<Self, InvalidConfig> {
+        if !(0.0 ..= 1.0).contains(&density) {
+            Err(InvalidConfig::Density { biome, density })?
+        }
+        self.densities[biome as usize] = density;
+        Ok(self)
+    }
+
+    pub fn with_road_ground(self, ground: Ground) -> Self {
+        Self { road_ground: ground, ..self }
+    }
+
+    fn validate(self) -> Result<Self, InvalidConfig> {
+        if self.min_building_size < Self::MIN_BUILDING_SIZE {
+            Err(InvalidConfig::BuildingTooSmall {
+                given: self.min_building_size,
+                min: Self::MIN_BUILDING_SIZE,
+            })?
+        }
+        if self.min_building_size > self.max_building_size {
+            Err(InvalidConfig::BuildingSizeBoundOrder {
+                min: self.min_building_size,
+                max: self.max_building_size,
+            })?
+        }
+        if self.min_buildings > self.max_buildings {
+            Err(InvalidConfig::BuildingCountBoundOrder {
+                min: self.min_buildings,
+                max: self.max_buildings,
+            })?
+        }
+        if self.min_size < self.max_building_size + Self::MARGIN * 2 {
+            Err(InvalidConfig::SizeTooSmall {
+                given: self.min_size,
+                building: self.max_building_size,
+            })?
+        }
+        if self.min_size > self.max_size {
+            Err(InvalidConfig::SizeBoundOrder {
+                min: self.min_size,
+                max: self.max_size,
+            })?
+        }
+        if self.cell_size < self.max_size {
+            Err(InvalidConfig::CellTooSmall {
+                cell_size: self.cell_size,
+                size: self.max_size,
+            })?
+        }
+        Ok(self)
+    }
+
+    /// Builds a settlement in the given rectangle, which must fit a building
+    /// of maximum size and its margin.
+    pub fn build<R>(&self, rect: Rect, rng: &mut R) -> Settlement
+    where
+        R: Rng + ?Sized,
+    {
+        let buildings = self.place_buildings(rect, rng);
+        let roads = Self::pave_roads(rect, &buildings);
+        let mut settlement = Settlement {
+            rect,
+            buildings,
+            roads,
+            road_ground: self.road_ground,
+            road_tiles: HashSet::new(),
+            blocks: HashMap::new(),
+        };
+        settlement.paint();
+        settlement
+    }
+
+    fn place_buildings<R>(&self, rect: Rect, rng: &mut R) -> Vec<Building>
+    where
+        R: Rng + ?Sized,
+    {
+        let count = rng.random_range(self.min_buildings ..= self.max_buildings);
+        let mut buildings = Vec::<Building>::with_capacity(count);
+        for _ in 0 .. count {
+            for _ in 0 .. Self::TRIES_PER_BUILDING {
+                let size = CoordPair::from_axes(|_| {
+                    rng.random_range(
+                        self.min_building_size ..= self.max_building_size,
+                    )
+                });
+                let top_left = rect.top_left.zip2_with(
+                    rect.size.zip2(size),
+                    |start, (available, size)| {
+                        start
+                            + Self::MARGIN
+                            + rng.random_range(
+                                0 ..= available - Self::MARGIN * 2 - size,
+                            )
+                    },
+                );
+                let building_rect = Rect { top_left, size };
+                let surroundings = Rect {
+                    top_left: top_left.map(|coord| coord - Self::GAP),
+                    size: size.map(|coord| coord + Self::GAP * 2),
+                };
+                if buildings
+                    .iter()
+                    .any(|other| overlaps(surroundings, other.rect))
+                {
+                    continue;
+                }
+                buildings.push(Building::new(building_rect, rng.random(), rng));
+                break;
+            }
+        }
+        buildings
+    }
+
+    /// Connects building entrances by the shortest roads that still reach
+    /// every building, that is, a minimum spanning tree.
+    fn pave_roads(rect: Rect, buildings: &[Building]) -> CoordGraph<Coord> {
+        let mut roads = CoordGraph::new();
+        let Some(first) = buildings.first() else {
+            return roads;
+        };
+        roads.insert_node(first.entrance);
+
+        let mut in_tree = vec![false; buildings.len()];
+        in_tree[0] = true;
+        let mut closest: Vec<_> = buildings
+            .iter()
+            .map(|building| (distance(first.entrance, building.entrance), 0))
+            .collect();
+        for _ in 1 .. buildings.len() {
+            let (next, &(_, parent)) = closest
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !in_tree[*i])
+                .min_by_key(|(_, (distance, _))| *distance)
+                .expect("some building is out of the tree");
+            in_tree[next] = true;
+
+            let start = buildings[parent].entrance;
+            let end = buildings[next].entrance;
+            if let Some(path) = Self::route(rect, buildings, start, end) {
+                connect_path(&mut roads, &path);
+            }
+
+            for (i, building) in buildings.iter().enumerate() {
+                let distance = distance(end, building.entrance);
+                if !in_tree[i] && distance < closest[i].0 {
+                    closest[i] = (distance, next);
+                }
+            }
+        }
+        roads
+    }
+
+    /// Finds the cheapest road between both points avoiding buildings, where
+    /// every turn costs as much as a few steps, so roads stay mostly
+    /// straight. Returns the points where the road starts, turns and ends.
+    fn route(
+        rect: Rect,
+        buildings: &[Building],
+        start: CoordPair,
+        end: CoordPair,
+    ) -> Option<Vec<CoordPair>> {
+        let index = |point: CoordPair, direction: Direction| {
+            let offset = (point - rect.top_left).map(usize::from);
+            let tile = offset.y * usize::from(rect.size.x) + offset.x;
+            tile * Direction::COUNT + direction as usize
+        };
+        let heuristic = |point: CoordPair| u32::from(distance(point, end));
+
+        let state_count =
+            rect.map(usize::from).total_area::<usize>() * Direction::COUNT;
+        let mut costs = vec![u32::MAX; state_count];
+        let mut previous = vec![None; state_count];
+        let mut frontier = BinaryHeap::new();
+        for direction in Direction::ALL {
+            costs[index(start, direction)] = 0;
+            frontier.push(Reverse((heuristic(start), 0, start, direction)));
+        }
+
+        while let Some(Reverse((_, cost, point, direction))) = frontier.pop() {
+            if costs[index(point, direction)] < cost {
+                continue;
+            }
+            if point == end {
+                let mut path = vec![point];
+                let mut state = (point, direction);
+                while let Some(prior) = previous[index(state.0, state.1)] {
+                    let (prior_point, prior_direction) = prior;
+                    if prior_direction != state.1 || prior_point == start {
+                        path.push(prior_point);
+                    }
+                    state = prior;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for next_direction in Direction::ALL {
+                let Ok(neighbor) =
+                    rect.checked_move_point_unit(point, next_direction)
+                else {
+                    continue;
+                };
+                if buildings
+                    .iter()
+                    .any(|building| building.rect.contains_point(neighbor))
+                {
+                    continue;
+                }
+                let turn_cost = if next_direction == direction {
+                    0
+                } else {
+                    Self::TURN_COST
+                };
+                let next_cost = cost + 1 + turn_cost;
+                let next_index = index(neighbor, next_direction);
+                if costs[next_index] <= next_cost {
+                    continue;
+                }
+                costs[next_index] = next_cost;
+                previous[next_index] = Some((point, direction));
+                frontier.push(Reverse((
+                    next_cost + heuristic(neighbor),
+                    next_cost,
+                    neighbor,
+                    next_direction,
+                )));
+            }
+        }
+        None
+    }
+
+    pub fn finish(
+        &self,
+        seed: u64,
+        map_rect: Rect,
+        climate: BiomeLayerDistr,
+    ) -> Sites {
+        Sites {
+            config: self.clone(),
+            seed: derive_seed(seed, Self::SEED_STREAM),
+            map_rect,
+            climate,
+        }
+    }
+}
+
+/// Points from one point to another aligned with it, both included.
+fn line(start: CoordPair, end: CoordPair) -> impl Iterator<Item = CoordPair> {
+    let min = start.zip2_with(end, Coord::min);
+    let max = start.zip2_with(end, Coord::max);
+    (min.y ..= max.y)
+        .flat_map(move |y| (min.x ..= max.x).map(move |x| CoordPair { y, x }))
+}
+
+/// A small walled building with a single door.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Building {
+    rect: Rect,
+    door: CoordPair,
+    entrance: CoordPair,
+}
+
+impl Building {
+    fn new<R>(rect: Rect, side: Direction, rng: &mut R) -> Self
+    where
+        R: Rng + ?Sized,
+    {
+        let last = rect.bottom_right().map(|coord: Coord| coord - 1);
+        let along = CoordPair::from_axes(|axis| {
+            rng.random_range(rect.top_left[axis] + 1 .. last[axis])
+        });
+        let door = match side {
+            Direction::Up => CoordPair { y: rect.top_left.y, x: along.x },
+            Direction::Down => CoordPair { y: last.y, x: along.x },
+            Direction::Left => CoordPair { y: along.y, x: rect.top_left.x },
+            Direction::Right => CoordPair { y: along.y, x: last.x },
+        };
+        Self { rect, door, entrance: door.move_unit(side) }
+    }
+
+    /// Including the walls.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    pub fn door(&self) -> CoordPair {
+        self.door
+    }
+
+    /// Tile right outside of the door, where roads arrive.
+    pub fn entrance(&self) -> CoordPair {
+        self.entrance
+    }
+
+    pub fn contains_floor(&self, point: CoordPair) -> bool {
+        is_interior(self.rect, point)
+    }
+}
+
+/// Buildings connected by roads, built into a rectangle of the map.
+#[derive(Debug, Clone)]
+pub struct Settlement {
+    rect: Rect,
+    buildings: Vec<Building>,
+    roads: CoordGraph<Coord>,
+    road_ground: Ground,
+    road_tiles: HashSet<CoordPair>,
+    blocks: HashMap<CoordPair, PlaceableBlock>,
+}
+
+impl Settlement {
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    pub fn center(&self) -> CoordPair {
+        center(self.rect)
+    }
+
+    pub fn buildings(&self) -> &[Building] {
+        &self.buildings
+    }
+
+    /// Roads run straight between connected nodes: building entrances and
+    /// road corners.
+    pub fn roads(&self) -> &CoordGraph<Coord> {
+        &self.roads
+    }
+
+    /// Block built at the given point, if the settlement changes it at all.
+    pub fn block(&self, point: CoordPair) -> Option<PlaceableBlock> {
+        self.blocks.get(&point).copied()
+    }
+
+    pub fn blocks(&self) -> impl Iterator<Item = (CoordPair, PlaceableBlock)> {
+        self.blocks.iter().map(|(point, block)| (*point, *block))
+    }
+
+    /// Ground paved at the given point, if the settlement changes it at all.
+    pub fn ground(&self, point: CoordPair) -> Option<Ground> {
+        self.road_tiles.contains(&point).then_some(self.road_ground)
+    }
+
+    pub fn grounds(&self) -> impl Iterator<Item = (CoordPair, Ground)> {
+        self.road_tiles.iter().map(|point| (*point, self.road_ground))
+    }
+
+    fn paint(&mut self) {
+        for (start, end, _) in segments(&self.roads) {
+            for point in line(start, end) {
+                if self
+                    .buildings
+                    .iter()
+                    .all(|building| !building.rect.contains_point(point))
+                {
+                    self.road_tiles.insert(point);
+                    self.blocks.insert(point, PlaceableBlock::Air);
+                }
+            }
+        }
+        for building in &self.buildings {
+            for point in line(
+                building.rect.top_left,
+                building.rect.bottom_right().map(|coord: Coord| coord - 1),
+            ) {
+                let block = if point == building.door {
+                    PlaceableBlock::ClosedDoor
+                } else if building.contains_floor(point) {
+                    PlaceableBlock::Air
+                } else {
+                    PlaceableBlock::Wall
+                };
+                self.blocks.insert(point, block);
+            }
+            self.blocks.insert(building.entrance, PlaceableBlock::Air);
+        }
+    }
+}
+
+/// Seeded locations of settlements in a map. Each cell of the map holds at
+/// most one settlement, more likely in some biomes than in others, derived
+/// only from the seed and the cell.
+#[derive(Debug, Clone)]
+pub struct Sites {
+    config: Config,
+    seed: u64,
+    map_rect: Rect,
+    climate: BiomeLayerDistr,
+}
+
+impl Sites {
+    fn placement(
+        &self,
+        cell: CoordPair,
+    ) -> Option<(Rect, PickedReproducibleRng)> {
+        let cell_size = self.config.cell_size;
+        let cell_center = cell
+            .checked_mul_scalar(&cell_size)?
+            .checked_add_to(&(cell_size / 2))?;
+        let biome = self.climate.biome_at(cell_center);
+        let position = cell.map(u32::from);
+        if positional_unit(self.seed, position)
+            >= self.config.densities[biome as usize]
+        {
+            return None;
+        }
+        let mut rng = create_positional_rng(self.seed, position);
+        let rect = place_in_cell(
+            cell,
+            cell_size,
+            self.config.min_size,
+            self.config.max_size,
+            self.map_rect,
+            &mut rng,
+        )?;
+        Some((rect, rng))
+    }
+
+    pub fn settlement_at(&self, cell: CoordPair) -> Option<Settlement> {
+        let (rect, mut rng) = self.placement(cell)?;
+        Some(self.config.build(rect, &mut rng))
+    }
+
+    /// Settlements of the cells overlapping the given rectangle.
+    pub fn settlements_in(
+        &self,
+        rect: Rect,
+    ) -> impl Iterator<Item = Settlement> + '_ {
+        cells_in(rect, self.config.cell_size)
+            .filter_map(|cell| self.settlement_at(cell))
+    }
+
+    /// Same as [`Sites::settlements_in`], but without building the
+    /// settlements, which is much cheaper.
+    pub fn rects_in(&self, rect: Rect) -> impl Iterator<Item = Rect> + '_ {
+        cells_in(rect, self.config.cell_size)
+            .filter_map(|cell| Some(self.placement(cell)?.0))
+    }
+}
@@ -0,0 +1,107 @@
+use thedes_domain::{
+    block::PlaceableBlock,
+    geometry::{CoordPair, Rect},
+    matter::Biome,
+};
+
+use crate::{
+    map::layer::matter::ClimateConfig,
+    structure::{overlaps, test::reachable},
+};
+
+use super::{Config, Sites};
+
+const SEED: u64 = 0xc0ffee;
+
+fn map_rect() -> Rect {
+    Rect {
+        top_left: CoordPair { y: 0, x: 0 },
+        size: CoordPair { y: 1200, x: 1200 },
+    }
+}
+
+fn sites(seed: u64) -> Sites {
+    let mut config = Config::new().with_cell_size(64).unwrap();
+    for biome in Biome::ALL {
+        config = config.with_density(biome, 1.0).unwrap();
+    }
+    config.finish(seed, map_rect(), ClimateConfig::new().finish(seed))
+}
+
+#[test]
+fn entrances_are_connected_by_roads() {
+    let mut count = 0;
+    for settlement in sites(SEED).settlements_in(map_rect()) {
+        let Some(first) = settlement.buildings().first() else {
+            continue;
+        };
+        let reachable = reachable(settlement.roads(), first.entrance());
+        for building in settlement.buildings() {
+            assert!(
+                reachable.contains(&building.entrance()),
+                "{:?} in {:?}",
+                building,
+                settlement.rect(),
+            );
+            assert!(settlement.ground(building.entrance()).is_some());
+        }
+        count += 1;
+    }
+    assert!(count > 100);
+}
+
+#[test]
+fn buildings_do_not_overlap() {
+    for settlement in sites(SEED).settlements_in(map_rect()) {
+        let buildings = settlement.buildings();
+        assert!(!buildings.is_empty());
+        for (i, building) in buildings.iter().enumerate() {
+            let rect = building.rect();
+            assert!(settlement.rect().contains_point(rect.top_left));
+            assert!(
+                settlement
+                    .rect()
+                    .contains_point(rect.bottom_right().map(|coord| coord - 1))
+            );
+            for other in &buildings[i + 1 ..] {
+                assert!(!overlaps(rect, other.rect()), "{rect:?} {other:?}");
+            }
+            assert!(!rect.contains_point(building.entrance()));
+            assert_eq!(
+                settlement.block(building.door()),
+                Some(PlaceableBlock::ClosedDoor),
+            );
+        }
+    }
+}
+
+#[test]
+fn settlements_are_deterministic_per_cell() {
+    let sites = sites(SEED);
+    let again = self::sites(SEED);
+    for y in 0 .. 6 {
+        for x in 0 .. 6 {
+            let cell = CoordPair { y, x };
+            let Some(settlement) = sites.settlement_at(cell) else {
+                continue;
+            };
+            let other = again.settlement_at(cell).unwrap();
+            assert_eq!(settlement.rect(), other.rect());
+            assert_eq!(settlement.buildings(), other.buildings());
+            let mut blocks: Vec<_> = settlement.blocks().collect();
+            let mut other_blocks: Vec<_> = other.blocks().collect();
+            blocks.sort_by_key(|(point, _)| *point);
+            other_blocks.sort_by_key(|(point, _)| *point);
+            assert_eq!(blocks, other_blocks);
+            let mut grounds: Vec<_> = settlement.grounds().collect();
+            let mut other_grounds: Vec<_> = other.grounds().collect();
+            grounds.sort_by_key(|(point, _)| *point);
+            other_grounds.sort_by_key(|(point, _)| *point);
+            assert_eq!(grounds, other_grounds);
+            assert_eq!(
+                sites.rects_in(settlement.rect()).collect::<Vec<_>>(),
+                [settlement.rect()],
+            );
+        }
+    }
+}
@@ -0,0 +1,541 @@
+use std::{collections::HashMap, ops::Range};
+
+use num::CheckedAdd;
+use rand::Rng;
+use thedes_domain::{
+    block::PlaceableBlock,
+    geometry::{Coord, CoordPair, Rect},
+};
+use thedes_geometry::{
+    collections::graph::CoordGraph,
+    orientation::{Axis, Direction},
+};
+use thiserror::Error;
+
+use crate::random::{create_positional_rng, derive_seed, positional_unit};
+
+#[cfg(test)]
+pub(crate) mod test;
+
+#[derive(Debug, Error)]
+pub enum InvalidConfig {
+    #[error("Rooms must be at least {min} wide, given {given}")]
+    RoomTooSmall { given: Coord, min: Coord },
+    #[error("Minimum room size {min} cannot be greater than maximum {max}")]
+    RoomSizeBoundOrder { min: Coord, max: Coord },
+    #[error(
+        "Structures of size {given} cannot fit a room of size {room} and its \
+         margin"
+    )]
+    SizeTooSmall { given: Coord, room: Coord },
+    #[error(
+        "Minimum structure size {min} cannot be greater than maximum {max}"
+    )]
+    SizeBoundOrder { min: Coord, max: Coord },
+    #[error(
+        "Structure cell size {cell_size} cannot fit structures {size} wide"
+    )]
+    CellTooSmall { cell_size: Coord, size: Coord },
+    #[error("Structure density {0} must be within 0 and 1")]
+    Density(f64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    min_room_size: Coord,
+    max_room_size: Coord,
+    min_size: Coord,
+    max_size: Coord,
+    cell_size: Coord,
+    density: f64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Config {
+    /// A room needs its walls plus at least one tile of floor.
+    const MIN_ROOM_SIZE: Coord = 3;
+
+    /// Free tiles around every room, so that corridors can pass between
+    /// rooms.
+    const ROOM_MARGIN: Coord = 1;
+
+    /// Distinct from the streams used by climate noise.
+    const SEED_STREAM: u64 = 2;
+
+    pub fn new() -> Self {
+        Self {
+            min_room_size: 5,
+            max_room_size: 11,
+            min_size: 28,
+            max_size: 56,
+            cell_size: 160,
+            density: 0.3,
+        }
+    }
+
+    /// Sizes of rooms, including their walls.
+    pub fn with_room_size(
+        self,
+        min: Coord,
+        max: Coord,
+    ) -> Result<Self, InvalidConfig> {
+        Self { min_room_size: min, max_room_size: max, ..self }.validate()
+    }
+
+    /// Sizes of whole structures, along both axes.
+    pub fn with_size(
+        self,
+        min: Coord,
+        max: Coord,
+    ) -> Result<Self, InvalidConfig> {
+        Self { min_size: min, max_size: max, ..self }.validate()
+    }
+
+    /// The overworld is split into square cells of this size, each holding
+    /// at most one structure, so that structures never overlap.
+    pub fn with_cell_size(self, size: Coord) -> Result<Self, InvalidConfig> {
+        Self { cell_size: size, ..self }.validate()
+    }
+
+    /// Probability of a cell holding a structure.
+    pub fn with_density(self, density: f64) -> Result<Self, InvalidConfig> {
+        if !(0.0 ..= 1.0).contains(&density) {
+            Err(InvalidConfig::Density(density))?
+        }
+        Ok(Self { density, ..self })
+    }
+
+    fn validate(self) -> Result<Self, InvalidConfig> {
+        if self.min_room_size < Self::MIN_ROOM_SIZE {
+            Err(InvalidConfig::RoomTooSmall {
+                given: self.min_room_size,
+                min: Self::MIN_ROOM_SIZE,
+            })?
+        }
+        if self.min_room_size > self.max_room_size {
+            Err(InvalidConfig::RoomSizeBoundOrder {
+                min: self.min_room_size,
+                max: self.max_room_size,
+            })?
+        }
+        if self.min_size < Self::leaf_size(self.min_room_size) {
+            Err(InvalidConfig::SizeTooSmall {
+                given: self.min_size,
+                room: self.min_room_size,
+            })?
+        }
+        if self.min_size > self.max_size {
+            Err(InvalidConfig::SizeBoundOrder {
+                min: self.min_size,
+                max: self.max_size,
+            })?
+        }
+        if self.cell_size < self.max_size {
+            Err(InvalidConfig::CellTooSmall {
+                cell_size: self.cell_size,
+                size: self.max_size,
+            })?
+        }
+        Ok(self)
+    }
+
+    fn leaf_size(room_size: Coord) -> Coord {
+        room_size + Self::ROOM_MARGIN * 2
+    }
+
+    /// Carves rooms connected by corridors into the given rectangle, which
+    /// must fit at least one room of minimum size and its margin.
+    pub fn carve<R>(&self, rect: Rect, rng: &mut R) -> Structure
+    where
+        R: Rng + ?Sized,
+    {
+        let mut carving = Carving {
+            config: self,
+            rng,
+            rooms: Vec::new(),
+            corridors: CoordGraph::new(),
+        };
+        carving.split(rect);
+        let entrance = carving.dig_entrance(rect);
+        let mut structure = Structure {
+            rect,
+            rooms: carving.rooms,
+            corridors: carving.corridors,
+            entrance,
+            blocks: HashMap::new(),
+        };
+        structure.paint();
+        structure
+    }
+
+    pub fn finish(&self, seed: u64, map_rect: Rect) -> Sites {
+        Sites {
+            config: self.clone(),
+            seed: derive_seed(seed, Self::SEED_STREAM),
+            map_rect,
+        }
+    }
+}
+
+/// Rooms connected by corridors, carved into a rectangle of the map.
+#[derive(Debug, Clone)]
+pub struct Structure {
+    rect: Rect,
+    rooms: Vec<Rect>,
+    corridors: CoordGraph<Coord>,
+    entrance: CoordPair,
+    blocks: HashMap<CoordPair, PlaceableBlock>,
+}
+
+impl Structure {
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Rooms, including their walls.
+    pub fn rooms(&self) -> &[Rect] {
+        &self.rooms
+    }
+
+    /// Corridors run straight between connected nodes: room centers,
+    /// corridor corners and the entrance.
+    pub fn corridors(&self) -> &CoordGraph<Coord> {
+        &self.corridors
+    }
+
+    /// Point at the border of the structure from which a corridor leads
+    /// inside.
+    pub fn entrance(&self) -> CoordPair {
+        self.entrance
+    }
+
+    /// Block carved at the given point, if the structure changes it at all.
+    pub fn block(&self, point: CoordPair) -> Option<PlaceableBlock> {
+        self.blocks.get(&point).copied()
+    }
+
+    pub fn blocks(&self) -> impl Iterator<Item = (CoordPair, PlaceableBlock)> {
+        self.blocks.iter().map(|(point, block)| (*point, *block))
+    }
+
+    fn paint(&mut self) {
+        let mut corridor_tiles = Vec::new();
+        for (start, end, direction) in segments(&self.corridors) {
+            let mut point = start;
+            loop {
+                corridor_tiles.push((point, direction));
+                if point == end {
+                    break;
+                }
+                point = point.move_unit(direction);
+            }
+        }
+
+        for &(tile, _) in &corridor_tiles {
+            for y in tile.y.saturating_sub(1) ..= tile.y.saturating_add(1) {
+                for x in tile.x.saturating_sub(1) ..= tile.x.saturating_add(1) {
+                    let point = CoordPair { y, x };
+                    if self.rect.contains_point(point) {
+                        self.blocks
+                            .entry(point)
+                            .or_insert(PlaceableBlock::Wall);
+                    }
+                }
+            }
+        }
+
+        for room in &self.rooms {
+            let bottom_right = room.bottom_right();
+            for y in room.top_left.y .. bottom_right.y {
+                for x in room.top_left.x .. bottom_right.x {
+                    let point = CoordPair { y, x };
+                    let block = if is_interior(*room, point) {
+                        PlaceableBlock::Air
+                    } else {
+                        PlaceableBlock::Wall
+                    };
+                    self.blocks.insert(point, block);
+                }
+            }
+        }
+
+        for (tile, direction) in corridor_tiles {
+            let block = if self.is_doorway(tile, direction) {
+                PlaceableBlock::ClosedDoor
+            } else {
+                PlaceableBlock::Air
+            };
+            self.blocks.insert(tile, block);
+        }
+    }
+
+    /// Corridors crossing a room's wall get a door, but corridors running
+    /// along a wall simply open it.
+    fn is_doorway(&self, tile: CoordPair, direction: Direction) -> bool {
+        self.rooms.iter().any(|room| {
+            room.contains_point(tile)
+                && !is_interior(*room, tile)
+                && [direction, -direction].into_iter().any(|direction| {
+                    tile.checked_move_unit(direction)
+                        .is_some_and(|point| is_interior(*room, point))
+                })
+        })
+    }
+}
+
+/// Straight segments between connected nodes of the graph, each given only
+/// once, from its top or left end.
+pub(crate) fn segments(
+    graph: &CoordGraph<Coord>,
+) -> impl Iterator<Item = (CoordPair, CoordPair, Direction)> + '_ {
+    graph.rows().flat_map(move |(location, node)| {
+        [Direction::Down, Direction::Right].into_iter().filter_map(
+            move |direction| {
+                if !node.connected(direction) {
+                    return None;
+                }
+                let (neighbor, _) =
+                    graph.neighbors(location, direction).next()?;
+                Some((location.copied(), neighbor.copied(), direction))
+            },
+        )
+    })
+}
+
+/// Inserts a node, keeping any path already passing through its location
+/// connected across it.
+pub(crate) fn insert_node(graph: &mut CoordGraph<Coord>, location: CoordPair) {
+    if !graph.insert_node(location) {
+        return;
+    }
+    for direction in [Direction::Up, Direction::Left] {
+        let before = graph
+            .neighbors(location.as_ref(), direction)
+            .next()
+            .filter(|(_, node)| node.connected(-direction))
+            .map(|(before, _)| before.copied());
+        let after = graph
+            .neighbors(location.as_ref(), -direction)
+            .next()
+            .map(|(after, _)| after.copied());
+        if let (Some(before), Some(after)) = (before, after) {
+            graph
+                .connect(before, after)
+                .expect("nodes are in the graph and are aligned");
+        }
+    }
+}
+
+/// Connects the points of the path in order, every point being aligned
+/// with the next one.
+pub(crate) fn connect_path(graph: &mut CoordGraph<Coord>, path: &[CoordPair]) {
+    for &location in path {
+        insert_node(graph, location);
+    }
+    for pair in path.windows(2) {
+        if pair[0] != pair[1] {
+            graph
+                .connect(pair[0], pair[1])
+                .expect("nodes were inserted and are aligned");
+        }
+    }
+}
+
+/// Picks a random square-ish rectangle inside of the cell, as long as it
+/// is also inside of the map.
+pub(crate) fn place_in_cell<R>(
+    cell: CoordPair,
+    cell_size: Coord,
+    min_size: Coord,
+    max_size: Coord,
+    map_rect: Rect,
+    rng: &mut R,
+) -> Option<Rect>
+where
+    R: Rng + ?Sized,
+{
+    let size =
+        CoordPair::from_axes(|_| rng.random_range(min_size ..= max_size));
+    let offset = size.map(|coord| rng.random_range(0 ..= cell_size - coord));
+    let top_left = cell.checked_mul_scalar(&cell_size)?.checked_add(&offset)?;
+    let rect = Rect { top_left, size };
+    let last = rect.checked_bottom_right()?.map(|coord| coord - 1);
+    if !map_rect.contains_point(top_left) || !map_rect.contains_point(last) {
+        return None;
+    }
+    Some(rect)
+}
+
+/// Cells of the given size overlapping the rectangle.
+pub(crate) fn cells_in(
+    rect: Rect,
+    cell_size: Coord,
+) -> impl Iterator<Item = CoordPair> {
+    let first = rect.top_left.map(|coord| coord / cell_size);
+    let last = rect
+        .bottom_right()
+        .map(|coord: Coord| coord.saturating_sub(1) / cell_size);
+    (first.y ..= last.y).flat_map(move |y| {
+        (first.x ..= last.x).map(move |x| CoordPair { y, x })
+    })
+}
+
+pub(crate) fn overlaps(a: Rect, b: Rect) -> bool {
+    a.top_left.zip3(a.bottom_right(), b.top_left.zip2(b.bottom_right())).all(
+        |(a_start, a_end, (b_start, b_end))| a_start < b_end && b_start < a_end,
+    )
+}
+
+pub(crate) fn center(room: Rect) -> CoordPair {
+    room.top_left + room.size.map(|coord| coord / 2)
+}
+
+pub(crate) fn distance(a: CoordPair, b: CoordPair) -> Coord {
+    let distance = a.zip2(b).map(|(a, b)| a.abs_diff(b));
+    distance.y + distance.x
+}
+
+pub(crate) fn is_interior(room: Rect, point: CoordPair) -> bool {
+    let bottom_right = room.bottom_right();
+    point.zip3(room.top_left, bottom_right).all(|(coord, start, end)| {
+        coord > start && coord.saturating_add(1) < end
+    })
+}
+
+#[derive(Debug)]
+struct Carving<'a, R>
+where
+    R: ?Sized,
+{
+    config: &'a Config,
+    rng: &'a mut R,
+    rooms: Vec<Rect>,
+    corridors: CoordGraph<Coord>,
+}
+
+impl<'a, R> Carving<'a, R>
+where
+    R: Rng + ?Sized,
+{
+    /// Splits the area in two until it is small enough for a single room,
+    /// connecting the closest rooms of both halves by a corridor. Returns
+    /// the range of rooms placed in the area.
+    fn split(&mut self, area: Rect) -> Range<usize> {
+        let min_leaf = Config::leaf_size(self.config.min_room_size);
+        let max_leaf = Config::leaf_size(self.config.max_room_size);
+        let axis = if area.size.y >= area.size.x { Axis::Y } else { Axis::X };
+        let length = area.size[axis];
+        if length < min_leaf * 2 || length <= max_leaf && self.rng.random() {
+            let start = self.rooms.len();
+            self.place_room(area);
+            return start .. self.rooms.len();
+        }
+
+        let cut = self.rng.random_range(min_leaf ..= length - min_leaf);
+        let mut first = area;
+        first.size[axis] = cut;
+        let mut second = area;
+        second.top_left[axis] += cut;
+        second.size[axis] -= cut;
+
+        let first_rooms = self.split(first);
+        let second_rooms = self.split(second);
+        let (start, end) = first_rooms
+            .clone()
+            .flat_map(|i| second_rooms.clone().map(move |j| (i, j)))
+            .map(|(i, j)| (center(self.rooms[i]), center(self.rooms[j])))
+            .min_by_key(|(start, end)| distance(*start, *end))
+            .expect("both halves have rooms");
+        self.connect(start, end);
+        first_rooms.start .. second_rooms.end
+    }
+
+    fn place_room(&mut self, leaf: Rect) {
+        let margin = Config::ROOM_MARGIN;
+        let size = leaf.size.map(|available| {
+            let max = self.config.max_room_size.min(available - margin * 2);
+            self.rng.random_range(self.config.min_room_size ..= max)
+        });
+        let top_left = leaf.top_left.zip2_with(
+            leaf.size.zip2(size),
+            |start, (available, size)| {
+                start
+                    + margin
+                    + self.rng.random_range(0 ..= available - margin * 2 - size)
+            },
+        );
+        let room = Rect { top_left, size };
+        self.rooms.push(room);
+        insert_node(&mut self.corridors, center(room));
+    }
+
+    /// Connects both points by a corridor with a single corner.
+    fn connect(&mut self, start: CoordPair, end: CoordPair) {
+        let corner = if self.rng.random() {
+            CoordPair { y: start.y, x: end.x }
+        } else {
+            CoordPair { y: end.y, x: start.x }
+        };
+        connect_path(&mut self.corridors, &[start, corner, end]);
+    }
+
+    /// Connects the first room to the nearest border of the structure.
+    fn dig_entrance(&mut self, rect: Rect) -> CoordPair {
+        let start = center(self.rooms[0]);
+        let last = rect.bottom_right().map(|coord: Coord| coord - 1);
+        let entrance = [
+            CoordPair { y: rect.top_left.y, x: start.x },
+            CoordPair { y: last.y, x: start.x },
+            CoordPair { y: start.y, x: rect.top_left.x },
+            CoordPair { y: start.y, x: last.x },
+        ]
+        .into_iter()
+        .min_by_key(|point| distance(*point, start))
+        .expect("there are four borders");
+        connect_path(&mut self.corridors, &[start, entrance]);
+        entrance
+    }
+}
+
+/// Seeded locations of structures in a map. Each cell of the map holds at
+/// most one structure, derived only from the seed and the cell, so any part
+/// of the map can be generated in any order.
+#[derive(Debug, Clone)]
+pub struct Sites {
+    config: Config,
+    seed: u64,
+    map_rect: Rect,
+}
+
+impl Sites {
+    pub fn structure_at(&self, cell: CoordPair) -> Option<Structure> {
+        let position = cell.map(u32::from);
+        if positional_unit(self.seed, position) >= self.config.density {
+            return None;
+        }
+        let mut rng = create_positional_rng(self.seed, position);
+        let rect = place_in_cell(
+            cell,
+            self.config.cell_size,
+            self.config.min_size,
+            self.config.max_size,
+            self.map_rect,
+            &mut rng,
+        )?;
+        Some(self.config.carve(rect, &mut rng))
+    }
+
+    /// Structures of the cells overlapping the given rectangle.
+    pub fn structures_in(
+        &self,
+        rect: Rect,
+    ) -> impl Iterator<Item = Structure> + '_ {
+        cells_in(rect, self.config.cell_size)
+            .filter_map(|cell| self.structure_at(cell))
+    }
+}
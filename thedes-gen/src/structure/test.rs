@@ -0,0 +1,123 @@
+use std::collections::HashSet;
+
+use rand::{SeedableRng, rngs::StdRng};
+use thedes_domain::geometry::{Coord, CoordPair, Rect};
+use thedes_geometry::{collections::graph::CoordGraph, orientation::Direction};
+
+use super::{Config, center, overlaps};
+
+/// Nodes of the graph connected to the start through its edges.
+pub(crate) fn reachable(
+    graph: &CoordGraph<Coord>,
+    start: CoordPair,
+) -> HashSet<CoordPair> {
+    let mut visited = HashSet::from([start]);
+    let mut frontier = vec![start];
+    while let Some(location) = frontier.pop() {
+        let node = graph.node(location.as_ref()).expect("node is in graph");
+        for direction in Direction::ALL {
+            if !node.connected(direction) {
+                continue;
+            }
+            let (neighbor, _) = graph
+                .neighbors(location.as_ref(), direction)
+                .next()
+                .expect("connected nodes have a neighbor");
+            if visited.insert(neighbor.copied()) {
+                frontier.push(neighbor.copied());
+            }
+        }
+    }
+    visited
+}
+
+fn map_rect() -> Rect {
+    Rect {
+        top_left: CoordPair { y: 0, x: 0 },
+        size: CoordPair { y: 1000, x: 1000 },
+    }
+}
+
+fn config() -> Config {
+    Config::new()
+        .with_room_size(4, 8)
+        .unwrap()
+        .with_size(20, 48)
+        .unwrap()
+        .with_cell_size(64)
+        .unwrap()
+        .with_density(1.0)
+        .unwrap()
+}
+
+#[test]
+fn rooms_are_reachable_from_entrance() {
+    let config = config();
+    for seed in 0 .. 50 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let rect = Rect {
+            top_left: CoordPair { y: 100, x: 200 },
+            size: CoordPair { y: 20 + seed as Coord % 29, x: 48 },
+        };
+        let structure = config.carve(rect, &mut rng);
+        let entrance = structure.entrance();
+        assert!(rect.contains_point(entrance));
+        let reachable = reachable(structure.corridors(), entrance);
+        for room in structure.rooms() {
+            assert!(reachable.contains(&center(*room)), "{room:?}");
+        }
+    }
+}
+
+#[test]
+fn sizes_are_within_bounds() {
+    let sites = config().finish(7, map_rect());
+    let mut count = 0;
+    for structure in sites.structures_in(map_rect()) {
+        let rect = structure.rect();
+        assert!(rect.size.all(|size| (20 ..= 48).contains(&size)), "{rect:?}");
+        for (i, room) in structure.rooms().iter().enumerate() {
+            assert!(room.size.all(|size| (4 ..= 8).contains(&size)));
+            assert!(rect.contains_point(room.top_left));
+            assert!(
+                rect.contains_point(room.bottom_right().map(|coord| coord - 1))
+            );
+            for other in &structure.rooms()[i + 1 ..] {
+                assert!(!overlaps(*room, *other), "{room:?} and {other:?}");
+            }
+        }
+        count += 1;
+    }
+    assert!(count > 100);
+}
+
+#[test]
+fn structures_do_not_overlap_across_cells() {
+    let sites = config().finish(11, map_rect());
+    let structures: Vec<_> = sites.structures_in(map_rect()).collect();
+    for (i, structure) in structures.iter().enumerate() {
+        assert!(map_rect().contains_point(structure.rect().top_left));
+        for other in &structures[i + 1 ..] {
+            assert!(!overlaps(structure.rect(), other.rect()));
+        }
+    }
+}
+
+#[test]
+fn structures_are_deterministic_per_cell() {
+    let sites = config().finish(3, map_rect());
+    for y in 0 .. 4 {
+        for x in 0 .. 4 {
+            let cell = CoordPair { y, x };
+            let first = sites.structure_at(cell).unwrap();
+            let second = sites.structure_at(cell).unwrap();
+            assert_eq!(first.rect(), second.rect());
+            assert_eq!(first.rooms(), second.rooms());
+            let mut blocks: Vec<_> = first.blocks().collect();
+            let mut other_blocks: Vec<_> = second.blocks().collect();
+            blocks.sort_by_key(|(point, _)| *point);
+            other_blocks.sort_by_key(|(point, _)| *point);
+            assert_eq!(blocks, other_blocks);
+        }
+    }
+}
@@ -3,9 +3,12 @@ use thedes_domain::{
     block::{Block, PlaceableBlock, SpecialBlock},
     game::Game,
     geometry::{Coord, CoordPair, Rect},
+    item::ItemKind,
     map,
     matter::Ground,
-    monster,
+    monster::{self, Species},
+    time::DayPhase,
+    weather::Weather,
 };
 use thedes_geometry::orientation::Direction;
 use thedes_tui::{
@@ -13,11 +16,18 @@ use thedes_tui::{
         App,
         color::{
             BasicColor,
+            Brightness,
+            Color,
             Rgb,
-            mutation::{MutateBg, MutateFg},
+            mutation::{
+                ColorMutationError,
+                MutateBg,
+                MutateBrightness,
+                MutateFg,
+            },
         },
         grapheme,
-        mutation::{MutationExt, Set},
+        mutation::{Mutation, MutationExt, MutationFn, Set},
         screen,
         tile::{MutateColors, MutateGrapheme},
     },
@@ -59,6 +69,12 @@ pub enum Error {
     ),
     #[error("Insufficient view for the camera")]
     InsufficientView(#[from] InsufficientView),
+    #[error("Failed to dim remembered tile")]
+    Dim(
+        #[from]
+        #[source]
+        ColorMutationError,
+    ),
 }
 
 #[derive(Debug, Error)]
@@ -133,6 +149,11 @@ pub(crate) struct Camera {
 }
 
 impl Camera {
+    const WEATHER_FRAME_EPOCHS: u64 = 400;
+
+    const REMEMBERED_BRIGHTNESS: Brightness =
+        Brightness::new(Brightness::MAX.level() / 3);
+
     fn new(config: Config) -> Self {
         Self {
             view: Rect {
@@ -175,6 +196,9 @@ impl Camera {
         let pos_string = format!("↱{}", game.player().position().head());
         text::styled(app, &pos_string, &text::Style::default())?;
 
+        let daylight = Self::daylight(game.time().phase());
+        let animation = game.time().epoch() / Self::WEATHER_FRAME_EPOCHS;
+
         for y in self.view.top_left.y .. self.view.bottom_right().y {
             for x in self.view.top_left.x .. self.view.bottom_right().x {
                 let player_pos = game.player().position();
@@ -182,16 +206,28 @@ impl Camera {
                 let canvas_point =
                     point - self.view.top_left + dynamic_style.margin_top_left;
 
+                if !game.map().is_loaded(point) {
+                    continue;
+                }
+                let visible = game.is_visible(point);
+                if !visible && !game.map().is_explored(point)? {
+                    continue;
+                }
+
                 let ground = game.map().get_ground(point)?;
-                let bg_color = match ground {
+                let mut bg_color = match ground {
                     Ground::Grass => Rgb::new(0x00, 0xff, 0x80).into(),
                     Ground::Sand => Rgb::new(0xff, 0xff, 0x80).into(),
                     Ground::Stone => Rgb::new(0xc0, 0xc0, 0xc0).into(),
                 };
 
-                let block = game.map().get_block(point)?;
+                let block = if visible {
+                    game.map().get_block(point)?
+                } else {
+                    game.map().get_covered_block(point)?.into()
+                };
 
-                let fg_color = BasicColor::Black.into();
+                let mut fg_color = BasicColor::Black.into();
                 let char = match block {
                     Block::Special(SpecialBlock::Player) => {
                         if player_pos.head() == point {
@@ -205,18 +241,108 @@ impl Camera {
                             }
                         }
                     },
+                    Block::Special(SpecialBlock::Npc(_)) => {
+                        fg_color = Rgb::new(0x00, 0x40, 0x80).into();
+                        '☺'
+                    },
                     Block::Special(SpecialBlock::Monster(id)) => {
-                        let monster_pos =
-                            game.monster_registry().get_by_id(id)?.position();
-                        match monster_pos.facing() {
-                            Direction::Up => 'ɷ',
-                            Direction::Down => 'ო',
-                            Direction::Left => 'ɞ',
-                            Direction::Right => 'ʚ',
+                        let monster = game.monster_registry().get_by_id(id)?;
+                        match monster.species() {
+                            Species::Bat => {
+                                fg_color = Rgb::new(0x40, 0x00, 0x60).into();
+                                match monster.position().facing() {
+                                    Direction::Up => 'ɷ',
+                                    Direction::Down => 'ო',
+                                    Direction::Left => 'ɞ',
+                                    Direction::Right => 'ʚ',
+                                }
+                            },
+                            Species::Wolf => {
+                                fg_color = Rgb::new(0x40, 0x40, 0x50).into();
+                                'w'
+                            },
+                            Species::Scorpion => {
+                                fg_color = Rgb::new(0x90, 0x20, 0x00).into();
+                                'ж'
+                            },
+                            Species::Snake => {
+                                fg_color = Rgb::new(0x30, 0x70, 0x00).into();
+                                '§'
+                            },
+                            Species::Ghoul => {
+                                fg_color = Rgb::new(0x60, 0x00, 0x00).into();
+                                'G'
+                            },
                         }
                     },
                     Block::Placeable(PlaceableBlock::Air) => ' ',
+                    Block::Placeable(PlaceableBlock::Wall) => {
+                        bg_color = Rgb::new(0x60, 0x50, 0x40).into();
+                        fg_color = Rgb::new(0x30, 0x28, 0x20).into();
+                        '#'
+                    },
+                    Block::Placeable(PlaceableBlock::Tree) => {
+                        fg_color = Rgb::new(0x00, 0x60, 0x00).into();
+                        '♣'
+                    },
+                    Block::Placeable(PlaceableBlock::Rock) => {
+                        fg_color = Rgb::new(0x50, 0x50, 0x50).into();
+                        '●'
+                    },
+                    Block::Placeable(PlaceableBlock::Water) => {
+                        bg_color = Rgb::new(0x20, 0x60, 0xff).into();
+                        fg_color = Rgb::new(0xa0, 0xd0, 0xff).into();
+                        '≈'
+                    },
+                    Block::Placeable(PlaceableBlock::ClosedDoor) => {
+                        fg_color = Rgb::new(0x80, 0x40, 0x00).into();
+                        '+'
+                    },
+                    Block::Placeable(PlaceableBlock::OpenDoor) => {
+                        fg_color = Rgb::new(0x80, 0x40, 0x00).into();
+                        '\''
+                    },
+                    Block::Placeable(PlaceableBlock::StairsDown) => {
+                        fg_color = Rgb::new(0x20, 0x20, 0x20).into();
+                        '▼'
+                    },
+                    Block::Placeable(PlaceableBlock::StairsUp) => {
+                        fg_color = Rgb::new(0x20, 0x20, 0x20).into();
+                        '▲'
+                    },
                 };
+                // Items lie under entities, so they only show on free tiles.
+                let char = if visible
+                    && char == ' '
+                    && let Some(stack) = game.map().ground_items(point)?.first()
+                {
+                    let (glyph, color) = Self::item_glyph(stack.kind());
+                    fg_color = color.into();
+                    glyph
+                } else {
+                    char
+                };
+                let char = if visible && char == ' ' {
+                    let weather = game.weather_at(point)?;
+                    match Self::weather_overlay(weather, point, animation) {
+                        Some((overlay, color)) => {
+                            fg_color = color.into();
+                            overlay
+                        },
+                        None => char,
+                    }
+                } else {
+                    char
+                };
+                if let Some(soft_max) = daylight {
+                    bg_color = Self::dim(bg_color, soft_max)?;
+                }
+                if !visible {
+                    bg_color =
+                        Self::dim(bg_color, Self::REMEMBERED_BRIGHTNESS)?;
+                    fg_color =
+                        Self::dim(fg_color, Self::REMEMBERED_BRIGHTNESS)?;
+                }
                 let grapheme = grapheme::Id::from(char);
 
                 let bg_mutation = MutateBg(Set(bg_color));
@@ -236,6 +362,59 @@ impl Camera {
         Ok(())
     }
 
+    fn dim(
+        color: Color,
+        soft_max: Brightness,
+    ) -> Result<Color, ColorMutationError> {
+        MutateBrightness(MutationFn(move |brightness: Brightness| {
+            brightness.compress(soft_max)
+        }))
+        .mutate(color)
+    }
+
+    fn item_glyph(kind: ItemKind) -> (char, Rgb) {
+        match kind {
+            ItemKind::Wood => ('≡', Rgb::new(0x80, 0x50, 0x20)),
+            ItemKind::Stone => ('•', Rgb::new(0x50, 0x50, 0x50)),
+            ItemKind::Berry => ('°', Rgb::new(0xc0, 0x00, 0x30)),
+            ItemKind::HealingPotion => ('¡', Rgb::new(0xd0, 0x00, 0xd0)),
+            ItemKind::WoodenSword => ('/', Rgb::new(0x80, 0x50, 0x20)),
+            ItemKind::StoneSword => ('/', Rgb::new(0x50, 0x50, 0x50)),
+        }
+    }
+
+    fn weather_overlay(
+        weather: Weather,
+        point: CoordPair,
+        animation: u64,
+    ) -> Option<(char, Rgb)> {
+        let y = u64::from(point.y);
+        let x = u64::from(point.x);
+        match weather {
+            Weather::Clear => None,
+            Weather::Rain => {
+                (y.wrapping_sub(animation).wrapping_add(x * 7) % 11 == 0)
+                    .then(|| ('╱', Rgb::new(0x30, 0x50, 0xff)))
+            },
+            Weather::Sandstorm => {
+                (x.wrapping_sub(animation * 2).wrapping_add(y * 5) % 7 == 0)
+                    .then(|| ('∙', Rgb::new(0xa0, 0x70, 0x30)))
+            },
+        }
+    }
+
+    fn daylight(phase: DayPhase) -> Option<Brightness> {
+        match phase {
+            DayPhase::Day => None,
+            DayPhase::Dawn | DayPhase::Dusk => {
+                Some(Brightness::new(Brightness::MAX.level() / 4 * 3))
+            },
+            DayPhase::Night => {
+                Some(Brightness::new(Brightness::MAX.level() / 2))
+            },
+        }
+    }
+
     fn border(&self) -> CoordPair {
         self.feasible_min_freedom().zip2_with(
             self.view.size,
@@ -1,13 +1,45 @@
+use std::path::Path;
+
 use camera::Camera;
 use num::rational::Ratio;
 use rand::{SeedableRng, distr::Distribution, rngs::StdRng};
 use thedes_dev::CommandContext;
 use thedes_domain::{
+    block::PlaceableBlock,
+    chunk::{ChunkIndex, ChunkStore, ChunkStoreError},
+    craft::{Recipe, RecipeBook},
+    dialogue::DialogueBook,
+    effect::EffectKind,
     event,
-    game::{Game, MovePlayerError},
+    game::{
+        BuildError as GameBuildError,
+        CloseDoorError as GameCloseDoorError,
+        CraftError as GameCraftError,
+        DigError as GameDigError,
+        Game,
+        GatherError as GameGatherError,
+        LoadChunkError,
+        MovePlayerError,
+        PickUpGroundError,
+        PlayerAttackError,
+        RespawnError as GameRespawnError,
+        SaveError as GameSaveError,
+        TalkError as GameTalkError,
+        TrackQuestsError,
+        UnloadChunkError,
+    },
+    map,
+    npc,
     stat::StatValue,
+    time::DayPhase,
+};
+use thedes_gen::{
+    chunk as gen_chunk,
+    event::{self as gen_event},
+    loot as gen_loot,
+    npc as gen_npc,
+    settlement as gen_settlement,
 };
-use thedes_gen::event::{self as gen_event};
 use thedes_geometry::orientation::Direction;
 use thedes_tui::{
     core::{
@@ -32,10 +64,30 @@ pub enum RenderError {
         #[source]
         camera::Error,
     ),
+    #[error("Failed to compute field of view")]
+    Fov(
+        #[from]
+        #[source]
+        map::AccessError,
+    ),
     #[error("Failed to write HP hearts")]
     HpHearts(#[source] text::Error),
     #[error("Failed to write HP text")]
     HpText(#[source] text::Error),
+    #[error("Failed to write hunger bar")]
+    HungerBar(#[source] text::Error),
+    #[error("Failed to write stamina bar")]
+    StaminaBar(#[source] text::Error),
+    #[error("Failed to write time of day")]
+    TimeOfDay(#[source] text::Error),
+    #[error("Failed to write status effects")]
+    Effects(#[source] text::Error),
+    #[error("Failed to write building material")]
+    Material(#[source] text::Error),
+    #[error("Failed to write player level")]
+    Level(#[source] text::Error),
+    #[error("Failed to write XP bar")]
+    XpBar(#[source] text::Error),
 }
 
 #[derive(Debug, Error)]
@@ -52,6 +104,58 @@ pub enum EventError {
         #[source]
         event::ApplyError,
     ),
+    #[error("Failed to track quests")]
+    Quests(
+        #[from]
+        #[source]
+        TrackQuestsError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum DropLootError {
+    #[error("Failed to put loot on the ground")]
+    MapAccess(
+        #[from]
+        #[source]
+        map::AccessError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum StreamChunksError {
+    #[error("Player is outside of the map")]
+    PlayerOutside(#[source] map::InvalidPoint),
+    #[error("Failed to access chunk store")]
+    Store(
+        #[from]
+        #[source]
+        ChunkStoreError,
+    ),
+    #[error("Failed to generate chunk")]
+    Generate(
+        #[from]
+        #[source]
+        gen_chunk::Error,
+    ),
+    #[error("Failed to load chunk")]
+    Load(
+        #[from]
+        #[source]
+        LoadChunkError,
+    ),
+    #[error("Failed to unload chunk")]
+    Unload(
+        #[from]
+        #[source]
+        UnloadChunkError,
+    ),
+    #[error("Failed to spawn NPCs of settlement")]
+    SpawnNpcs(
+        #[from]
+        #[source]
+        gen_npc::Error,
+    ),
 }
 
 #[derive(Debug, Error)]
@@ -74,12 +178,132 @@ pub enum QuickStepError {
     ),
 }
 
+#[derive(Debug, Error)]
+pub enum AttackError {
+    #[error("Failed to make player attack")]
+    PlayerAttack(
+        #[from]
+        #[source]
+        PlayerAttackError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum PickUpError {
+    #[error("Failed to pick up items under the player")]
+    PickUpGround(
+        #[from]
+        #[source]
+        PickUpGroundError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum CraftError {
+    #[error("Failed to craft item")]
+    Craft(
+        #[from]
+        #[source]
+        GameCraftError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum GatherError {
+    #[error("Failed to gather material in front of the player")]
+    Gather(
+        #[from]
+        #[source]
+        GameGatherError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum DigError {
+    #[error("Failed to dig block in front of the player")]
+    Dig(
+        #[from]
+        #[source]
+        GameDigError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum CloseDoorError {
+    #[error("Failed to close door in front of the player")]
+    CloseDoor(
+        #[from]
+        #[source]
+        GameCloseDoorError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum BuildError {
+    #[error("Failed to build block in front of the player")]
+    Build(
+        #[from]
+        #[source]
+        GameBuildError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum TalkError {
+    #[error("Failed to talk to NPC in front of the player")]
+    Talk(
+        #[from]
+        #[source]
+        GameTalkError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum RespawnError {
+    #[error("Failed to respawn player")]
+    Respawn(
+        #[from]
+        #[source]
+        GameRespawnError,
+    ),
+    #[error("Failed to stream chunks around spawn point")]
+    StreamChunks(
+        #[from]
+        #[source]
+        StreamChunksError,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum SaveError {
+    #[error("Failed to save game")]
+    Game(
+        #[from]
+        #[source]
+        GameSaveError,
+    ),
+    #[error("Failed to commit chunks unloaded since last save")]
+    Chunks(
+        #[from]
+        #[source]
+        ChunkStoreError,
+    ),
+}
+
 #[derive(Debug, Clone)]
 pub struct Config {
     camera: camera::Config,
     event_interval: Ratio<u64>,
     event_tick_size: u64,
+    turn_ticks: u64,
     event_distr_config: gen_event::DistrConfig,
+    loot_config: gen_loot::Config,
+    chunk_gen_config: gen_chunk::Config,
+    chunk_stream_radius: Coord,
+    chunk_store: Option<ChunkStore>,
+    villager_config: gen_npc::Config,
+    recipes: RecipeBook,
+    dialogues: DialogueBook,
 }
 
 impl Default for Config {
@@ -94,7 +318,15 @@ impl Config {
             camera: camera::Config::new(),
             event_interval: Ratio::new(4, 100),
             event_tick_size: 2,
+            turn_ticks: 8,
             event_distr_config: gen_event::DistrConfig::new(),
+            loot_config: gen_loot::Config::new(),
+            chunk_gen_config: gen_chunk::Config::new(),
+            chunk_stream_radius: 4,
+            chunk_store: None,
+            villager_config: gen_npc::Config::new_villagers(),
+            recipes: RecipeBook::new(),
+            dialogues: DialogueBook::new(),
         }
     }
 
@@ -110,10 +342,45 @@ impl Config {
         Self { event_tick_size: size, ..self }
     }
 
+    pub fn with_turn_ticks(self, ticks: u64) -> Self {
+        Self { turn_ticks: ticks, ..self }
+    }
+
     pub fn with_event_distr(self, config: gen_event::DistrConfig) -> Self {
         Self { event_distr_config: config, ..self }
     }
 
+    pub fn with_loot(self, config: gen_loot::Config) -> Self {
+        Self { loot_config: config, ..self }
+    }
+
+    pub fn with_chunk_gen(self, config: gen_chunk::Config) -> Self {
+        Self { chunk_gen_config: config, ..self }
+    }
+
+    /// Radius, in chunks, around the player's chunk kept loaded.
+    pub fn with_chunk_stream_radius(self, radius: Coord) -> Self {
+        Self { chunk_stream_radius: radius, ..self }
+    }
+
+    /// Without a store, chunks are never unloaded, since they would be lost.
+    pub fn with_chunk_store(self, store: ChunkStore) -> Self {
+        Self { chunk_store: Some(store), ..self }
+    }
+
+    /// NPCs spawned in every settlement found while streaming chunks.
+    pub fn with_villagers(self, config: gen_npc::Config) -> Self {
+        Self { villager_config: config, ..self }
+    }
+
+    pub fn with_recipes(self, recipes: RecipeBook) -> Self {
+        Self { recipes, ..self }
+    }
+
+    pub fn with_dialogues(self, dialogues: DialogueBook) -> Self {
+        Self { dialogues, ..self }
+    }
+
     pub fn finish(self, game: Game) -> Session {
         Session {
             rng: StdRng::from_os_rng(),
@@ -122,7 +389,15 @@ impl Config {
             event_interval: self.event_interval,
             event_ticks: Ratio::ZERO,
             event_tick_size: self.event_tick_size,
+            turn_ticks: self.turn_ticks,
             event_distr_config: self.event_distr_config,
+            loot_config: self.loot_config,
+            chunk_gen_config: self.chunk_gen_config,
+            chunk_stream_radius: self.chunk_stream_radius,
+            chunk_store: self.chunk_store,
+            villager_config: self.villager_config,
+            recipes: self.recipes,
+            dialogues: self.dialogues,
         }
     }
 }
@@ -135,7 +410,15 @@ pub struct Session {
     event_interval: Ratio<u64>,
     event_ticks: Ratio<u64>,
     event_tick_size: u64,
+    turn_ticks: u64,
     event_distr_config: gen_event::DistrConfig,
+    loot_config: gen_loot::Config,
+    chunk_gen_config: gen_chunk::Config,
+    chunk_stream_radius: Coord,
+    chunk_store: Option<ChunkStore>,
+    villager_config: gen_npc::Config,
+    recipes: RecipeBook,
+    dialogues: DialogueBook,
 }
 
 impl Session {
@@ -143,8 +426,17 @@ impl Session {
     const GAME_INFO_WIDTH: Coord = Self::STAT_VALUE_WIDTH * 2 + 1;
 
     const POS_HEIGHT: Coord = 1;
+    const HP_HEIGHT: Coord = 2;
+    const HUNGER_Y: Coord = Self::POS_HEIGHT + Self::HP_HEIGHT;
+    const STAMINA_Y: Coord = Self::HUNGER_Y + 1;
+    const LEVEL_Y: Coord = Self::STAMINA_Y + 1;
+    const XP_Y: Coord = Self::LEVEL_Y + 1;
+    const TIME_Y: Coord = Self::XP_Y + 1;
+    const EFFECTS_Y: Coord = Self::TIME_Y + 1;
+    const MATERIAL_Y: Coord = Self::EFFECTS_Y + 1;
 
     pub fn render(&mut self, app: &mut App) -> Result<(), RenderError> {
+        self.game.update_fov()?;
         self.camera.render(
             app,
             &mut self.game,
@@ -154,6 +446,81 @@ impl Session {
             },
         )?;
         self.render_hp(app)?;
+        self.render_survival(app)?;
+        self.render_xp(app)?;
+        self.render_time(app)?;
+        self.render_effects(app)?;
+        self.render_material(app)?;
+        Ok(())
+    }
+
+    /// Loads chunks around the player, from the store when they were saved
+    /// before or generating them otherwise, and unloads chunks far from the
+    /// player into the store, staged until the game is saved. Settlements of
+    /// newly generated chunks are populated with villagers.
+    pub fn stream_chunks(&mut self) -> Result<(), StreamChunksError> {
+        let map_id = self.game.current_map_id();
+        let center = self
+            .game
+            .map()
+            .chunk_index(self.game.player().position().head())
+            .map_err(StreamChunksError::PlayerOutside)?;
+        let radius = self.chunk_stream_radius;
+
+        if let Some(store) = &self.chunk_store {
+            // Keeps a margin so that walking back and forth along a chunk
+            // border does not keep reloading chunks.
+            let far_chunks: Vec<ChunkIndex> = self
+                .game
+                .map()
+                .loaded_chunks()
+                .filter(|index| {
+                    index.zip2(center).any(|(a, b)| a.abs_diff(b) > radius + 1)
+                })
+                .collect();
+            for index in far_chunks {
+                if let Some(chunk) = self.game.unload_chunk(index)? {
+                    store.save(map_id, index, &chunk)?;
+                }
+            }
+        }
+
+        let grid_size = self.game.map().chunk_grid_size();
+        let start = center.map(|coord| coord.saturating_sub(radius));
+        let end = center.zip2_with(grid_size, |coord, size| {
+            coord.saturating_add(radius).min(size - 1)
+        });
+        let mut settlements = gen_settlement::Centers::new();
+        for y in start.y ..= end.y {
+            for x in start.x ..= end.x {
+                let index = CoordPair { y, x };
+                let map = self.game.map();
+                if map.is_chunk_loaded(index) {
+                    continue;
+                }
+                let stored = match &self.chunk_store {
+                    Some(store) => store.load(map_id, index)?,
+                    None => None,
+                };
+                let chunk = match stored {
+                    Some(chunk) => chunk,
+                    None if map.seed().is_some() => self
+                        .chunk_gen_config
+                        .generate_with(map, index, &mut settlements)?,
+                    None => continue,
+                };
+                self.game.load_chunk(index, chunk)?;
+            }
+        }
+        // Villagers may spawn in chunks next to their settlement's center,
+        // so they are spawned only once every chunk around it is loaded.
+        for center in settlements {
+            self.villager_config.spawn_around(
+                &mut self.game,
+                center,
+                &mut self.rng,
+            )?;
+        }
         Ok(())
     }
 
@@ -167,6 +534,28 @@ impl Session {
                 .sample(&mut self.rng);
             self.game.schedule_event(event, 0);
             self.game.execute_events()?;
+            self.game.track_quests()?;
+        }
+        Ok(())
+    }
+
+    /// Advances events by as many ticks as a single turn lasts, for
+    /// turn-based simulation.
+    pub fn advance_turn(&mut self) -> Result<(), EventError> {
+        for _ in 0 .. self.turn_ticks {
+            self.tick_event()?;
+        }
+        Ok(())
+    }
+
+    /// Drops loot of monsters killed since the last call where they died.
+    pub fn drop_loot(&mut self) -> Result<(), DropLootError> {
+        for kill in self.game.take_kills() {
+            let stacks =
+                self.loot_config.table(kill.species).sample(&mut self.rng);
+            for stack in stacks {
+                self.game.put_ground_items(kill.point, stack)?;
+            }
         }
         Ok(())
     }
@@ -183,7 +572,77 @@ impl Session {
         &mut self,
         direction: Direction,
     ) -> Result<(), QuickStepError> {
-        self.game.move_player_head(direction)?;
+        self.game.player_quick_step(direction)?;
+        Ok(())
+    }
+
+    pub fn attack(&mut self) -> Result<(), AttackError> {
+        self.game.player_attack()?;
+        Ok(())
+    }
+
+    pub fn pick_up(&mut self) -> Result<(), PickUpError> {
+        self.game.pick_up_ground_items()?;
+        Ok(())
+    }
+
+    pub fn gather(&mut self) -> Result<(), GatherError> {
+        self.game.gather()?;
+        Ok(())
+    }
+
+    pub fn dig(&mut self) -> Result<(), DigError> {
+        self.game.player_dig()?;
+        Ok(())
+    }
+
+    pub fn build(&mut self) -> Result<(), BuildError> {
+        self.game.player_build()?;
+        Ok(())
+    }
+
+    pub fn close_door(&mut self) -> Result<(), CloseDoorError> {
+        self.game.player_close_door()?;
+        Ok(())
+    }
+
+    pub fn select_next_material(&mut self) {
+        self.game.select_next_material();
+    }
+
+    pub fn recipes(&self) -> &RecipeBook {
+        &self.recipes
+    }
+
+    pub fn craft(&mut self, recipe: &Recipe) -> Result<(), CraftError> {
+        self.game.craft(recipe)?;
+        Ok(())
+    }
+
+    pub fn dialogues(&self) -> &DialogueBook {
+        &self.dialogues
+    }
+
+    /// Starts talking to the NPC in front of the player, if any.
+    pub fn talk(&mut self) -> Result<Option<npc::Id>, TalkError> {
+        Ok(self.game.talk_to_npc()?)
+    }
+
+    /// Chunks around the spawn point are streamed right away, so that the
+    /// player is back on the map before anything else happens.
+    pub fn respawn(&mut self) -> Result<(), RespawnError> {
+        self.game.respawn_player()?;
+        self.stream_chunks()?;
+        Ok(())
+    }
+
+    /// Saves the game, then commits the chunks unloaded since the last save,
+    /// so that the stored chunks never get ahead of the saved game.
+    pub async fn save(&self, path: &Path) -> Result<(), SaveError> {
+        self.game.save(path).await?;
+        if let Some(store) = &self.chunk_store {
+            store.commit()?;
+        }
         Ok(())
     }
 
@@ -236,4 +695,222 @@ impl Session {
 
         Ok(())
     }
+
+    fn render_survival(&self, app: &mut App) -> Result<(), RenderError> {
+        let player = self.game.player();
+
+        let hunger = player.hunger();
+        let hunger_bar =
+            Self::stat_bar(hunger.value(), hunger.curr_max(), '●', '○');
+        let hunger_point = CoordPair { y: Self::HUNGER_Y, x: 0 };
+        let hunger_colors = ColorPair {
+            background: BasicColor::Black.into(),
+            foreground: BasicColor::LightYellow.into(),
+        };
+        text::inline(app, hunger_point, &hunger_bar, hunger_colors)
+            .map_err(RenderError::HungerBar)?;
+
+        let stamina = player.stamina();
+        let stamina_bar =
+            Self::stat_bar(stamina.value(), stamina.curr_max(), '▮', '▯');
+        let stamina_point = CoordPair { y: Self::STAMINA_Y, x: 0 };
+        let stamina_colors = ColorPair {
+            background: BasicColor::Black.into(),
+            foreground: BasicColor::LightCyan.into(),
+        };
+        text::inline(app, stamina_point, &stamina_bar, stamina_colors)
+            .map_err(RenderError::StaminaBar)?;
+
+        Ok(())
+    }
+
+    fn stat_bar(
+        value: StatValue,
+        max: StatValue,
+        full: char,
+        empty: char,
+    ) -> String {
+        let width = u64::from(Self::GAME_INFO_WIDTH);
+        let full_count =
+            (u64::from(value) * width).div_ceil(u64::from(max.max(1)));
+        let full_count = full_count.min(width) as usize;
+        let empty_count = width as usize - full_count;
+        let mut bar = String::new();
+        bar.extend(std::iter::repeat_n(full, full_count));
+        bar.extend(std::iter::repeat_n(empty, empty_count));
+        bar
+    }
+
+    fn render_xp(&self, app: &mut App) -> Result<(), RenderError> {
+        let experience = self.game.player().experience();
+        let info_colors = ColorPair {
+            background: BasicColor::Black.into(),
+            foreground: BasicColor::White.into(),
+        };
+
+        let level = format!(
+            "Lv{:>w$}",
+            experience.level(),
+            w = usize::from(Self::GAME_INFO_WIDTH) - 2,
+        );
+        let level_point = CoordPair { y: Self::LEVEL_Y, x: 0 };
+        text::inline(app, level_point, &level, info_colors)
+            .map_err(RenderError::Level)?;
+
+        let bar = Self::stat_bar(
+            experience.points(),
+            experience.threshold(),
+            '█',
+            '░',
+        );
+        let bar_point = CoordPair { y: Self::XP_Y, x: 0 };
+        let bar_colors = ColorPair {
+            background: BasicColor::Black.into(),
+            foreground: BasicColor::LightGreen.into(),
+        };
+        text::inline(app, bar_point, &bar, bar_colors)
+            .map_err(RenderError::XpBar)?;
+
+        Ok(())
+    }
+
+    fn render_time(&self, app: &mut App) -> Result<(), RenderError> {
+        let time = self.game.time();
+        let (symbol, color) = match time.phase() {
+            DayPhase::Day => ('☼', BasicColor::LightYellow),
+            DayPhase::Dawn | DayPhase::Dusk => ('◐', BasicColor::LightRed),
+            DayPhase::Night => ('☾', BasicColor::LightBlue),
+        };
+        let text = format!("{symbol} {time}");
+        let point = CoordPair { y: Self::TIME_Y, x: 0 };
+        let colors = ColorPair {
+            background: BasicColor::Black.into(),
+            foreground: color.into(),
+        };
+        text::inline(app, point, &text, colors)
+            .map_err(RenderError::TimeOfDay)?;
+        Ok(())
+    }
+
+    fn render_effects(&self, app: &mut App) -> Result<(), RenderError> {
+        let mut point = CoordPair { y: Self::EFFECTS_Y, x: 0 };
+        for (kind, active) in self.game.player().effects().iter() {
+            let (symbol, color) = match kind {
+                EffectKind::Poison => ('☠', BasicColor::LightGreen),
+                EffectKind::Regeneration => ('✚', BasicColor::LightRed),
+                EffectKind::Slow => ('≈', BasicColor::LightBlue),
+                EffectKind::Stun => ('✱', BasicColor::LightYellow),
+            };
+            // Only stackable effects show their intensity.
+            let text = if kind.max_intensity() > 1 {
+                format!("{symbol}{}", active.intensity())
+            } else {
+                symbol.to_string()
+            };
+            let colors = ColorPair {
+                background: BasicColor::Black.into(),
+                foreground: color.into(),
+            };
+            point.x += text::inline(app, point, &text, colors)
+                .map_err(RenderError::Effects)?;
+        }
+        Ok(())
+    }
+
+    fn render_material(&self, app: &mut App) -> Result<(), RenderError> {
+        let materials = self.game.player().materials();
+        let Some(block) = materials.selected() else {
+            return Ok(());
+        };
+        let symbol = match block {
+            PlaceableBlock::Wall => '#',
+            PlaceableBlock::Tree => '♣',
+            PlaceableBlock::Rock => '●',
+            PlaceableBlock::ClosedDoor | PlaceableBlock::OpenDoor => '+',
+            PlaceableBlock::Air
+            | PlaceableBlock::Water
+            | PlaceableBlock::StairsDown
+            | PlaceableBlock::StairsUp => '?',
+        };
+        let text = format!("{symbol} {}", materials.count(block));
+        let point = CoordPair { y: Self::MATERIAL_Y, x: 0 };
+        let colors = ColorPair {
+            background: BasicColor::Black.into(),
+            foreground: BasicColor::White.into(),
+        };
+        text::inline(app, point, &text, colors)
+            .map_err(RenderError::Material)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use thedes_domain::{
+        block::PlaceableBlock,
+        game::Game,
+        geometry::{CoordPair, Rect},
+        map::Map,
+        matter::Biome,
+        player::{Player, PlayerPosition},
+    };
+    use thedes_gen::{
+        chunk as gen_chunk,
+        map::layer::matter::ClimateConfig,
+        settlement as gen_settlement,
+    };
+    use thedes_geometry::orientation::Direction;
+
+    use super::Config;
+
+    const SEED: u64 = 0x5eed;
+
+    #[test]
+    fn streamed_settlements_get_villagers() {
+        let rect = Rect {
+            top_left: CoordPair { y: 0, x: 0 },
+            size: CoordPair { y: 512, x: 512 },
+        };
+        let mut settlements = gen_settlement::Config::new();
+        for biome in Biome::ALL {
+            settlements = settlements.with_density(biome, 1.0).unwrap();
+        }
+        let center = settlements
+            .finish(SEED, rect, ClimateConfig::new().finish(SEED))
+            .settlements_in(rect)
+            .next()
+            .unwrap()
+            .center();
+        let chunk_gen =
+            gen_chunk::Config::new().with_settlements(settlements.clone());
+
+        // The player starts two chunks away from the settlement, so that the
+        // settlement is only generated when streamed.
+        let mut map = Map::new_lazy(rect, SEED).unwrap();
+        let settlement_chunk = map.chunk_index(center).unwrap();
+        let player_chunk = settlement_chunk
+            .map(|coord| if coord >= 2 { coord - 2 } else { coord + 2 });
+        let chunk = chunk_gen.generate(&map, player_chunk).unwrap();
+        map.load_chunk(player_chunk, chunk).unwrap();
+        let head = map.chunk_rect(player_chunk).unwrap().top_left
+            + CoordPair { y: 16, x: 16 };
+        let position = PlayerPosition::new(head, Direction::Up).unwrap();
+        for point in [position.head(), position.pointer()] {
+            map.set_placeable_block(point, PlaceableBlock::Air).unwrap();
+        }
+        let game =
+            Game::new(map, Player::new(position, Player::DEFAULT_HP)).unwrap();
+
+        let mut session = Config::new()
+            .with_chunk_gen(chunk_gen)
+            .with_chunk_stream_radius(2)
+            .finish(game);
+        assert_eq!(session.game().npc_registry().len(), 0);
+        session.stream_chunks().unwrap();
+
+        assert!(session.game().map().is_chunk_loaded(settlement_chunk));
+        assert!(session.game().npc_registry().iter().any(|(_, npc)| {
+            npc.home().zip2(center).all(|(a, b)| a.abs_diff(b) <= 10)
+        }));
+    }
 }